@@ -0,0 +1,100 @@
+use crate::geo;
+
+// Below this the effectiveness matrix is treated as singular, relative to
+// the size of its entries
+const SINGULAR_TOLERANCE: f64 = 1e-12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Allocator{
+    effectiveness: geo::Matrix3x3,
+    inverse: Option<geo::Matrix3x3>
+}
+
+impl Allocator{
+    pub fn new(effectiveness: geo::Matrix3x3) -> Allocator{
+        // Column j is the body moment from a unit command on actuator j
+        let scale = effectiveness.norm().powi(3);
+        let inverse = if effectiveness.det().abs() <= SINGULAR_TOLERANCE * scale{
+            None
+        } else{
+            effectiveness.inv()
+        };
+        return Allocator{effectiveness, inverse}
+    }
+
+    pub fn from_actuator_moments(moments: [geo::Vector3; 3]) -> Allocator{
+        // Moment per unit command of each actuator, one per column
+        let [m1, m2, m3] = moments;
+        return Allocator::new(geo::Matrix3x3::new(
+            m1.i, m2.i, m3.i,
+            m1.j, m2.j, m3.j,
+            m1.k, m2.k, m3.k
+        ))
+    }
+
+    pub fn is_singular(&self) -> bool{
+        // Some moment direction no combination of the actuators can make
+        return self.inverse.is_none()
+    }
+
+    pub fn allocate(&self, desired_moment_nm: geo::Vector3) -> Option<geo::Vector3>{
+        // Actuator commands that produce the desired moment, None when the
+        // actuators cannot span all three axes
+        return self.inverse.map(|inverse| inverse * desired_moment_nm)
+    }
+
+    pub fn get_moment_nm(&self, commands: geo::Vector3) -> geo::Vector3{
+        // Forward map, the moment a set of commands produces
+        return self.effectiveness * commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::almost_equal_array;
+
+    #[test]
+    fn diagonal(){
+        let allocator = Allocator::new(geo::Matrix3x3::new(
+            2.0, 0.0, 0.0,
+            0.0, 0.5, 0.0,
+            0.0, 0.0, 4.0
+        ));
+        let commands = allocator.allocate(geo::Vector3::new(1.0, -1.0, 2.0)).unwrap();
+        almost_equal_array(&commands.to_array(), &[0.5, -2.0, 0.5]);
+    }
+
+    #[test]
+    fn skewed_actuators(){
+        // Thrusters at an offset, each couples into two axes
+        let allocator = Allocator::from_actuator_moments([
+            geo::Vector3::new(1.0, 0.2, 0.0),
+            geo::Vector3::new(0.0, 1.0, -0.3),
+            geo::Vector3::new(0.1, 0.0, 1.0)
+        ]);
+        let desired_nm = geo::Vector3::new(0.3, -0.7, 1.1);
+        let commands = allocator.allocate(desired_nm).unwrap();
+        assert!((allocator.get_moment_nm(commands) - desired_nm).norm() < 1e-12);
+    }
+
+    #[test]
+    fn singular(){
+        // Two wheels on the same axis
+        let allocator = Allocator::from_actuator_moments([
+            geo::Vector3::new(1.0, 0.0, 0.0),
+            geo::Vector3::new(-2.0, 0.0, 0.0),
+            geo::Vector3::new(0.0, 0.0, 1.0)
+        ]);
+        assert!(allocator.is_singular());
+        assert_eq!(allocator.allocate(geo::Vector3::new(1.0, 0.0, 0.0)), None);
+
+        // Nearly parallel is caught too
+        let allocator = Allocator::from_actuator_moments([
+            geo::Vector3::new(1.0, 0.0, 0.0),
+            geo::Vector3::new(1.0, 1e-14, 0.0),
+            geo::Vector3::new(0.0, 0.0, 1.0)
+        ]);
+        assert!(allocator.is_singular());
+    }
+}
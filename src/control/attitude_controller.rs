@@ -0,0 +1,134 @@
+use crate::{geo, sim, control::PID};
+
+pub struct AttitudeController{
+    target: geo::Quaternion,
+    x: PID,
+    y: PID,
+    z: PID,
+    kd: f64,
+    attitude_error_rad: geo::Vector3,
+    moment_cmd_nm: geo::Vector3
+}
+
+impl AttitudeController{
+    pub fn new(gains: [f64; 3]) -> AttitudeController{
+        // Gains are [kp, ki, kd], the same for each axis. The D term acts on
+        // the measured body rate rather than the differentiated error.
+        let [kp, ki, kd] = gains;
+        let pid = PID::new(kp, ki, 0.0, 0.0);
+
+        return AttitudeController{
+            target: geo::Quaternion::identity(),
+            x: pid.clone(),
+            y: pid.clone(),
+            z: pid,
+            kd,
+            attitude_error_rad: geo::Vector3::zeros(),
+            moment_cmd_nm: geo::Vector3::zeros()
+        }
+    }
+
+    pub fn set_target(&mut self, target: geo::Quaternion){
+        self.target = target.normalized();
+    }
+
+    pub fn get_target(&self) -> geo::Quaternion{
+        return self.target
+    }
+
+    pub fn output(
+        &mut self,
+        current: geo::Quaternion,
+        body_rate_radps: geo::Vector3,
+        dt: f64
+    ) -> geo::Vector3{
+        // current is the body to inertial quaternion, returns the body
+        // moment. The error is taken on the quaternion so there is no
+        // singular attitude like with Euler angles. The inertial error is
+        // rotated into the body frame.
+        let error = current.error(self.target);
+        self.attitude_error_rad = current.conjugate().transform(error.rotation_vector());
+
+        // The PIDs see -error so their output has the sign of the error
+        let error = self.attitude_error_rad;
+        self.moment_cmd_nm = geo::Vector3::new(
+            self.x.ouput(-error.i, dt),
+            self.y.ouput(-error.j, dt),
+            self.z.ouput(-error.k, dt)
+        ) - (body_rate_radps * self.kd);
+
+        return self.moment_cmd_nm
+    }
+
+    pub fn get_attitude_error_rad(&self) -> geo::Vector3{
+        return self.attitude_error_rad
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for AttitudeController{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        runtime.add_vector3(
+            format!("{node_name}.attitude_error").as_str(), "rad", self.attitude_error_rad
+        );
+        runtime.add_vector3(
+            format!("{node_name}.moment_cmd").as_str(), "Nm", self.moment_cmd_nm
+        );
+    }
+
+    fn save_data_verbose(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        self.save_data(node_name, runtime);
+        runtime.add_quaternion(format!("{node_name}.target").as_str(), self.target);
+        self.x.save_data_verbose(format!("{node_name}.x").as_str(), runtime);
+        self.y.save_data_verbose(format!("{node_name}.y").as_str(), runtime);
+        self.z.save_data_verbose(format!("{node_name}.z").as_str(), runtime);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::RigidBody;
+    use crate::sim::Integrate;
+    use std::f64::consts::PI;
+
+    const DT: f64 = 1e-3;
+
+    #[test]
+    fn slew_through_pitch_90(){
+        // 90 deg pitch is singular for the Z-Y-X Euler angles
+        let target = geo::Quaternion::from_euler(0.3, PI / 2.0, -0.4);
+        let mut controller = AttitudeController::new([4.0, 0.0, 4.0]);
+        controller.set_target(target);
+
+        let mut body = RigidBody::identity();
+        for _ in 0..(15.0 / DT) as usize{
+            body.body_moment_nm = controller.output(body.get_quat(), body.get_body_ang_vel_radps(), DT);
+            assert!(body.body_moment_nm.is_finite());
+            body = body.rk4(DT);
+        }
+
+        assert!(body.get_quat().angle(target) < 1e-4);
+        assert!(body.get_body_ang_vel_radps().norm() < 1e-4);
+    }
+
+    #[test]
+    fn error_sign(){
+        // Small positive rotation about each body axis gives a positive error
+        let body = geo::Quaternion::from_axis_angle(geo::Vector3::new(0.0, 0.0, 1.0), PI / 2.0);
+        let mut controller = AttitudeController::new([1.0, 0.0, 0.0]);
+        for axis in [geo::Vector3::new(1.0, 0.0, 0.0), geo::Vector3::new(0.0, 1.0, 0.0), geo::Vector3::new(0.0, 0.0, 1.0)]{
+            controller.set_target(body * geo::Quaternion::from_axis_angle(axis, 1e-3));
+            let moment_nm = controller.output(body, geo::Vector3::zeros(), DT);
+            assert!((moment_nm - (axis * 1e-3)).norm() < 1e-9);
+        }
+
+        // The short way around past 180 deg
+        controller.set_target(geo::Quaternion::from_axis_angle(geo::Vector3::new(1.0, 0.0, 0.0), 1.5 * PI));
+        let moment_nm = controller.output(geo::Quaternion::identity(), geo::Vector3::zeros(), DT);
+        assert!(moment_nm.i < 0.0);
+    }
+}
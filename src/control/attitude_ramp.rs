@@ -0,0 +1,102 @@
+use crate::{geo, sim::{Runtime, Save}};
+
+pub struct AttitudeRamp{
+    start: geo::Quaternion,
+    pub target: geo::Quaternion,
+    duration_s: f64,
+    elapsed_s: f64,
+    current: geo::Quaternion
+}
+
+impl AttitudeRamp{
+    pub fn new(start: geo::Quaternion, target: geo::Quaternion, duration_s: f64) -> AttitudeRamp{
+        if duration_s < 0.0{
+            panic!("    ERROR| Ramp duration [{}] must be non-negative", duration_s)
+        }
+        let start = start.normalized();
+        return AttitudeRamp{
+            start,
+            target: target.normalized(),
+            duration_s,
+            elapsed_s: 0.0,
+            current: start
+        }
+    }
+
+    pub fn output(&mut self, dt: f64) -> geo::Quaternion{
+        // Slerp by elapsed time, held at the target once the duration is up
+        self.elapsed_s += dt;
+        self.current = if self.elapsed_s >= self.duration_s{
+            self.target
+        } else{
+            self.start.slerp(self.target, self.elapsed_s / self.duration_s)
+        };
+
+        return self.current
+    }
+
+    pub fn is_done(&self) -> bool{
+        return self.elapsed_s >= self.duration_s
+    }
+}
+
+fn euler_rad(quat: geo::Quaternion) -> geo::Vector3{
+    // Z-Y-X sequence as roll, pitch, yaw, the same as RigidBody
+    let (yaw, pitch, roll) = quat.to_euler_zyx();
+    return geo::Vector3::new(roll, pitch, yaw)
+}
+
+impl Save for AttitudeRamp{
+    fn save_data(&self, node_name: &str, runtime: &mut Runtime) where Self: Sized {
+        runtime.add_vector3(
+            format!("{node_name}.euler_cmd").as_str(), "rad", euler_rad(self.current)
+        );
+    }
+
+    fn save_data_verbose(&self, node_name: &str, runtime: &mut Runtime) where Self: Sized {
+        self.save_data(node_name, runtime);
+        runtime.add_quaternion(format!("{node_name}.quat_cmd").as_str(), self.current);
+        runtime.add_vector3(
+            format!("{node_name}.euler_target").as_str(), "rad", euler_rad(self.target)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::almost_equal_array;
+
+    #[test]
+    fn reaches_target(){
+        let start = geo::Quaternion::from_axis_angle(geo::Vector3::new(0.0, 0.0, 1.0), 0.2);
+        let target = geo::Quaternion::from_axis_angle(geo::Vector3::new(1.0, 0.0, 0.0), 0.8);
+        let mut ramp = AttitudeRamp::new(start, target, 2.0);
+
+        // Steps that sum exactly to the duration
+        let dt = 0.125;
+        let total_rad = start.angle(target);
+        for i in 1..16{
+            let quat = ramp.output(dt);
+            assert!(!ramp.is_done());
+            assert!((quat.angle(start) - (total_rad * i as f64 / 16.0)).abs() < 1e-12);
+        }
+        almost_equal_array(&ramp.output(dt).to_array(), &target.to_array());
+        assert!(ramp.is_done());
+
+        // Held after the end
+        assert_eq!(ramp.output(dt), target);
+    }
+
+    #[test]
+    fn save_euler(){
+        let target = geo::Quaternion::from_axis_angle(geo::Vector3::new(0.0, 0.0, 1.0), 0.5);
+        let mut ramp = AttitudeRamp::new(geo::Quaternion::identity(), target, 1.0);
+        let mut runtime = Runtime::new(1.0, 1e-3, "time [s]");
+        ramp.output(0.5);
+        ramp.save_data("ramp", &mut runtime);
+
+        let yaw_rad = runtime.get_value("ramp.euler_cmd.z [rad]");
+        assert!((yaw_rad - 0.25).abs() < 1e-12);
+    }
+}
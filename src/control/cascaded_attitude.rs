@@ -0,0 +1,221 @@
+use crate::{geo, sim, control::PID};
+
+pub struct CascadedAttitude{
+    attitude_x: PID,
+    attitude_y: PID,
+    attitude_z: PID,
+    rate_x: PID,
+    rate_y: PID,
+    rate_z: PID,
+    max_rate_radps: f64,
+    attitude_error_rad: geo::Vector3,
+    rate_cmd_radps: geo::Vector3,
+    moment_cmd_nm: geo::Vector3
+}
+
+impl CascadedAttitude{
+    pub fn new(
+        attitude_gains: [f64; 3],
+        rate_gains: [f64; 3],
+        max_rate_radps: f64
+    ) -> CascadedAttitude{
+        // Gains are [kp, ki, kd], the same for each axis
+        let [akp, aki, akd] = attitude_gains;
+        let [rkp, rki, rkd] = rate_gains;
+
+        let mut attitude = PID::new(akp, aki, akd, 0.0);
+        attitude.set_output_limits(-max_rate_radps.abs(), max_rate_radps.abs());
+        let rate = PID::new(rkp, rki, rkd, 0.0);
+
+        return CascadedAttitude{
+            attitude_x: attitude.clone(),
+            attitude_y: attitude.clone(),
+            attitude_z: attitude,
+            rate_x: rate.clone(),
+            rate_y: rate.clone(),
+            rate_z: rate,
+            max_rate_radps: max_rate_radps.abs(),
+            attitude_error_rad: geo::Vector3::zeros(),
+            rate_cmd_radps: geo::Vector3::zeros(),
+            moment_cmd_nm: geo::Vector3::zeros()
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        quat_err: geo::Quaternion,
+        body_rates_radps: geo::Vector3,
+        dt: f64
+    ) -> geo::Vector3{
+        // quat_err rotates the body onto the target in the body frame,
+        // q_err = q_b2i* x q_target
+        self.attitude_error_rad = quat_err.rotation_vector();
+
+        // Outer loop, the PIDs see -error so their output has the sign of
+        // the error
+        let error = self.attitude_error_rad;
+        let rate_cmd_radps = geo::Vector3::new(
+            self.attitude_x.ouput(-error.i, dt),
+            self.attitude_y.ouput(-error.j, dt),
+            self.attitude_z.ouput(-error.k, dt)
+        );
+
+        return self.update_rates(rate_cmd_radps, body_rates_radps, dt)
+    }
+
+    pub fn update_rates(
+        &mut self,
+        rate_cmd_radps: geo::Vector3,
+        body_rates_radps: geo::Vector3,
+        dt: f64
+    ) -> geo::Vector3{
+        // Inner loop only, body rates to body moments
+        self.rate_cmd_radps = rate_cmd_radps;
+
+        self.rate_x.setpoint = self.rate_cmd_radps.i;
+        self.rate_y.setpoint = self.rate_cmd_radps.j;
+        self.rate_z.setpoint = self.rate_cmd_radps.k;
+
+        self.moment_cmd_nm = geo::Vector3::new(
+            self.rate_x.ouput(body_rates_radps.i, dt),
+            self.rate_y.ouput(body_rates_radps.j, dt),
+            self.rate_z.ouput(body_rates_radps.k, dt)
+        );
+        return self.moment_cmd_nm
+    }
+
+    pub fn get_max_rate_radps(&self) -> f64{
+        return self.max_rate_radps
+    }
+
+    pub fn get_rate_cmd_radps(&self) -> geo::Vector3{
+        return self.rate_cmd_radps
+    }
+
+    fn save_commands(&self, node_name: &str, runtime: &mut sim::Runtime){
+        runtime.add_vector3(
+            format!("{node_name}.attitude_error").as_str(), "rad", self.attitude_error_rad
+        );
+        runtime.add_vector3(
+            format!("{node_name}.rate_cmd").as_str(), "rad/s", self.rate_cmd_radps
+        );
+        runtime.add_vector3(
+            format!("{node_name}.moment_cmd").as_str(), "Nm", self.moment_cmd_nm
+        );
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for CascadedAttitude{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        self.save_commands(node_name, runtime);
+
+        self.attitude_x.save_data(format!("{node_name}.attitude_x").as_str(), runtime);
+        self.attitude_y.save_data(format!("{node_name}.attitude_y").as_str(), runtime);
+        self.attitude_z.save_data(format!("{node_name}.attitude_z").as_str(), runtime);
+        self.rate_x.save_data(format!("{node_name}.rate_x").as_str(), runtime);
+        self.rate_y.save_data(format!("{node_name}.rate_y").as_str(), runtime);
+        self.rate_z.save_data(format!("{node_name}.rate_z").as_str(), runtime);
+    }
+
+    fn save_data_verbose(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        self.save_commands(node_name, runtime);
+
+        self.attitude_x.save_data_verbose(format!("{node_name}.attitude_x").as_str(), runtime);
+        self.attitude_y.save_data_verbose(format!("{node_name}.attitude_y").as_str(), runtime);
+        self.attitude_z.save_data_verbose(format!("{node_name}.attitude_z").as_str(), runtime);
+        self.rate_x.save_data_verbose(format!("{node_name}.rate_x").as_str(), runtime);
+        self.rate_y.save_data_verbose(format!("{node_name}.rate_y").as_str(), runtime);
+        self.rate_z.save_data_verbose(format!("{node_name}.rate_z").as_str(), runtime);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::RigidBody;
+    use crate::sim::{Integrate, Save};
+    use approx::assert_relative_eq;
+
+    const DT: f64 = 1e-3;
+
+    fn step_response<F>(mut controller: F) -> (f64, f64, f64)
+        where F: FnMut(geo::Quaternion, geo::Vector3) -> geo::Vector3
+    {
+        // 30 deg step about x, returns (final error, max rate, overshoot)
+        let step_rad = 30.0_f64.to_radians();
+        let target = geo::Quaternion::from_axis_angle(geo::Vector3::new(1.0, 0.0, 0.0), step_rad);
+        let mut body = RigidBody::identity();
+
+        let mut max_rate_radps: f64 = 0.0;
+        let mut max_angle_rad: f64 = 0.0;
+        for _ in 0..(15.0 / DT) as usize{
+            let quat_err = body.get_quat().conjugate() * target;
+            body.body_moment_nm = controller(quat_err, body.get_body_ang_vel_radps());
+            body = body.rk4(DT);
+
+            max_rate_radps = max_rate_radps.max(body.get_body_ang_vel_radps().norm());
+            max_angle_rad = max_angle_rad.max(body.get_quat().rotation_vector().i);
+        }
+
+        let final_error_rad = (body.get_quat().conjugate() * target).rotation_vector().norm();
+        return (final_error_rad, max_rate_radps, max_angle_rad - step_rad)
+    }
+
+    #[test]
+    fn attitude_step(){
+        let mut cascaded = CascadedAttitude::new([2.0, 0.0, 0.0], [10.0, 0.0, 0.0], 0.2);
+        let (final_error_rad, max_rate_radps, overshoot_rad) = step_response(
+            |quat_err, rates| cascaded.update(quat_err, rates, DT)
+        );
+
+        assert!(final_error_rad < 1e-3);
+        assert!(max_rate_radps <= cascaded.get_max_rate_radps() + 1e-9);
+
+        // Single loop, attitude error straight to moment
+        let mut single = PID::new(2.0, 0.0, 0.0, 0.0);
+        let (_, _, single_overshoot_rad) = step_response(
+            |quat_err, _| geo::Vector3::new(single.ouput(-quat_err.rotation_vector().i, DT), 0.0, 0.0)
+        );
+
+        assert!(overshoot_rad < single_overshoot_rad);
+        assert!(overshoot_rad < 1e-3);
+    }
+
+    #[test]
+    fn rate_step(){
+        let mut cascaded = CascadedAttitude::new([2.0, 0.0, 0.0], [10.0, 0.0, 0.0], 0.2);
+        let mut body = RigidBody::identity();
+        let rate_cmd_radps = geo::Vector3::new(0.1, -0.2, 0.3);
+
+        for _ in 0..(2.0 / DT) as usize{
+            body.body_moment_nm = cascaded.update_rates(
+                rate_cmd_radps,
+                body.get_body_ang_vel_radps(),
+                DT
+            );
+            body = body.rk4(DT);
+        }
+
+        let rates = body.get_body_ang_vel_radps();
+        assert_relative_eq!(rates.i, rate_cmd_radps.i, max_relative = 1e-3);
+        assert_relative_eq!(rates.j, rate_cmd_radps.j, max_relative = 1e-3);
+        assert_relative_eq!(rates.k, rate_cmd_radps.k, max_relative = 1e-3);
+    }
+
+    #[test]
+    fn namespaced_channels(){
+        let mut runtime = sim::Runtime::new(1.0, 0.1, "time [s]");
+        let cascaded = CascadedAttitude::new([2.0, 0.0, 0.0], [10.0, 0.0, 0.0], 0.2);
+        cascaded.save_data("att", &mut runtime);
+
+        let keys = runtime.list_keys();
+        for pid in ["attitude_x", "attitude_y", "attitude_z", "rate_x", "rate_y", "rate_z"]{
+            assert!(keys.contains(&format!("att.{pid}.output [-]")));
+        }
+        assert!(keys.contains(&"att.rate_cmd.x [rad/s]".to_string()));
+    }
+}
@@ -1,3 +1,17 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClampedValue{
+    pub value: f64,
+    pub saturated_high: bool,
+    pub saturated_low: bool
+}
+
+impl ClampedValue{
+    pub fn is_saturated(&self) -> bool{
+        return self.saturated_high || self.saturated_low
+    }
+}
+
+#[deprecated(note = "use clamp_ex, which also reports saturation")]
 pub fn clamp<T>(value: T, max: T, min: T) -> T where T: std::cmp::PartialOrd{
     if value >= max{
         return max
@@ -8,4 +22,39 @@ pub fn clamp<T>(value: T, max: T, min: T) -> T where T: std::cmp::PartialOrd{
     else {
         return value
     }
-}
\ No newline at end of file
+}
+
+pub fn clamp_ex(value: f64, max: f64, min: f64) -> ClampedValue{
+    // Values exactly on a limit are not saturated
+    if value > max{
+        return ClampedValue{value: max, saturated_high: true, saturated_low: false}
+    }
+    else if value < min{
+        return ClampedValue{value: min, saturated_high: false, saturated_low: true}
+    }
+    else {
+        return ClampedValue{value, saturated_high: false, saturated_low: false}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_ex_flags(){
+        assert_eq!(
+            clamp_ex(2.0, 1.0, -1.0),
+            ClampedValue{value: 1.0, saturated_high: true, saturated_low: false}
+        );
+        assert_eq!(
+            clamp_ex(-2.0, 1.0, -1.0),
+            ClampedValue{value: -1.0, saturated_high: false, saturated_low: true}
+        );
+
+        let clamped = clamp_ex(1.0, 1.0, -1.0);
+        assert_eq!(clamped.value, 1.0);
+        assert!(!clamped.is_saturated());
+        assert!(!clamp_ex(0.5, 1.0, -1.0).is_saturated());
+    }
+}
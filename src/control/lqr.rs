@@ -0,0 +1,358 @@
+use crate::{geo, sim};
+
+// Small dense matrices are stored row major in a flat Vec
+const MAX_ITERATIONS: usize = 100_000;
+const TOLERANCE: f64 = 1e-10;
+
+#[derive(Debug)]
+pub enum LqrError{
+    BadShape(String),
+    Singular,
+    NoConvergence(f64)
+}
+
+impl std::fmt::Display for LqrError{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result{
+        match self{
+            LqrError::BadShape(msg) => write!(f, "ERROR| Bad matrix shape: {}", msg),
+            LqrError::Singular => write!(f, "ERROR| R + B'PB was not invertible"),
+            LqrError::NoConvergence(change) => write!(
+                f, "ERROR| Riccati iteration did not converge, last change [{}]", change
+            )
+        }
+    }
+}
+
+fn mat_mul(a: &[f64], b: &[f64], rows: usize, inner: usize, cols: usize) -> Vec<f64>{
+    let mut out = vec![0.0; rows * cols];
+    for i in 0..rows{
+        for k in 0..inner{
+            for j in 0..cols{
+                out[i * cols + j] += a[i * inner + k] * b[k * cols + j];
+            }
+        }
+    }
+    return out
+}
+
+fn transpose(a: &[f64], rows: usize, cols: usize) -> Vec<f64>{
+    let mut out = vec![0.0; rows * cols];
+    for i in 0..rows{
+        for j in 0..cols{
+            out[j * rows + i] = a[i * cols + j];
+        }
+    }
+    return out
+}
+
+fn inverse(a: &[f64], n: usize) -> Result<Vec<f64>, LqrError>{
+    // Gauss-Jordan with partial pivoting
+    let mut a = a.to_vec();
+    let mut inv = vec![0.0; n * n];
+    for i in 0..n{
+        inv[i * n + i] = 1.0;
+    }
+
+    for col in 0..n{
+        let pivot = (col..n)
+            .max_by(|&x, &y| a[x * n + col].abs().total_cmp(&a[y * n + col].abs()))
+            .unwrap();
+        if a[pivot * n + col].abs() < f64::EPSILON{
+            return Err(LqrError::Singular)
+        }
+        for j in 0..n{
+            a.swap(col * n + j, pivot * n + j);
+            inv.swap(col * n + j, pivot * n + j);
+        }
+
+        let scale = a[col * n + col];
+        for j in 0..n{
+            a[col * n + j] /= scale;
+            inv[col * n + j] /= scale;
+        }
+
+        for row in 0..n{
+            if row == col{
+                continue
+            }
+            let factor = a[row * n + col];
+            for j in 0..n{
+                a[row * n + j] -= factor * a[col * n + j];
+                inv[row * n + j] -= factor * inv[col * n + j];
+            }
+        }
+    }
+    return Ok(inv)
+}
+
+fn input_count(a: &[f64], b: &[f64], q: &[f64], r: &[f64], n: usize) -> Result<usize, LqrError>{
+    if n == 0 || a.len() != n * n || q.len() != n * n{
+        return Err(LqrError::BadShape(format!("A and Q must be {n}x{n}")))
+    }
+    if b.is_empty() || b.len() % n != 0{
+        return Err(LqrError::BadShape(format!("B must have {n} rows")))
+    }
+    let m = b.len() / n;
+    if r.len() != m * m{
+        return Err(LqrError::BadShape(format!("R must be {m}x{m}")))
+    }
+    return Ok(m)
+}
+
+fn riccati_step(a: &[f64], b: &[f64], q: &[f64], r: &[f64], p: &[f64], n: usize, m: usize)
+    -> Result<Vec<f64>, LqrError>
+{
+    // Q + A'PA - A'PB (R + B'PB)^-1 B'PA
+    let at = transpose(a, n, n);
+    let bt = transpose(b, n, m);
+    let pa = mat_mul(p, a, n, n, n);
+    let pb = mat_mul(p, b, n, n, m);
+
+    let btpa = mat_mul(&bt, &pa, m, n, n);
+    let s = mat_mul(&bt, &pb, m, n, m).iter().zip(r).map(|(x, y)| x + y).collect::<Vec<f64>>();
+    let k = mat_mul(&inverse(&s, m)?, &btpa, m, m, n);
+
+    let atpa = mat_mul(&at, &pa, n, n, n);
+    let atpbk = mat_mul(&mat_mul(&at, &pb, n, n, m), &k, n, m, n);
+
+    return Ok((0..n * n).map(|i| q[i] + atpa[i] - atpbk[i]).collect())
+}
+
+pub fn solve_dare(a: &[f64], b: &[f64], q: &[f64], r: &[f64], n: usize)
+    -> Result<Vec<f64>, LqrError>
+{
+    // Iterates P = Q + A'PA - A'PB (R + B'PB)^-1 B'PA from P = Q, B is n x m
+    // and R is m x m
+    let m = input_count(a, b, q, r, n)?;
+
+    let mut p = q.to_vec();
+    let mut change = f64::INFINITY;
+    for _ in 0..MAX_ITERATIONS{
+        let next = riccati_step(a, b, q, r, &p, n, m)?;
+        let scale = next.iter().fold(1.0_f64, |acc, x| acc.max(x.abs()));
+        change = next.iter().zip(p.iter()).fold(0.0_f64, |acc, (x, y)| acc.max((x - y).abs()));
+        p = next;
+
+        if !change.is_finite(){
+            break
+        }
+        if change <= TOLERANCE * scale{
+            return Ok(p)
+        }
+    }
+    return Err(LqrError::NoConvergence(change))
+}
+
+pub fn gain(a: &[f64], b: &[f64], r: &[f64], p: &[f64], n: usize) -> Result<Vec<f64>, LqrError>{
+    // K = (R + B'PB)^-1 B'PA, m x n so that u = -Kx
+    let m = input_count(a, b, p, r, n)?;
+    let bt = transpose(b, n, m);
+    let btp = mat_mul(&bt, p, m, n, n);
+
+    let s = mat_mul(&btp, b, m, n, m).iter().zip(r).map(|(x, y)| x + y).collect::<Vec<f64>>();
+    return Ok(mat_mul(&inverse(&s, m)?, &mat_mul(&btp, a, m, n, n), m, m, n))
+}
+
+// ----------------------------------------------------------------------------
+// Attitude
+// ----------------------------------------------------------------------------
+
+pub struct AttitudeLqr{
+    inertia_kgpm2: geo::Vector3,
+    q_angle: f64,
+    q_rate: f64,
+    r: f64,
+    dt: f64,
+    angle_gains: geo::Vector3,
+    rate_gains: geo::Vector3,
+    moment_cmd_nm: geo::Vector3
+}
+
+impl AttitudeLqr{
+    pub fn new(inertia_kgpm2: geo::Vector3, q_angle: f64, q_rate: f64, r: f64) -> AttitudeLqr{
+        // Each body axis is a discrete double integrator with states
+        // (angle, rate), gains are solved on the first update
+        return AttitudeLqr{
+            inertia_kgpm2,
+            q_angle,
+            q_rate,
+            r,
+            dt: 0.0,
+            angle_gains: geo::Vector3::zeros(),
+            rate_gains: geo::Vector3::zeros(),
+            moment_cmd_nm: geo::Vector3::zeros()
+        }
+    }
+
+    fn solve_gains(&mut self, dt: f64){
+        let axis_gains = |inertia_kgpm2: f64| -> [f64; 2]{
+            let a = [1.0, dt, 0.0, 1.0];
+            let b = [dt * dt / (2.0 * inertia_kgpm2), dt / inertia_kgpm2];
+            let q = [self.q_angle, 0.0, 0.0, self.q_rate];
+            let r = [self.r];
+
+            let k = solve_dare(&a, &b, &q, &r, 2)
+                .and_then(|p| gain(&a, &b, &r, &p, 2))
+                .unwrap_or_else(|err| panic!("    {}", err));
+            return [k[0], k[1]]
+        };
+
+        let [kx_angle, kx_rate] = axis_gains(self.inertia_kgpm2.i);
+        let [ky_angle, ky_rate] = axis_gains(self.inertia_kgpm2.j);
+        let [kz_angle, kz_rate] = axis_gains(self.inertia_kgpm2.k);
+
+        self.angle_gains = geo::Vector3::new(kx_angle, ky_angle, kz_angle);
+        self.rate_gains = geo::Vector3::new(kx_rate, ky_rate, kz_rate);
+        self.dt = dt;
+    }
+
+    pub fn update(
+        &mut self,
+        angle_err_rad: geo::Vector3,
+        body_rates_radps: geo::Vector3,
+        dt: f64
+    ) -> geo::Vector3{
+        // angle_err is target minus current, gains are re-solved when dt
+        // changes
+        if dt != self.dt{
+            self.solve_gains(dt);
+        }
+
+        self.moment_cmd_nm = geo::Vector3::new(
+            (self.angle_gains.i * angle_err_rad.i) - (self.rate_gains.i * body_rates_radps.i),
+            (self.angle_gains.j * angle_err_rad.j) - (self.rate_gains.j * body_rates_radps.j),
+            (self.angle_gains.k * angle_err_rad.k) - (self.rate_gains.k * body_rates_radps.k)
+        );
+        return self.moment_cmd_nm
+    }
+
+    pub fn get_angle_gains(&self) -> geo::Vector3{
+        return self.angle_gains
+    }
+
+    pub fn get_rate_gains(&self) -> geo::Vector3{
+        return self.rate_gains
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for AttitudeLqr{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        runtime.add_vector3(format!("{node_name}.moment_cmd").as_str(), "Nm", self.moment_cmd_nm);
+    }
+
+    fn save_data_verbose(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        self.save_data(node_name, runtime);
+        runtime.add_vector3(format!("{node_name}.angle_gains").as_str(), "Nm/rad", self.angle_gains);
+        runtime.add_vector3(format!("{node_name}.rate_gains").as_str(), "Nms/rad", self.rate_gains);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::RigidBody;
+    use crate::sim::Integrate;
+    use approx::assert_relative_eq;
+
+    fn double_integrator(dt: f64) -> ([f64; 4], [f64; 2]){
+        return ([1.0, dt, 0.0, 1.0], [dt * dt / 2.0, dt])
+    }
+
+    #[test]
+    fn scalar_golden_ratio(){
+        // p^2 - p - 1 = 0 for a = b = q = r = 1
+        let golden = (1.0 + 5.0_f64.sqrt()) / 2.0;
+        let p = solve_dare(&[1.0], &[1.0], &[1.0], &[1.0], 1).unwrap();
+        assert_relative_eq!(p[0], golden, max_relative = 1e-9);
+
+        let k = gain(&[1.0], &[1.0], &[1.0], &p, 1).unwrap();
+        assert_relative_eq!(k[0], golden - 1.0, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn double_integrator_gains(){
+        // Closed form for dt = 1, Q = diag(1, 0) and R = 1. P = [2 1; 1 1.5]
+        // solves the DARE exactly, B'PB = 3 and B'PA = [2 4] so
+        // K = [2 4] / (1 + 3) = [0.5 1], and A - BK has both poles at
+        // radius 0.5 so it is the stabilizing solution.
+        let (a, b) = double_integrator(1.0);
+        let q = [1.0, 0.0, 0.0, 0.0];
+        let r = [1.0];
+
+        let p = solve_dare(&a, &b, &q, &r, 2).unwrap();
+        for (value, expected) in p.iter().zip([2.0, 1.0, 1.0, 1.5]){
+            assert_relative_eq!(*value, expected, max_relative = 1e-9);
+        }
+        let k = gain(&a, &b, &r, &p, 2).unwrap();
+        assert_relative_eq!(k[0], 0.5, max_relative = 1e-9);
+        assert_relative_eq!(k[1], 1.0, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn riccati_convergence(){
+        let (a, b) = double_integrator(0.1);
+        let q = [1.0, 0.0, 0.0, 1.0];
+        let r = [1.0];
+
+        // The solution is a fixed point of the iteration
+        let p = solve_dare(&a, &b, &q, &r, 2).unwrap();
+        let next = riccati_step(&a, &b, &q, &r, &p, 2, 1).unwrap();
+        let residual = next.iter().zip(p.iter()).fold(0.0_f64, |acc, (x, y)| acc.max((x - y).abs()));
+        assert!(residual < 1e-8);
+
+        // Unstabilizable, the input cannot reach the unstable state
+        assert!(matches!(
+            solve_dare(&[2.0], &[0.0], &[1.0], &[1.0], 1),
+            Err(LqrError::NoConvergence(_))
+        ));
+
+        // Shapes
+        assert!(matches!(
+            solve_dare(&a, &b, &q, &[1.0, 0.0], 2),
+            Err(LqrError::BadShape(_))
+        ));
+    }
+
+    #[test]
+    fn closed_loop_attitude(){
+        let dt = 1e-3;
+        let mut lqr = AttitudeLqr::new(geo::Vector3::new(1.0, 1.0, 1.0), 1.0, 0.1, 1e-3);
+
+        // Small rotation about x, regulated back to identity
+        let quat = geo::Quaternion::from_axis_angle(
+            geo::Vector3::new(1.0, 0.0, 0.0),
+            10.0_f64.to_radians()
+        );
+        let mut state = [0.0; 13];
+        state[6..10].copy_from_slice(&quat.to_array());
+        let mut body = RigidBody::from_state_array(
+            state,
+            1.0,
+            geo::Matrix3x3::identity().to_array()
+        );
+
+        let mut settled_s = None;
+        for step in 0..(10.0 / dt) as usize{
+            let quat = body.get_quat();
+            let angle_err_rad = geo::Vector3::new(-quat.b, -quat.c, -quat.d) * 2.0;
+            body.body_moment_nm = lqr.update(angle_err_rad, body.get_body_ang_vel_radps(), dt);
+            body = body.rk4(dt);
+
+            let error_rad = angle_err_rad.norm();
+            if error_rad > 0.01 * 10.0_f64.to_radians(){
+                settled_s = None;
+            } else if settled_s.is_none(){
+                settled_s = Some(step as f64 * dt);
+            }
+        }
+
+        // Settled to 1% of the initial 10 deg within 5 s and stays there
+        assert!(settled_s.unwrap() < 5.0);
+        assert!(lqr.get_angle_gains().i > 0.0);
+        assert!(lqr.get_rate_gains().i > 0.0);
+    }
+}
@@ -5,4 +5,18 @@ pub use ramp::Ramp;
 pub mod bangbang;
 pub use bangbang::BangBang;
 pub mod clamp;
-pub use clamp::clamp;
\ No newline at end of file
+#[allow(deprecated)]
+pub use clamp::clamp;
+pub use clamp::{clamp_ex, ClampedValue};
+pub mod cascaded_attitude;
+pub use cascaded_attitude::CascadedAttitude;
+pub mod lqr;
+pub use lqr::AttitudeLqr;
+pub mod waypoint_guidance;
+pub use waypoint_guidance::WaypointGuidance;
+pub mod attitude_ramp;
+pub use attitude_ramp::AttitudeRamp;
+pub mod attitude_controller;
+pub use attitude_controller::AttitudeController;
+pub mod allocator;
+pub use allocator::Allocator;
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::sim;
+use crate::{sim, control, units::Lookup1D};
 
 #[derive(
     Debug,
@@ -15,7 +15,13 @@ pub struct PID{
     i_term: f64,
     d_term: f64,
     ouput: f64,
-    last_error: f64
+    raw_output: f64,
+    output_min: f64,
+    output_max: f64,
+    saturated: bool,
+    last_error: f64,
+    initial_output: Option<f64>,
+    gain_schedule: Option<(Lookup1D, Lookup1D, Lookup1D)>
 }
 
 impl PID{
@@ -35,22 +41,142 @@ impl PID{
             i_term: 0.0,
             d_term: 0.0,
             ouput: 0.0,
-            last_error: 0.0
+            raw_output: 0.0,
+            output_min: f64::NEG_INFINITY,
+            output_max: f64::INFINITY,
+            saturated: false,
+            last_error: 0.0,
+            initial_output: None,
+            gain_schedule: None
         }
     }
 
+    pub fn set_output_limits(&mut self, output_min: f64, output_max: f64){
+        if output_min > output_max{
+            panic!(
+                "    ERROR| Output min [{}] is greater than output max [{}]",
+                output_min,
+                output_max
+            )
+        }
+        self.output_min = output_min;
+        self.output_max = output_max;
+    }
+
+    pub fn is_saturated(&self) -> bool{
+        return self.saturated
+    }
+
+    pub fn set_gains(&mut self, kp: f64, ki: f64, kd: f64){
+        // Bumpless transfer, the integrator absorbs the change in the P and D
+        // terms so the output is continuous
+        let p_term = kp * self.error;
+        let d_term = if self.kd != 0.0{
+            self.d_term * (kd / self.kd)
+        } else{
+            0.0
+        };
+        self.i_term += (self.p_term - p_term) + (self.d_term - d_term);
+
+        self.p_term = p_term;
+        self.d_term = d_term;
+        self.kp = kp;
+        self.ki = ki;
+        self.kd = kd;
+    }
+
+    pub fn reset(&mut self){
+        // Clears the controller history, gains, limits and setpoint are kept
+        self.i_term = 0.0;
+        self.d_term = 0.0;
+        self.last_error = 0.0;
+        self.initial_output = None;
+    }
+
+    pub fn set_initial_output(&mut self, initial_output: f64){
+        // Bumpless start, the next ouput() back calculates the integrator as
+        // i_term = initial_output - kp*error (less the D term) so it returns
+        // initial_output, e.g. hover thrust on an altitude loop
+        self.initial_output = Some(initial_output);
+    }
+
+    pub fn set_gain_schedule(&mut self, kp: Lookup1D, ki: Lookup1D, kd: Lookup1D){
+        // Gains against the scheduling variable
+        self.gain_schedule = Some((kp, ki, kd));
+    }
+
+    pub fn set_gain_table(&mut self, table: Vec<(f64, f64, f64, f64)>){
+        // Rows of (schedule_var, kp, ki, kd)
+        if table.is_empty(){
+            panic!("    ERROR| Gain table must have at least one row")
+        }
+
+        let mut table = table;
+        table.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let schedule: Vec<f64> = table.iter().map(|row| row.0).collect();
+        let to_lookup = |gains: Vec<f64>| Lookup1D::new(schedule.clone(), gains)
+            .unwrap_or_else(|err| panic!("    {}", err));
+
+        self.set_gain_schedule(
+            to_lookup(table.iter().map(|row| row.1).collect()),
+            to_lookup(table.iter().map(|row| row.2).collect()),
+            to_lookup(table.iter().map(|row| row.3).collect())
+        );
+    }
+
+    pub fn update_gains_from_schedule(&mut self, schedule_var: f64){
+        // Linearly interpolated and clamped to the table endpoints, the
+        // integrator is not reset so the output does not step
+        let (kp, ki, kd) = match &self.gain_schedule{
+            Some((kp, ki, kd)) => (
+                kp.sample(schedule_var),
+                ki.sample(schedule_var),
+                kd.sample(schedule_var)
+            ),
+            None => panic!("    ERROR| No gain schedule set")
+        };
+        self.set_gains(kp, ki, kd);
+    }
+
+    pub fn get_gains(&self) -> (f64, f64, f64){
+        return (self.kp, self.ki, self.kd)
+    }
+
     pub fn ouput(&mut self, process_value: f64, dt: f64) -> f64{
 
         // Simple PID
         self.error = self.setpoint - process_value;
         self.p_term = self.kp * self.error;
-        self.i_term += self.ki * self.error * dt;
+        let mut i_step = self.ki * self.error * dt;
+        self.i_term += i_step;
         self.d_term = self.kd * (self.error - self.last_error / dt);
         self.last_error = self.error;
 
-        self.ouput = self.p_term + self.i_term + self.d_term;
+        if let Some(initial_output) = self.initial_output.take(){
+            self.i_term = initial_output - self.p_term - self.d_term;
+            i_step = 0.0;
+        }
+
+        self.raw_output = self.p_term + self.i_term + self.d_term;
+        let clamped = control::clamp_ex(self.raw_output, self.output_max, self.output_min);
+
+        // Anti-windup, the integrator is held while it pushes further into
+        // the limit the output is saturated against
+        if (clamped.saturated_high && i_step > 0.0) || (clamped.saturated_low && i_step < 0.0){
+            self.i_term -= i_step;
+            self.raw_output -= i_step;
+        }
+
+        self.ouput = clamped.value;
+        self.saturated = clamped.is_saturated();
         return self.ouput
     }
+
+    pub fn ouput_scheduled(&mut self, process_value: f64, schedule_var: f64, dt: f64) -> f64{
+        self.update_gains_from_schedule(schedule_var);
+        return self.ouput(process_value, dt)
+    }
 }
 
 impl sim::Save for PID{
@@ -64,13 +190,13 @@ impl sim::Save for PID{
             self.ouput,
         );
         runtime.add_or_set(format!(
-            "{node_name}.error [-]").as_str(),
-            self.error,
+            "{node_name}.raw_output [-]").as_str(),
+            self.raw_output,
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.saturated [-]").as_str(),
+            self.saturated as u8 as f64,
         );
-    }
-
-    fn save_data_verbose(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
-        self.save_data(node_name, runtime);
 
         // Gains
         runtime.add_or_set(format!(
@@ -85,6 +211,14 @@ impl sim::Save for PID{
             "{node_name}.kd [-]").as_str(),
             self.kd,
         );
+        runtime.add_or_set(format!(
+            "{node_name}.error [-]").as_str(),
+            self.error,
+        );
+    }
+
+    fn save_data_verbose(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        self.save_data(node_name, runtime);
 
         // Indiviual Contributions
         runtime.add_or_set(format!(
@@ -101,4 +235,210 @@ impl sim::Save for PID{
         );
 
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn output_saturation(){
+        use crate::sim::{Runtime, Save};
+
+        let mut runtime = Runtime::new(1.0, 0.1, "time [s]");
+        let mut pid = PID::new(2.0, 0.0, 0.0, 1.0);
+        pid.set_output_limits(-0.5, 0.5);
+
+        // Linear
+        assert_eq!(pid.ouput(0.875, 0.1), 0.25);
+        assert!(!pid.is_saturated());
+        pid.save_data("pid", &mut runtime);
+        assert_eq!(runtime.get_value("pid.saturated [-]"), 0.0);
+        assert_eq!(runtime.get_value("pid.raw_output [-]"), 0.25);
+
+        // Saturated
+        runtime.increment();
+        assert_eq!(pid.ouput(0.0, 0.1), 0.5);
+        assert!(pid.is_saturated());
+        pid.save_data("pid", &mut runtime);
+        assert_eq!(runtime.get_value("pid.saturated [-]"), 1.0);
+        assert_eq!(runtime.get_value("pid.output [-]"), 0.5);
+        assert!(runtime.get_value("pid.raw_output [-]") > 0.5);
+    }
+
+    #[test]
+    fn anti_windup(){
+        let dt = 0.1;
+        let mut pid = PID::new(0.0, 1.0, 0.0, 1.0);
+        pid.set_output_limits(-0.5, 0.5);
+
+        // Integrator stops once the output saturates high
+        for _ in 0..100{
+            pid.ouput(0.0, dt);
+        }
+        assert!(pid.is_saturated());
+        assert_relative_eq!(pid.i_term, 0.5, epsilon = 1e-12);
+
+        // Comes off the limit on the first step after the error reverses
+        pid.setpoint = -1.0;
+        assert_relative_eq!(pid.ouput(0.0, dt), 0.4, epsilon = 1e-12);
+        assert!(!pid.is_saturated());
+
+        // And again against the low limit
+        for _ in 0..100{
+            pid.ouput(0.0, dt);
+        }
+        assert_eq!(pid.ouput(0.0, dt), -0.5);
+        assert!(pid.is_saturated());
+        assert_relative_eq!(pid.i_term, -0.5, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn initial_output(){
+        let dt = 0.1;
+        let mut pid = PID::new(0.25, 0.001, 0.0, 0.5);
+        pid.set_initial_output(9.8);
+
+        // First output matches even with an error on the first step
+        assert_relative_eq!(pid.ouput(0.0, dt), 9.8, epsilon = 1e-12);
+        assert_relative_eq!(pid.i_term, 9.8 - 0.125, epsilon = 1e-12);
+
+        // Then a normal PID from there
+        let i_term = pid.i_term;
+        assert_relative_eq!(pid.ouput(0.5, dt), i_term, epsilon = 1e-12);
+
+        // Still limited
+        pid.set_output_limits(0.0, 5.0);
+        pid.set_initial_output(9.8);
+        assert_eq!(pid.ouput(0.5, dt), 5.0);
+        assert!(pid.is_saturated());
+    }
+
+    #[test]
+    fn reset(){
+        let dt = 0.1;
+        let mut pid = PID::new(1.0, 1.0, 0.0, 1.0);
+        for _ in 0..10{
+            pid.ouput(0.0, dt);
+        }
+        assert!(pid.i_term > 0.0);
+
+        pid.set_initial_output(3.0);
+        pid.reset();
+        assert_eq!(pid.i_term, 0.0);
+        assert_eq!(pid.d_term, 0.0);
+        assert_eq!(pid.last_error, 0.0);
+
+        // Same as a new controller, the pending initial output is dropped
+        let mut fresh = PID::new(1.0, 1.0, 0.0, 1.0);
+        assert_eq!(pid.ouput(0.0, dt), fresh.ouput(0.0, dt));
+        assert_eq!(pid.get_gains(), (1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn gain_schedule(){
+        let mut pid = PID::new(0.0, 0.0, 0.0, 0.0);
+        pid.set_gain_table(vec![
+            (10.0, 3.0, 0.3, 0.03),
+            (0.0, 1.0, 0.1, 0.01),
+        ]);
+
+        // Endpoints and interpolation
+        pid.update_gains_from_schedule(0.0);
+        assert_eq!(pid.get_gains(), (1.0, 0.1, 0.01));
+        pid.update_gains_from_schedule(5.0);
+        let (kp, ki, kd) = pid.get_gains();
+        assert_relative_eq!(kp, 2.0);
+        assert_relative_eq!(ki, 0.2);
+        assert_relative_eq!(kd, 0.02);
+
+        // Clamped outside of the table
+        pid.update_gains_from_schedule(-5.0);
+        assert_eq!(pid.get_gains(), (1.0, 0.1, 0.01));
+        pid.update_gains_from_schedule(50.0);
+        assert_eq!(pid.get_gains(), (3.0, 0.3, 0.03));
+
+        // Small steps in the schedule give small steps in the gains
+        let mut last_kp = 1.0;
+        for i in 0..=1000{
+            pid.update_gains_from_schedule(i as f64 * 1e-2);
+            let (kp, _, _) = pid.get_gains();
+            assert!((kp - last_kp).abs() <= 2e-3 + 1e-12);
+            last_kp = kp;
+        }
+    }
+
+    #[test]
+    fn bumpless_gain_change(){
+        let dt = 1e-2;
+        let mut pid = PID::new(1.0, 0.5, 0.0, 1.0);
+
+        let mut x = 0.0;
+        let mut last_output = 0.0;
+        let mut last_error = 0.0;
+        for i in 0..200{
+            // Large step in the gains halfway through
+            let mut p_change: f64 = 0.0;
+            if i == 100{
+                pid.set_gains(4.0, 2.0, 0.0);
+                p_change = (4.0 - 1.0) * last_error;
+            }
+
+            let output = pid.ouput(x, dt);
+            if i == 100{
+                let jump = (output - last_output).abs();
+                assert!(jump < p_change.abs());
+                assert!(jump < 1e-2);
+            }
+            last_output = output;
+            last_error = pid.error;
+
+            x += (output - x) * dt;
+        }
+    }
+
+    #[test]
+    fn scheduled_output(){
+        use crate::sim::{Runtime, Save};
+
+        let mut pid = PID::new(0.0, 0.0, 0.0, 1.0);
+        pid.set_gain_schedule(
+            Lookup1D::new(vec![0.0, 1.0], vec![1.0, 3.0]).unwrap(),
+            Lookup1D::new(vec![0.0, 1.0], vec![0.0, 0.0]).unwrap(),
+            Lookup1D::new(vec![0.0, 1.0], vec![0.0, 0.0]).unwrap()
+        );
+
+        let mut runtime = Runtime::new(1.0, 0.1, "time [s]");
+        pid.ouput_scheduled(0.0, 0.5, 0.1);
+        pid.save_data("pid", &mut runtime);
+        assert_eq!(runtime.get_value("pid.kp [-]"), 2.0);
+
+        pid.ouput_scheduled(0.0, 1.0, 0.1);
+        assert_eq!(pid.get_gains(), (3.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn gain_schedule_no_output_step(){
+        let dt = 1e-3;
+        let mut pid = PID::new(1.0, 0.5, 0.0, 1.0);
+        pid.set_gain_table(vec![
+            (0.0, 1.0, 0.5, 0.0),
+            (1.0, 2.0, 1.0, 0.0),
+            (2.0, 3.0, 1.5, 0.0)
+        ]);
+
+        // First order plant, x' = u - x
+        let mut x = 0.0;
+        let mut last_output = pid.ouput(x, dt);
+        for i in 1..3000{
+            pid.update_gains_from_schedule(i as f64 * dt);
+
+            let output = pid.ouput(x, dt);
+            assert!((output - last_output).abs() < 1e-2);
+            last_output = output;
+
+            x += (output - x) * dt;
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{geo, sim};
+
+#[derive(Debug, Clone)]
+struct Segment{
+    start_m: geo::Vector3,
+    direction: geo::Vector3,
+    length_m: f64,
+    peak_speed_mps: f64,
+    accel_time_s: f64,
+    cruise_time_s: f64
+}
+
+impl Segment{
+    fn new(start_m: geo::Vector3, end_m: geo::Vector3, max_speed_mps: f64, max_accel_mps2: f64) -> Segment{
+        // Rest to rest trapezoidal profile, triangular when the segment is
+        // too short to reach max speed
+        let delta_m = end_m - start_m;
+        let length_m = delta_m.norm();
+        if length_m == 0.0{
+            return Segment{
+                start_m,
+                direction: geo::Vector3::zeros(),
+                length_m,
+                peak_speed_mps: 0.0,
+                accel_time_s: 0.0,
+                cruise_time_s: 0.0
+            }
+        }
+
+        let peak_speed_mps = max_speed_mps.min((length_m * max_accel_mps2).sqrt());
+        let accel_time_s = peak_speed_mps / max_accel_mps2;
+        let accel_dist_m = peak_speed_mps * accel_time_s;
+
+        return Segment{
+            start_m,
+            direction: delta_m / length_m,
+            length_m,
+            peak_speed_mps,
+            accel_time_s,
+            cruise_time_s: (length_m - accel_dist_m).max(0.0) / peak_speed_mps
+        }
+    }
+
+    fn duration_s(&self) -> f64{
+        return (2.0 * self.accel_time_s) + self.cruise_time_s
+    }
+
+    fn sample(&self, time_s: f64) -> (f64, f64){
+        // Distance along and speed at time_s since the segment started
+        let accel_mps2 = if self.accel_time_s > 0.0{
+            self.peak_speed_mps / self.accel_time_s
+        } else{
+            0.0
+        };
+        let decel_start_s = self.accel_time_s + self.cruise_time_s;
+        let duration_s = self.duration_s();
+
+        if time_s <= 0.0{
+            return (0.0, 0.0)
+        } else if time_s <= self.accel_time_s{
+            return (0.5 * accel_mps2 * time_s.powi(2), accel_mps2 * time_s)
+        } else if time_s <= decel_start_s{
+            let accel_dist_m = 0.5 * accel_mps2 * self.accel_time_s.powi(2);
+            return (
+                accel_dist_m + (self.peak_speed_mps * (time_s - self.accel_time_s)),
+                self.peak_speed_mps
+            )
+        } else if time_s < duration_s{
+            let time_left_s = duration_s - time_s;
+            return (
+                self.length_m - (0.5 * accel_mps2 * time_left_s.powi(2)),
+                accel_mps2 * time_left_s
+            )
+        }
+        return (self.length_m, 0.0)
+    }
+}
+
+pub struct WaypointGuidance{
+    segments: Vec<Segment>,
+    max_accel_mps2: f64,
+    end_m: geo::Vector3,
+    segment_index: usize,
+    segment_time_s: f64,
+    position_m: geo::Vector3,
+    velocity_mps: geo::Vector3
+}
+
+impl WaypointGuidance{
+    pub fn new(start_m: geo::Vector3, max_accel_mps2: f64) -> WaypointGuidance{
+        if max_accel_mps2 <= 0.0{
+            panic!("    ERROR| Max acceleration [{}] must be positive", max_accel_mps2)
+        }
+        return WaypointGuidance{
+            segments: Vec::new(),
+            max_accel_mps2,
+            end_m: start_m,
+            segment_index: 0,
+            segment_time_s: 0.0,
+            position_m: start_m,
+            velocity_mps: geo::Vector3::zeros()
+        }
+    }
+
+    pub fn add_waypoint(&mut self, position_m: geo::Vector3, max_speed_mps: f64){
+        // Straight line from the previous waypoint, flown at up to
+        // max_speed_mps and stopping at each waypoint
+        if max_speed_mps <= 0.0{
+            panic!("    ERROR| Segment speed limit [{}] must be positive", max_speed_mps)
+        }
+        self.segments.push(Segment::new(self.end_m, position_m, max_speed_mps, self.max_accel_mps2));
+        self.end_m = position_m;
+    }
+
+    pub fn output(&mut self, dt: f64) -> geo::Vector3{
+        // Position setpoint, the final waypoint is held once reached
+        self.segment_time_s += dt;
+        while self.segment_index < self.segments.len()
+            && self.segment_time_s >= self.segments[self.segment_index].duration_s()
+        {
+            self.segment_time_s -= self.segments[self.segment_index].duration_s();
+            self.segment_index += 1;
+        }
+
+        match self.segments.get(self.segment_index){
+            Some(segment) => {
+                let (distance_m, speed_mps) = segment.sample(self.segment_time_s);
+                self.position_m = segment.start_m + (segment.direction * distance_m);
+                self.velocity_mps = segment.direction * speed_mps;
+            },
+            None => {
+                self.segment_time_s = 0.0;
+                self.position_m = self.end_m;
+                self.velocity_mps = geo::Vector3::zeros();
+            }
+        }
+
+        return self.position_m
+    }
+
+    pub fn current_velocity_setpoint(&self) -> geo::Vector3{
+        return self.velocity_mps
+    }
+
+    pub fn get_segment_index(&self) -> usize{
+        return self.segment_index
+    }
+
+    pub fn is_complete(&self) -> bool{
+        return self.segment_index >= self.segments.len()
+    }
+
+    pub fn get_total_time_s(&self) -> f64{
+        return self.segments.iter().map(|segment| segment.duration_s()).sum()
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for WaypointGuidance{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        runtime.add_or_set(format!(
+            "{node_name}.segment [-]").as_str(),self.segment_index as f64
+        );
+        runtime.add_vector3(format!("{node_name}.position").as_str(), "m", self.position_m);
+        runtime.add_vector3(format!("{node_name}.velocity").as_str(), "m/s", self.velocity_mps);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::Save;
+    use approx::assert_relative_eq;
+
+    const DT: f64 = 1e-3;
+
+    fn fly(guidance: &mut WaypointGuidance, duration_s: f64, max_speed_mps: f64) -> Vec<f64>{
+        // Returns the time each segment was finished, checks continuity
+        let mut arrivals = Vec::new();
+        let mut last_position = guidance.position_m;
+        let mut last_index = guidance.get_segment_index();
+
+        for step in 1..=(duration_s / DT).round() as usize{
+            let position = guidance.output(DT);
+            assert!((position - last_position).norm() <= (max_speed_mps * DT) + 1e-12);
+            assert!(guidance.current_velocity_setpoint().norm() <= max_speed_mps + 1e-12);
+
+            while last_index < guidance.get_segment_index(){
+                arrivals.push(step as f64 * DT);
+                last_index += 1;
+            }
+            last_position = position;
+        }
+        return arrivals
+    }
+
+    #[test]
+    fn square_pattern(){
+        // 2 m sides at 1 m/s and 1 m/s^2, 1 s accel, 1 s cruise, 1 s decel
+        let mut guidance = WaypointGuidance::new(geo::Vector3::zeros(), 1.0);
+        guidance.add_waypoint(geo::Vector3::new(0.0, 0.0, 1.0), 1.0);
+        for corner in [[2.0, 0.0, 1.0], [2.0, 2.0, 1.0], [0.0, 2.0, 1.0], [0.0, 0.0, 1.0]]{
+            guidance.add_waypoint(geo::Vector3::from_array(corner), 1.0);
+        }
+
+        // Climb is triangular, 2 * sqrt(1 m / 1 m/s^2)
+        assert_relative_eq!(guidance.get_total_time_s(), 2.0 + (4.0 * 3.0));
+
+        let arrivals = fly(&mut guidance, 20.0, 1.0);
+        let expected = [2.0, 5.0, 8.0, 11.0, 14.0];
+        assert_eq!(arrivals.len(), expected.len());
+        for (arrival, expected) in arrivals.iter().zip(expected.iter()){
+            assert!((arrival - expected).abs() <= DT * 1.5);
+        }
+
+        // Final waypoint is held
+        assert!(guidance.is_complete());
+        assert_eq!(guidance.output(100.0), geo::Vector3::new(0.0, 0.0, 1.0));
+        assert_eq!(guidance.current_velocity_setpoint(), geo::Vector3::zeros());
+    }
+
+    #[test]
+    fn triangular_profile(){
+        // Too short to reach 5 m/s
+        let mut guidance = WaypointGuidance::new(geo::Vector3::zeros(), 2.0);
+        guidance.add_waypoint(geo::Vector3::new(0.0, 1.0, 0.0), 5.0);
+
+        let peak_speed_mps = (1.0_f64 * 2.0).sqrt();
+        assert_relative_eq!(guidance.get_total_time_s(), 2.0 * peak_speed_mps / 2.0);
+
+        // Midpoint at peak speed
+        guidance.output(peak_speed_mps / 2.0);
+        assert_relative_eq!(guidance.position_m.j, 0.5, epsilon = 1e-12);
+        assert_relative_eq!(guidance.current_velocity_setpoint().j, peak_speed_mps, epsilon = 1e-12);
+
+        let arrivals = fly(&mut guidance, 1.0, peak_speed_mps);
+        assert_eq!(arrivals.len(), 1);
+    }
+
+    #[test]
+    fn zero_length_segment(){
+        let mut guidance = WaypointGuidance::new(geo::Vector3::zeros(), 1.0);
+        guidance.add_waypoint(geo::Vector3::zeros(), 1.0);
+        guidance.add_waypoint(geo::Vector3::new(1.0, 0.0, 0.0), 1.0);
+        guidance.add_waypoint(geo::Vector3::new(1.0, 0.0, 0.0), 1.0);
+
+        assert_relative_eq!(guidance.get_total_time_s(), 2.0);
+        fly(&mut guidance, 3.0, 1.0);
+        assert!(guidance.is_complete());
+        assert_eq!(guidance.position_m, geo::Vector3::new(1.0, 0.0, 0.0));
+
+        let mut runtime = sim::Runtime::new(1.0, 0.1, "time [s]");
+        guidance.save_data("guidance", &mut runtime);
+        assert_eq!(runtime.get_value("guidance.segment [-]"), 3.0);
+        assert_eq!(runtime.get_value("guidance.position.x [m]"), 1.0);
+    }
+}
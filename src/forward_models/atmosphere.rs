@@ -0,0 +1,166 @@
+use crate::units::STANDARD_GRAVITY_MPS2;
+
+// ISA constants
+// Source:
+//    https://en.wikipedia.org/wiki/International_Standard_Atmosphere
+pub const SEA_LEVEL_PRESSURE_PA: f64 = 101325.0;
+pub const SEA_LEVEL_TEMPERATURE_K: f64 = 288.15;
+pub const LAPSE_RATE_KPM: f64 = 0.0065;
+pub const GAS_CONSTANT_JPKGK: f64 = 287.05287;
+const EARTH_RADIUS_M: f64 = 6356766.0;
+
+// Layer base geopotential altitude [m] and lapse rate [K/m], temperature and
+// pressure at each base follow from the sea level values
+const LAYERS: [(f64, f64); 3] = [
+    (0.0, -LAPSE_RATE_KPM),
+    (11000.0, 0.0),
+    (20000.0, 0.001)
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Atmosphere{}
+
+impl Atmosphere{
+    pub fn new() -> Atmosphere{
+        // ISA troposphere and lower stratosphere, the top layer is extended
+        // above 32 km and the bottom layer below sea level
+        return Atmosphere{}
+    }
+
+    fn geopotential_m(alt_m: f64) -> f64{
+        return EARTH_RADIUS_M * alt_m / (EARTH_RADIUS_M + alt_m)
+    }
+
+    fn layer_at(alt_m: f64) -> (f64, f64, f64, f64){
+        // Base altitude, lapse rate, base temperature and base pressure of
+        // the layer containing the geopotential altitude
+        let mut base_temperature_k = SEA_LEVEL_TEMPERATURE_K;
+        let mut base_pressure_pa = SEA_LEVEL_PRESSURE_PA;
+
+        for i in 0..LAYERS.len(){
+            let (base_m, lapse_kpm) = LAYERS[i];
+            let top_m = match LAYERS.get(i + 1){
+                Some(&(top_m, _)) if alt_m > top_m => top_m,
+                _ => return (base_m, lapse_kpm, base_temperature_k, base_pressure_pa)
+            };
+
+            base_pressure_pa = layer_pressure_pa(
+                top_m - base_m,
+                lapse_kpm,
+                base_temperature_k,
+                base_pressure_pa
+            );
+            base_temperature_k += lapse_kpm * (top_m - base_m);
+        }
+        unreachable!()
+    }
+
+    pub fn temperature_k(&self, alt_m: f64) -> f64{
+        let alt_m = Atmosphere::geopotential_m(alt_m);
+        let (base_m, lapse_kpm, base_temperature_k, _) = Atmosphere::layer_at(alt_m);
+        return base_temperature_k + (lapse_kpm * (alt_m - base_m))
+    }
+
+    pub fn pressure_pa(&self, alt_m: f64) -> f64{
+        let alt_m = Atmosphere::geopotential_m(alt_m);
+        let (base_m, lapse_kpm, base_temperature_k, base_pressure_pa) = Atmosphere::layer_at(alt_m);
+        return layer_pressure_pa(alt_m - base_m, lapse_kpm, base_temperature_k, base_pressure_pa)
+    }
+
+    pub fn pressure_altitude_m(&self, pressure_pa: f64) -> f64{
+        // Inverse of pressure_pa, geometric altitude
+        let mut base_temperature_k = SEA_LEVEL_TEMPERATURE_K;
+        let mut base_pressure_pa = SEA_LEVEL_PRESSURE_PA;
+
+        let mut i = 0;
+        while let Some(&(top_m, _)) = LAYERS.get(i + 1){
+            let (base_m, lapse_kpm) = LAYERS[i];
+            let top_pressure_pa = layer_pressure_pa(top_m - base_m, lapse_kpm, base_temperature_k, base_pressure_pa);
+            if pressure_pa >= top_pressure_pa{
+                break
+            }
+            base_pressure_pa = top_pressure_pa;
+            base_temperature_k += lapse_kpm * (top_m - base_m);
+            i += 1;
+        }
+
+        let (base_m, lapse_kpm) = LAYERS[i];
+        let alt_m = base_m + layer_height_m(pressure_pa, lapse_kpm, base_temperature_k, base_pressure_pa);
+        return EARTH_RADIUS_M * alt_m / (EARTH_RADIUS_M - alt_m)
+    }
+
+    pub fn density_kgpm3(&self, alt_m: f64) -> f64{
+        // Ideal gas
+        return self.pressure_pa(alt_m) / (GAS_CONSTANT_JPKGK * self.temperature_k(alt_m))
+    }
+}
+
+fn layer_pressure_pa(height_m: f64, lapse_kpm: f64, base_temperature_k: f64, base_pressure_pa: f64) -> f64{
+    // Barometric formula within a single layer
+    if lapse_kpm == 0.0{
+        return base_pressure_pa * (
+            -STANDARD_GRAVITY_MPS2 * height_m / (GAS_CONSTANT_JPKGK * base_temperature_k)
+        ).exp()
+    }
+
+    let temperature_ratio = 1.0 + (lapse_kpm * height_m / base_temperature_k);
+    return base_pressure_pa * temperature_ratio.powf(-STANDARD_GRAVITY_MPS2 / (GAS_CONSTANT_JPKGK * lapse_kpm))
+}
+
+fn layer_height_m(pressure_pa: f64, lapse_kpm: f64, base_temperature_k: f64, base_pressure_pa: f64) -> f64{
+    // Inverse of layer_pressure_pa
+    let pressure_ratio = pressure_pa / base_pressure_pa;
+    if lapse_kpm == 0.0{
+        return -pressure_ratio.ln() * GAS_CONSTANT_JPKGK * base_temperature_k / STANDARD_GRAVITY_MPS2
+    }
+
+    let exponent = -GAS_CONSTANT_JPKGK * lapse_kpm / STANDARD_GRAVITY_MPS2;
+    return (base_temperature_k / lapse_kpm) * (pressure_ratio.powf(exponent) - 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn standard_tables(){
+        // U.S. Standard Atmosphere 1976, geometric altitude
+        let atmosphere = Atmosphere::new();
+        let table = [
+            // alt [m], temperature [K], pressure [Pa], density [kg/m^3]
+            (0.0, 288.15, 101325.0, 1.2250),
+            (11000.0, 216.77, 22700.0, 0.36480),
+            (20000.0, 216.65, 5529.3, 0.088910)
+        ];
+
+        for (alt_m, temperature_k, pressure_pa, density_kgpm3) in table{
+            assert_relative_eq!(atmosphere.temperature_k(alt_m), temperature_k, max_relative = 0.01);
+            assert_relative_eq!(atmosphere.pressure_pa(alt_m), pressure_pa, max_relative = 0.01);
+            assert_relative_eq!(atmosphere.density_kgpm3(alt_m), density_kgpm3, max_relative = 0.01);
+        }
+    }
+
+    #[test]
+    fn layer_continuity(){
+        let atmosphere = Atmosphere::new();
+        for boundary_m in [11000.0, 20000.0]{
+            let alt_m = boundary_m * EARTH_RADIUS_M / (EARTH_RADIUS_M - boundary_m);
+            assert_relative_eq!(
+                atmosphere.pressure_pa(alt_m - 1e-6),
+                atmosphere.pressure_pa(alt_m + 1e-6),
+                max_relative = 1e-9
+            );
+        }
+    }
+
+    #[test]
+    fn pressure_altitude_round_trip(){
+        // Below sea level, both troposphere and stratosphere layers
+        let atmosphere = Atmosphere::new();
+        for alt_m in [-200.0, 0.0, 500.0, 2000.0, 10999.0, 11500.0, 15000.0, 25000.0]{
+            let pressure_pa = atmosphere.pressure_pa(alt_m);
+            assert_relative_eq!(atmosphere.pressure_altitude_m(pressure_pa), alt_m, epsilon = 1e-6);
+        }
+    }
+}
@@ -7,8 +7,17 @@ pub struct BasicTVC{
     thrust_n: f64,
     theta_rad: f64,
     phi_rad: f64,
+    theta_cmd_rad: f64,
+    phi_cmd_rad: f64,
+    tau_s: f64,
     max_angle_rad: f64,
-    max_thrust_n: f64
+    max_thrust_n: f64,
+    theta_clamped: control::ClampedValue,
+    phi_clamped: control::ClampedValue,
+    thrust_clamped: control::ClampedValue,
+    theta_saturated_count: usize,
+    phi_saturated_count: usize,
+    thrust_saturated_count: usize
 }
 
 impl BasicTVC{
@@ -33,8 +42,17 @@ impl BasicTVC{
             thrust_n,
             theta_rad,
             phi_rad,
+            theta_cmd_rad: theta_rad,
+            phi_cmd_rad: phi_rad,
+            tau_s: 0.0,
             max_angle_rad,
-            max_thrust_n
+            max_thrust_n,
+            theta_clamped: control::ClampedValue{value: theta_rad, saturated_high: false, saturated_low: false},
+            phi_clamped: control::ClampedValue{value: phi_rad, saturated_high: false, saturated_low: false},
+            thrust_clamped: control::ClampedValue{value: thrust_n, saturated_high: false, saturated_low: false},
+            theta_saturated_count: 0,
+            phi_saturated_count: 0,
+            thrust_saturated_count: 0
         }
     }
 
@@ -44,18 +62,62 @@ impl BasicTVC{
         self.moment_vec_nm = self.pos_joint_m.cross(&self.thrust_vec_n);
     }
 
+    pub fn set_tau_s(&mut self, tau_s: f64){
+        // Servo time constant, 0.0 moves the nozzle instantly
+        self.tau_s = tau_s.abs();
+    }
+
+    pub fn step(&mut self, dt: f64){
+        // First order lag toward the commanded angles
+        if self.tau_s == 0.0{
+            self.theta_rad = self.theta_cmd_rad;
+            self.phi_rad = self.phi_cmd_rad;
+        } else{
+            let alpha = (dt / self.tau_s).min(1.0);
+            self.theta_rad += (self.theta_cmd_rad - self.theta_rad) * alpha;
+            self.phi_rad += (self.phi_cmd_rad - self.phi_rad) * alpha;
+        }
+        self.update_params();
+    }
+
     pub fn set_theta_rad(&mut self, theta_rad: f64){
-        self.theta_rad = control::clamp(theta_rad, self.max_angle_rad, -self.max_angle_rad);
+        self.theta_clamped = control::clamp_ex(theta_rad, self.max_angle_rad, -self.max_angle_rad);
+        self.theta_cmd_rad = self.theta_clamped.value;
+        if self.theta_clamped.is_saturated(){
+            self.theta_saturated_count += 1;
+        }
+        if self.tau_s == 0.0{
+            self.theta_rad = self.theta_cmd_rad;
+        }
         self.update_params();
     }
 
     pub fn set_phi_rad(&mut self, phi_rad: f64){
-        self.phi_rad = control::clamp(phi_rad, self.max_angle_rad, -self.max_angle_rad);
+        self.phi_clamped = control::clamp_ex(phi_rad, self.max_angle_rad, -self.max_angle_rad);
+        self.phi_cmd_rad = self.phi_clamped.value;
+        if self.phi_clamped.is_saturated(){
+            self.phi_saturated_count += 1;
+        }
+        if self.tau_s == 0.0{
+            self.phi_rad = self.phi_cmd_rad;
+        }
         self.update_params();
     }
 
+    pub fn get_theta_rad(&self) -> f64{
+        return self.theta_rad
+    }
+
+    pub fn get_phi_rad(&self) -> f64{
+        return self.phi_rad
+    }
+
     pub fn set_thrust_n(&mut self, thrust_n: f64){
-        self.thrust_n = control::clamp(thrust_n, self.max_thrust_n, 0.0);
+        self.thrust_clamped = control::clamp_ex(thrust_n, self.max_thrust_n, 0.0);
+        self.thrust_n = self.thrust_clamped.value;
+        if self.thrust_clamped.is_saturated(){
+            self.thrust_saturated_count += 1;
+        }
         self.update_params();
     }
 
@@ -80,6 +142,12 @@ impl sim::Save for BasicTVC{
         runtime.add_or_set(format!(
             "{node_name}.phi [rad]").as_str(),self.phi_rad
         );
+        runtime.add_or_set(format!(
+            "{node_name}.theta_cmd [rad]").as_str(),self.theta_cmd_rad
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.phi_cmd [rad]").as_str(),self.phi_cmd_rad
+        );
         runtime.add_or_set(format!(
             "{node_name}.total_thrust [N]").as_str(),self.thrust_n
         );
@@ -117,6 +185,48 @@ impl sim::Save for BasicTVC{
             "{node_name}.pos_joint.z [m]").as_str(),self.pos_joint_m.k
         );
 
+        runtime.add_or_set(format!(
+            "{node_name}.tau [s]").as_str(),self.tau_s
+        );
+
+        // Saturation
+        runtime.add_or_set(format!(
+            "{node_name}.theta_saturated [-]").as_str(),self.theta_clamped.is_saturated() as u8 as f64
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.theta_saturated_high [-]").as_str(),self.theta_clamped.saturated_high as u8 as f64
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.theta_saturated_low [-]").as_str(),self.theta_clamped.saturated_low as u8 as f64
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.phi_saturated [-]").as_str(),self.phi_clamped.is_saturated() as u8 as f64
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.phi_saturated_high [-]").as_str(),self.phi_clamped.saturated_high as u8 as f64
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.phi_saturated_low [-]").as_str(),self.phi_clamped.saturated_low as u8 as f64
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.thrust_saturated [-]").as_str(),self.thrust_clamped.is_saturated() as u8 as f64
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.thrust_saturated_high [-]").as_str(),self.thrust_clamped.saturated_high as u8 as f64
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.thrust_saturated_low [-]").as_str(),self.thrust_clamped.saturated_low as u8 as f64
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.theta_saturated_count [-]").as_str(),self.theta_saturated_count as f64
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.phi_saturated_count [-]").as_str(),self.phi_saturated_count as f64
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.thrust_saturated_count [-]").as_str(),self.thrust_saturated_count as f64
+        );
+
     }
 }
 
@@ -168,6 +278,73 @@ mod tests {
 
     }
 
+    #[test]
+    fn saturation_flags(){
+        let mut runtime = sim::Runtime::new(1.0, 0.1, "time [s]");
+        let mut tvc = BasicTVC::new(1.0, [0.0, 0.0, -1.0], 0.0, 0.0, 0.1, 10.0);
+
+        // Within limits
+        tvc.set_theta_rad(0.05);
+        tvc.set_phi_rad(-0.05);
+        tvc.set_thrust_n(5.0);
+        tvc.save_data_verbose("tvc", &mut runtime);
+
+        assert_eq!(runtime.get_value("tvc.theta_saturated [-]"), 0.0);
+        assert_eq!(runtime.get_value("tvc.phi_saturated [-]"), 0.0);
+        assert_eq!(runtime.get_value("tvc.thrust_saturated [-]"), 0.0);
+
+        // Beyond limits for two steps
+        for _ in 0..2{
+            runtime.increment();
+            tvc.set_theta_rad(0.5);
+            tvc.set_phi_rad(-0.5);
+            tvc.set_thrust_n(20.0);
+            tvc.save_data_verbose("tvc", &mut runtime);
+        }
+
+        assert_eq!(runtime.get_value("tvc.theta_saturated [-]"), 1.0);
+        assert_eq!(runtime.get_value("tvc.phi_saturated [-]"), 1.0);
+        assert_eq!(runtime.get_value("tvc.thrust_saturated [-]"), 1.0);
+        assert_eq!(runtime.get_value("tvc.theta_saturated_high [-]"), 1.0);
+        assert_eq!(runtime.get_value("tvc.theta_saturated_low [-]"), 0.0);
+        assert_eq!(runtime.get_value("tvc.phi_saturated_high [-]"), 0.0);
+        assert_eq!(runtime.get_value("tvc.phi_saturated_low [-]"), 1.0);
+        assert_eq!(runtime.get_value("tvc.thrust_saturated_high [-]"), 1.0);
+        assert_eq!(runtime.get_value("tvc.theta_saturated_count [-]"), 2.0);
+        assert_eq!(runtime.get_value("tvc.phi_saturated_count [-]"), 2.0);
+        assert_eq!(runtime.get_value("tvc.thrust_saturated_count [-]"), 2.0);
+    }
+
+    #[test]
+    fn servo_lag(){
+        let tau_s = 0.05;
+        let dt = tau_s / 1000.0;
+        let step_rad = 0.1;
+
+        let mut tvc = BasicTVC::new(1.0, [0.0, 0.0, -1.0], 0.0, 0.0, 0.5, 10.0);
+        tvc.set_tau_s(tau_s);
+        tvc.set_theta_rad(step_rad);
+
+        // Nothing moves until the actuator is stepped
+        assert_relative_eq!(tvc.get_theta_rad(), 0.0);
+
+        for _ in 0..1000{
+            tvc.step(dt);
+        }
+
+        // 1 - e^-1 after one time constant
+        assert_relative_eq!(
+            tvc.get_theta_rad(),
+            step_rad * (1.0 - (-1.0_f64).exp()),
+            max_relative = 1e-3
+        );
+
+        // Instantaneous without a time constant
+        tvc.set_tau_s(0.0);
+        tvc.set_phi_rad(step_rad);
+        assert_relative_eq!(tvc.get_phi_rad(), step_rad);
+    }
+
     #[test]
     fn sin_sweep(){
         let mut runtime = sim::Runtime::new(PI * 2.0 + 1e-2, 1e-2, "angle [rad]");
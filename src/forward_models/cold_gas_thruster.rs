@@ -0,0 +1,157 @@
+use crate::{geo, sim};
+
+pub struct ColdGasThruster{
+    pos_m: geo::Vector3,
+    direction: geo::Vector3,
+    thrust_n: f64,
+    min_on_time_s: f64,
+    is_firing: bool,
+    on_time_s: f64,
+    impulse_ns: f64
+}
+
+impl ColdGasThruster{
+    pub fn new(
+        pos_m: geo::Vector3,
+        direction: geo::Vector3,
+        thrust_n: f64,
+        min_on_time_s: f64
+    ) -> ColdGasThruster{
+        // Position relative to the cg and thrust direction, body frame
+        if direction.norm() == 0.0{
+            panic!("    ERROR| Thruster direction must be non-zero")
+        }
+        if thrust_n < 0.0 || min_on_time_s < 0.0{
+            panic!(
+                "    ERROR| Thrust [{}] and minimum on time [{}] must be non-negative",
+                thrust_n,
+                min_on_time_s
+            )
+        }
+        return ColdGasThruster{
+            pos_m,
+            direction: direction.to_unit(),
+            thrust_n,
+            min_on_time_s,
+            is_firing: false,
+            on_time_s: 0.0,
+            impulse_ns: 0.0
+        }
+    }
+
+    pub fn command(&mut self, on: bool, dt: f64){
+        // State for the next dt. Once opened the valve stays open for at
+        // least min_on_time_s, so short pulses are extended to the minimum
+        // impulse bit.
+        let in_min_on = self.is_firing && self.on_time_s < self.min_on_time_s - (1e-6 * dt);
+        self.is_firing = on || in_min_on;
+
+        if self.is_firing{
+            self.on_time_s += dt;
+            self.impulse_ns += self.thrust_n * dt;
+        } else{
+            self.on_time_s = 0.0;
+        }
+    }
+
+    pub fn is_firing(&self) -> bool{
+        return self.is_firing
+    }
+
+    pub fn get_force_n(&self) -> geo::Vector3{
+        // Body frame
+        if self.is_firing{
+            return self.direction * self.thrust_n
+        }
+        return geo::Vector3::zeros()
+    }
+
+    pub fn get_moment_nm(&self) -> geo::Vector3{
+        // Body frame, about the cg
+        return self.pos_m.cross(&self.get_force_n())
+    }
+
+    pub fn get_min_impulse_bit_ns(&self) -> f64{
+        return self.thrust_n * self.min_on_time_s
+    }
+
+    pub fn get_impulse_ns(&self) -> f64{
+        // Total delivered since construction
+        return self.impulse_ns
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for ColdGasThruster{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        runtime.add_or_set(format!(
+            "{node_name}.firing [-]").as_str(),self.is_firing as u8 as f64
+        );
+        runtime.add_vector3(format!("{node_name}.force").as_str(), "N", self.get_force_n());
+        runtime.add_vector3(format!("{node_name}.moment").as_str(), "Nm", self.get_moment_nm());
+    }
+
+    fn save_data_verbose(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        self.save_data(node_name, runtime);
+        runtime.add_or_set(format!(
+            "{node_name}.impulse [Ns]").as_str(),self.impulse_ns
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn thruster() -> ColdGasThruster{
+        return ColdGasThruster::new(
+            geo::Vector3::new(0.0, 0.5, -1.0),
+            geo::Vector3::new(2.0, 0.0, 0.0),
+            4.0,
+            0.01
+        )
+    }
+
+    #[test]
+    fn minimum_impulse_bit(){
+        // A single 1 ms pulse still fires for the full 10 ms
+        let dt = 1e-3;
+        let mut uut = thruster();
+        let mut steps_on = 0;
+        for i in 0..50{
+            uut.command(i == 0, dt);
+            if uut.is_firing(){
+                steps_on += 1;
+            }
+        }
+        assert_eq!(steps_on, 10);
+        assert_relative_eq!(uut.get_impulse_ns(), uut.get_min_impulse_bit_ns(), max_relative = 1e-9);
+
+        // Longer commands are not cut short or extended
+        let mut uut = thruster();
+        let mut steps_on = 0;
+        for i in 0..50{
+            uut.command(i < 25, dt);
+            if uut.is_firing(){
+                steps_on += 1;
+            }
+        }
+        assert_eq!(steps_on, 25);
+        assert!(!uut.is_firing());
+    }
+
+    #[test]
+    fn moment_arm(){
+        let mut uut = thruster();
+        assert_eq!(uut.get_moment_nm(), geo::Vector3::zeros());
+
+        // r x F = (0, 0.5, -1) x (4, 0, 0)
+        uut.command(true, 1e-3);
+        assert_eq!(uut.get_force_n(), geo::Vector3::new(4.0, 0.0, 0.0));
+        assert_eq!(uut.get_moment_nm(), geo::Vector3::new(0.0, -4.0, -2.0));
+    }
+}
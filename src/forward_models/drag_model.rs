@@ -0,0 +1,76 @@
+use crate::{geo, sim};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DragModel{
+    cd: f64,
+    area_m2: f64,
+    force_n: geo::Vector3
+}
+
+impl DragModel{
+    pub fn new(cd: f64, area_m2: f64) -> DragModel{
+        // Constant drag coefficient referenced to area_m2
+        if cd < 0.0 || area_m2 < 0.0{
+            panic!(
+                "    ERROR| Drag coefficient [{}] and area [{}] must be non-negative",
+                cd,
+                area_m2
+            )
+        }
+        return DragModel{cd, area_m2, force_n: geo::Vector3::zeros()}
+    }
+
+    pub fn force_n(&self, vel_mps: geo::Vector3, air_density_kgpm3: f64) -> geo::Vector3{
+        // -0.5 * rho * Cd * A * |v| * v, opposes the air relative velocity
+        return vel_mps * (-0.5 * air_density_kgpm3 * self.cd * self.area_m2 * vel_mps.norm())
+    }
+
+    pub fn update(&mut self, vel_mps: geo::Vector3, air_density_kgpm3: f64) -> geo::Vector3{
+        // Same as force_n but keeps the result for recording
+        self.force_n = self.force_n(vel_mps, air_density_kgpm3);
+        return self.force_n
+    }
+
+    pub fn get_force_n(&self) -> geo::Vector3{
+        return self.force_n
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for DragModel{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        runtime.add_vector3(format!("{node_name}.force").as_str(), "N", self.force_n);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forward_models::Atmosphere;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn zero_velocity(){
+        let drag = DragModel::new(0.75, 0.1);
+        assert_eq!(drag.force_n(geo::Vector3::zeros(), 1.225), geo::Vector3::zeros());
+    }
+
+    #[test]
+    fn opposes_velocity(){
+        let drag = DragModel::new(0.5, 0.2);
+        let vel_mps = geo::Vector3::new(3.0, -4.0, 12.0);
+        let rho = Atmosphere::new().density_kgpm3(0.0);
+        let force_n = drag.force_n(vel_mps, rho);
+
+        // |v| = 13
+        assert_relative_eq!(force_n.norm(), 0.5 * rho * 0.5 * 0.2 * 13.0_f64.powi(2), epsilon = 1e-12);
+        assert_relative_eq!(force_n.dot(&vel_mps), -force_n.norm() * vel_mps.norm(), epsilon = 1e-12);
+
+        // Thinner air higher up
+        let force_high_n = drag.force_n(vel_mps, Atmosphere::new().density_kgpm3(10000.0));
+        assert!(force_high_n.norm() < force_n.norm());
+    }
+}
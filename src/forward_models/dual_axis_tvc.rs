@@ -0,0 +1,163 @@
+use crate::{geo, sim, control, forward_models::FourBarLinkage};
+
+pub struct DualAxisTVC{
+    pitch: FourBarLinkage,
+    yaw: FourBarLinkage,
+    pos_joint_m: geo::Vector3,
+    thrust_vec_n: geo::Vector3,
+    moment_vec_nm: geo::Vector3,
+    thrust_n: f64,
+    max_thrust_n: f64
+}
+
+impl DualAxisTVC{
+    pub fn new(
+        pitch: FourBarLinkage,
+        yaw: FourBarLinkage,
+        thrust_n: f64,
+        pos_joint_m: [f64; 3],
+        max_thrust_n: f64
+    ) -> DualAxisTVC{
+        // Pitch linkage tilts the nozzle about body y, yaw about body x
+        let mut tvc = DualAxisTVC{
+            pitch,
+            yaw,
+            pos_joint_m: geo::Vector3::from_array(pos_joint_m),
+            thrust_vec_n: geo::Vector3::zeros(),
+            moment_vec_nm: geo::Vector3::zeros(),
+            thrust_n: 0.0,
+            max_thrust_n
+        };
+        tvc.set_thrust_n(thrust_n);
+        return tvc
+    }
+
+    fn update_params(&mut self){
+        // Nozzle starts along body z, pitched about y then yawed about x
+        let pitch_rad = self.pitch.get_output_deflection_rad();
+        let yaw_rad = self.yaw.get_output_deflection_rad();
+
+        let axis = geo::Matrix3x3::from_rotation_x(yaw_rad)
+            * geo::Matrix3x3::from_rotation_y(pitch_rad)
+            * geo::Vector3::new(0.0, 0.0, 1.0);
+
+        self.thrust_vec_n = axis * self.thrust_n;
+        self.moment_vec_nm = self.pos_joint_m.cross(&self.thrust_vec_n);
+    }
+
+    pub fn set_pitch_rad(&mut self, servo_angle_rad: f64){
+        // Servo angle of the pitch linkage
+        self.pitch.set_servo_angle_rad(servo_angle_rad);
+        self.update_params();
+    }
+
+    pub fn set_yaw_rad(&mut self, servo_angle_rad: f64){
+        // Servo angle of the yaw linkage
+        self.yaw.set_servo_angle_rad(servo_angle_rad);
+        self.update_params();
+    }
+
+    pub fn set_thrust_n(&mut self, thrust_n: f64){
+        self.thrust_n = control::clamp_ex(thrust_n, self.max_thrust_n, 0.0).value;
+        self.update_params();
+    }
+
+    pub fn get_pitch_deflection_rad(&self) -> f64{
+        return self.pitch.get_output_deflection_rad()
+    }
+
+    pub fn get_yaw_deflection_rad(&self) -> f64{
+        return self.yaw.get_output_deflection_rad()
+    }
+
+    pub fn get_thrust_vec_n(&self) -> geo::Vector3{
+        return self.thrust_vec_n
+    }
+
+    pub fn get_moment_vec_nm(&self) -> geo::Vector3{
+        return self.moment_vec_nm
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for DualAxisTVC{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        runtime.add_or_set(format!(
+            "{node_name}.pitch_deflection [rad]").as_str(),self.get_pitch_deflection_rad()
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.yaw_deflection [rad]").as_str(),self.get_yaw_deflection_rad()
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.total_thrust [N]").as_str(),self.thrust_n
+        );
+    }
+
+    fn save_data_verbose(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        self.save_data(node_name, runtime);
+        runtime.add_vector3(format!("{node_name}.thrust").as_str(), "N", self.thrust_vec_n);
+        runtime.add_vector3(format!("{node_name}.moment").as_str(), "Nm", self.moment_vec_nm);
+
+        self.pitch.save_data(format!("{node_name}.pitch").as_str(), runtime);
+        self.yaw.save_data(format!("{node_name}.yaw").as_str(), runtime);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn linkage() -> FourBarLinkage{
+        return FourBarLinkage::new_basic(-1.5, 0.5, 1.0, 1.0)
+    }
+
+    #[test]
+    fn sweep_both_axes(){
+        let thrust_n = 10.0;
+        let mut tvc = DualAxisTVC::new(linkage(), linkage(), thrust_n, [0.0, 0.0, -0.5], 20.0);
+
+        // Neutral
+        tvc.set_pitch_rad(0.0);
+        tvc.set_yaw_rad(0.0);
+        let thrust = tvc.get_thrust_vec_n();
+        assert_relative_eq!(thrust.i, 0.0, epsilon = 1e-12);
+        assert_relative_eq!(thrust.j, 0.0, epsilon = 1e-12);
+        assert_relative_eq!(thrust.k, thrust_n, epsilon = 1e-12);
+
+        let steps = 10;
+        for i in -steps..=steps{
+            for j in -steps..=steps{
+                tvc.set_pitch_rad(0.8 * i as f64 / steps as f64);
+                tvc.set_yaw_rad(0.8 * j as f64 / steps as f64);
+
+                let pitch_rad = tvc.get_pitch_deflection_rad();
+                let yaw_rad = tvc.get_yaw_deflection_rad();
+                let thrust = tvc.get_thrust_vec_n();
+
+                // Magnitude is shared, direction follows both deflections
+                assert_relative_eq!(thrust.norm(), thrust_n, max_relative = 1e-12);
+                assert_relative_eq!(thrust.i, thrust_n * pitch_rad.sin(), epsilon = 1e-9);
+                assert_relative_eq!(
+                    thrust.j,
+                    -thrust_n * yaw_rad.sin() * pitch_rad.cos(),
+                    epsilon = 1e-9
+                );
+
+                // Lever arm
+                let moment = tvc.get_moment_vec_nm();
+                assert_relative_eq!(moment.dot(&thrust), 0.0, epsilon = 1e-9);
+            }
+        }
+
+        // Servo commands move the nozzle in both directions
+        tvc.set_pitch_rad(0.5);
+        let positive = tvc.get_pitch_deflection_rad();
+        tvc.set_pitch_rad(-0.5);
+        let negative = tvc.get_pitch_deflection_rad();
+        assert!(positive * negative < 0.0);
+    }
+}
@@ -1,5 +1,8 @@
+use std::f64::consts::PI;
+
 use crate::{geo, sim, control};
 
+const ENVELOPE_MARGIN_RAD: f64 = 1e-9;
 
 pub struct FourBarLinkage{
     a: geo::Line2, // Input link 
@@ -8,7 +11,12 @@ pub struct FourBarLinkage{
     l: geo::Line2, // Free link 
     input_angle_rad: f64,
     output_angle_rad: f64,
-    max_input_angle_rad: f64
+    max_input_angle_rad: f64,
+    min_reachable_rad: f64,
+    max_reachable_rad: f64,
+    is_saturated: bool,
+    tau_s: f64,
+    max_rate_radps: f64
 }
 
 impl FourBarLinkage{
@@ -19,15 +27,98 @@ impl FourBarLinkage{
         l: geo::Line2,
         max_input_angle_rad: f64
     ) -> FourBarLinkage{
+        let (min_reachable_rad, max_reachable_rad) = FourBarLinkage::reachable_envelope_rad(&a, &b, &l);
         return FourBarLinkage{
             a,
             b,
             g,
             l,
-            input_angle_rad: a.angle_x_rad(),
-            output_angle_rad: b.angle_x_rad(),
-            max_input_angle_rad
+            input_angle_rad: FourBarLinkage::servo_angle_of(&a),
+            output_angle_rad: FourBarLinkage::servo_angle_of(&b),
+            max_input_angle_rad,
+            min_reachable_rad,
+            max_reachable_rad,
+            is_saturated: false,
+            tau_s: 0.0,
+            max_rate_radps: f64::INFINITY
+        }
+    }
+
+    pub fn with_dynamics(mut self, tau_s: f64, max_rate_radps: f64) -> FourBarLinkage{
+        // Servo time constant and slew rate used by step, 0.0 and infinity
+        // give an instant servo
+        if tau_s < 0.0 || max_rate_radps <= 0.0{
+            panic!(
+                "    ERROR| Servo time constant [{}] must be non-negative and rate limit [{}] positive",
+                tau_s,
+                max_rate_radps
+            )
         }
+        self.tau_s = tau_s;
+        self.max_rate_radps = max_rate_radps;
+        return self
+    }
+
+    fn servo_angle_of(link: &geo::Line2) -> f64{
+        // Inverse of the alpha = 3pi/2 + servo angle used when setting the
+        // servo, wrapped to [-pi, pi). Also used for the output link.
+        return (link.angle_x_rad() - geo::PI_THREE_HALFS + PI).rem_euclid(geo::PI_DOUBLE) - PI
+    }
+
+    fn reachable_envelope_rad(a: &geo::Line2, b: &geo::Line2, l: &geo::Line2) -> (f64, f64){
+        // Servo angles around the initial pose where the coupler can still
+        // close the loop, i.e. the servo tip is between |l - b| and l + b from
+        // the output pivot. Infinite limits when the servo can fully rotate.
+        // Source:
+        //    https://en.wikipedia.org/wiki/Four-bar_linkage#Grashof_condition
+        let ground = geo::Vector2::from_points(b.start_x_m, b.start_y_m, a.start_x_m, a.start_y_m);
+        let (g_m, a_m) = (ground.norm(), a.length_m());
+        let closest_m = (l.length_m() - b.length_m()).abs();
+        let furthest_m = l.length_m() + b.length_m();
+
+        // Tip distance^2 = g^2 + a^2 + 2ag cos(psi), psi the servo angle
+        // measured from the ground vector
+        let cos_psi = |distance_m: f64| (distance_m.powi(2) - g_m.powi(2) - a_m.powi(2)) / (2.0 * a_m * g_m);
+        let (cos_min, cos_max) = (cos_psi(closest_m), cos_psi(furthest_m));
+
+        let a_vec = a.to_vector2();
+        let psi_0 = ((ground.i * a_vec.j) - (ground.j * a_vec.i)).atan2(ground.dot(&a_vec));
+        let (lower_rad, upper_rad) = if g_m == 0.0 || (cos_min <= -1.0 && cos_max >= 1.0){
+            // The servo can rotate fully
+            (f64::NEG_INFINITY, f64::INFINITY)
+        } else if cos_max >= 1.0{
+            // Passes through psi = 0, the tip is too close beyond acos(cos_min)
+            let limit = cos_min.acos();
+            (-limit, limit)
+        } else if cos_min <= -1.0{
+            // Passes through psi = pi
+            let limit = cos_max.acos();
+            if psi_0 >= 0.0 {(limit, geo::PI_DOUBLE - limit)} else {(-geo::PI_DOUBLE + limit, -limit)}
+        } else if psi_0 >= 0.0{
+            (cos_max.acos(), cos_min.acos())
+        } else{
+            (-cos_min.acos(), -cos_max.acos())
+        };
+
+        // Back to servo angles, with a small margin so the boundary itself
+        // does not fail on rounding
+        let theta_0 = FourBarLinkage::servo_angle_of(a);
+        return (
+            theta_0 + (lower_rad - psi_0) + ENVELOPE_MARGIN_RAD,
+            theta_0 + (upper_rad - psi_0) - ENVELOPE_MARGIN_RAD
+        )
+    }
+
+    pub fn get_input_limits_rad(&self) -> (f64, f64){
+        // Combined servo limit and reachable envelope
+        return (
+            self.min_reachable_rad.max(-self.max_input_angle_rad),
+            self.max_reachable_rad.min(self.max_input_angle_rad)
+        )
+    }
+
+    pub fn is_saturated(&self) -> bool{
+        return self.is_saturated
     }
 
     pub fn from_points(
@@ -60,8 +151,12 @@ impl FourBarLinkage{
     }
 
     pub fn set_servo_angle_rad(&mut self, input_angle_rad: f64){
-
-        self.input_angle_rad = control::clamp(input_angle_rad, self.max_input_angle_rad, -self.max_input_angle_rad);
+        // Commands outside of the servo limit or the reachable envelope are
+        // clamped and flagged
+        let (min_rad, max_rad) = self.get_input_limits_rad();
+        let clamped = control::clamp_ex(input_angle_rad, max_rad, min_rad);
+        self.input_angle_rad = clamped.value;
+        self.is_saturated = clamped.is_saturated();
 
         let alpha = geo::PI_THREE_HALFS + self.input_angle_rad;
 
@@ -84,8 +179,10 @@ impl FourBarLinkage{
             self.b.start_x_m, self.b.start_y_m, self.b.length_m()
         );
 
-        let intersect_l_b = match c1.intersect_circle(&c0){
-            None => panic!("Bad Intersect"),
+        // Stay on the branch of the previous pose
+        let last_end = geo::Vector2::new(self.b.end_x_m, self.b.end_y_m);
+        let intersect_l_b = match c1.intersect_circle(&c0).closest_to(last_end){
+            None => panic!("    ERROR| Bad Intersect at servo angle [{}]", self.input_angle_rad),
             Some(vector) => vector
         };
 
@@ -103,16 +200,80 @@ impl FourBarLinkage{
         self.get_tvc_angle_rad();
     }
 
+    pub fn step(&mut self, commanded_rad: f64, dt: f64){
+        // First order lag toward the command, then rate limited, before the
+        // linkage is solved. set_servo_angle_rad stays instantaneous.
+        let current_rad = self.input_angle_rad;
+        let target_rad = if self.tau_s == 0.0{
+            commanded_rad
+        } else{
+            current_rad + ((commanded_rad - current_rad) * (dt / self.tau_s).min(1.0))
+        };
+
+        let max_step_rad = self.max_rate_radps * dt;
+        let step_rad = control::clamp_ex(target_rad - current_rad, max_step_rad, -max_step_rad).value;
+        self.set_servo_angle_rad(current_rad + step_rad);
+    }
+
+    pub fn get_servo_angle_rad(&self) -> f64{
+        return self.input_angle_rad
+    }
+
     pub fn get_tvc_angle_rad(&mut self) -> f64{
-        let beta = self.b.angle_x_rad();
-        self.output_angle_rad = beta - geo::PI_THREE_HALFS;
+        self.output_angle_rad = FourBarLinkage::servo_angle_of(&self.b);
 
         return self.output_angle_rad
     }
 
+    pub fn get_output_velocity_radps(&self, input_vel_radps: f64) -> f64{
+        // Velocity loop closure, the coupler length is constant so
+        // (v_a - v_b) . (p_a - p_b) = 0 with v = w x r
+        // Source:
+        //    https://en.wikipedia.org/wiki/Four-bar_linkage
+        let r_a = self.a.to_vector2();
+        let r_b = self.b.to_vector2();
+        let coupler = geo::Vector2::from_points(
+            self.b.end_x_m,
+            self.b.end_y_m,
+            self.a.end_x_m,
+            self.a.end_y_m
+        );
+
+        // Jacobian coefficients of the current configuration
+        let input_coeff = r_a.rotate_90_ccw().dot(&coupler);
+        let output_coeff = r_b.rotate_90_ccw().dot(&coupler);
+
+        if output_coeff == 0.0{
+            // Toggle position, the output velocity is undefined
+            return f64::NAN
+        }
+
+        // Positive is counter-clockwise
+        return input_vel_radps * input_coeff / output_coeff
+    }
+
+    pub fn compute_transmission_angle(&self) -> f64{
+        // Angle between the coupler and the output link in [0, pi], force is
+        // transmitted best near pi/2 and the linkage jams at 0 or pi. From
+        // atan2 rather than acos of the dot product so it stays accurate at
+        // the toggle positions.
+        return self.l.signed_angle_rad(&self.b).abs()
+    }
+
+    pub fn is_in_toggle_position(&self, tol: f64) -> bool{
+        let angle_rad = self.compute_transmission_angle();
+        return angle_rad <= tol || angle_rad >= PI - tol
+    }
+
+    pub fn get_output_deflection_rad(&self) -> f64{
+        // Signed angle of the output link from straight down, positive is
+        // counter-clockwise
+        let b = self.b.to_vector2();
+        return b.i.atan2(-b.j)
+    }
+
     pub fn get_thrust_vector(&mut self) -> geo::Vector2{
-        let beta = self.b.angle_x_rad();
-        self.output_angle_rad = beta - geo::PI_THREE_HALFS;
+        self.output_angle_rad = FourBarLinkage::servo_angle_of(&self.b);
 
         return geo::Vector2::from_angle_rad(self.b.length_m(), self.output_angle_rad)
     }
@@ -130,6 +291,9 @@ impl sim::Save for FourBarLinkage{
         runtime.add_or_set(format!(
             "{node_name}.servo_angle [rad]").as_str(),self.input_angle_rad
         );
+        runtime.add_or_set(format!(
+            "{node_name}.servo_saturated [-]").as_str(),self.is_saturated as u8 as f64
+        );
 
     }
 
@@ -213,6 +377,234 @@ mod tests {
     use crate::sim::Save;
     use super::*;
     use approx::assert_relative_eq;
+    fn output_link_angle_rad(four_bar: &FourBarLinkage) -> f64{
+        let b = four_bar.b.to_vector2();
+        return b.j.atan2(b.i)
+    }
+
+    fn is_reachable(four_bar: &FourBarLinkage, input_angle_rad: f64) -> bool{
+        // Direct circle intersection of the servo tip and output link
+        let a = geo::Line2::from_angle_rad(
+            four_bar.a.start_x_m,
+            four_bar.a.start_y_m,
+            four_bar.a.length_m(),
+            geo::PI_THREE_HALFS + input_angle_rad
+        );
+        let c0 = geo::Circle::new(a.end_x_m, a.end_y_m, four_bar.l.length_m());
+        let c1 = geo::Circle::new(four_bar.b.start_x_m, four_bar.b.start_y_m, four_bar.b.length_m());
+        return !c1.intersect_circle(&c0).points().is_empty()
+    }
+
+    fn boundary_rad(four_bar: &FourBarLinkage, inside_rad: f64, outside_rad: f64) -> f64{
+        let (mut inside_rad, mut outside_rad) = (inside_rad, outside_rad);
+        for _ in 0..60{
+            let mid_rad = 0.5 * (inside_rad + outside_rad);
+            if is_reachable(four_bar, mid_rad){
+                inside_rad = mid_rad;
+            } else{
+                outside_rad = mid_rad;
+            }
+        }
+        return inside_rad
+    }
+
+    #[test]
+    fn reachable_envelope(){
+        // Long servo arm, the coupler cannot close the loop past about
+        // -1.41 and 2.59 rad
+        let mut four_bar = FourBarLinkage::new_basic(-1.5, 1.2, 1.0, 3.0 * PI);
+        let (min_rad, max_rad) = four_bar.get_input_limits_rad();
+        assert!(min_rad > -PI && max_rad < PI);
+
+        assert!((min_rad - boundary_rad(&four_bar, 0.0, -PI)).abs() < 1e-3);
+        assert!((max_rad - boundary_rad(&four_bar, 0.0, PI)).abs() < 1e-3);
+
+        // Sweep well past both ends
+        let mut runtime = sim::Runtime::new(1.0, 0.1, "time [s]");
+        let mut last_output_rad = f64::NEG_INFINITY;
+        let mut input_angle_rad = -6.0;
+        while input_angle_rad <= 6.0{
+            four_bar.set_servo_angle_rad(input_angle_rad);
+            let output_rad = four_bar.get_output_deflection_rad();
+            assert!(output_rad.is_finite());
+
+            // Toward the max limit the coupler straightens out along the
+            // output link, toward the min limit the output rocks back first
+            if input_angle_rad >= 0.0{
+                assert!(output_rad >= last_output_rad - 1e-12);
+            }
+            assert_eq!(four_bar.is_saturated(), input_angle_rad < min_rad || input_angle_rad > max_rad);
+
+            last_output_rad = output_rad;
+            input_angle_rad += 1e-3;
+        }
+
+        four_bar.save_data("linkage", &mut runtime);
+        assert_eq!(runtime.get_value("linkage.servo_saturated [-]"), 1.0);
+        assert_relative_eq!(runtime.get_value("linkage.servo_angle [rad]"), max_rad);
+
+        // Servo limit inside the envelope wins
+        let four_bar = FourBarLinkage::new_basic(-1.5, 1.2, 1.0, 0.5);
+        assert_eq!(four_bar.get_input_limits_rad(), (-0.5, 0.5));
+    }
+
+    #[test]
+    fn transmission_angle(){
+        // Servo range of the basic TVC geometry
+        let mut four_bar = FourBarLinkage::new_basic(-1.5, 0.5, 1.0, 0.5);
+        let (min_rad, max_rad) = four_bar.get_input_limits_rad();
+
+        let mut min_angle_rad = PI;
+        let mut max_angle_rad: f64 = 0.0;
+        let mut input_angle_rad = min_rad;
+        while input_angle_rad <= max_rad{
+            four_bar.set_servo_angle_rad(input_angle_rad);
+            let angle_rad = four_bar.compute_transmission_angle();
+            min_angle_rad = min_angle_rad.min(angle_rad);
+            max_angle_rad = max_angle_rad.max(angle_rad);
+            input_angle_rad += 1e-3;
+        }
+        assert!(min_angle_rad > 20.0_f64.to_radians());
+        assert!(max_angle_rad < 160.0_f64.to_radians());
+
+        // Square at the initial pose
+        let four_bar = FourBarLinkage::new_basic(-1.5, 0.5, 1.0, 0.5);
+        assert_relative_eq!(four_bar.compute_transmission_angle(), PI / 2.0);
+        assert!(!four_bar.is_in_toggle_position(0.1));
+
+        // The reachable envelope ends where the coupler and output link line up
+        let mut four_bar = FourBarLinkage::new_basic(-1.5, 1.2, 1.0, 3.0 * PI);
+        let (min_rad, max_rad) = four_bar.get_input_limits_rad();
+        for limit_rad in [min_rad, max_rad]{
+            four_bar.set_servo_angle_rad(limit_rad);
+            assert!(four_bar.is_in_toggle_position(0.05));
+        }
+    }
+
+    #[test]
+    fn odd_symmetric_output(){
+        // Parallelogram, mirror symmetric about the vertical so the output
+        // follows the servo with the same sign
+        let mut four_bar = FourBarLinkage::new_basic(0.0, 0.5, 1.0, 3.0 * PI);
+        assert_relative_eq!(four_bar.get_tvc_angle_rad(), 0.0, epsilon = 1e-12);
+
+        let mut input_angle_rad = 0.05;
+        while input_angle_rad <= 1.0{
+            four_bar.set_servo_angle_rad(input_angle_rad);
+            let positive_rad = four_bar.get_tvc_angle_rad();
+            assert_relative_eq!(positive_rad, four_bar.get_output_deflection_rad(), epsilon = 1e-9);
+            four_bar.set_servo_angle_rad(-input_angle_rad);
+            let negative_rad = four_bar.get_tvc_angle_rad();
+
+            assert!(positive_rad > 0.0);
+            assert_relative_eq!(positive_rad, -negative_rad, epsilon = 1e-9);
+            assert_relative_eq!(positive_rad, input_angle_rad, epsilon = 1e-9);
+            input_angle_rad += 0.05;
+        }
+    }
+
+    #[test]
+    fn branch_continuity(){
+        // Down to the min limit and back, where the two coupler solutions
+        // are close together
+        let mut four_bar = FourBarLinkage::new_basic(-1.5, 1.2, 1.0, 3.0 * PI);
+        let (min_rad, _) = four_bar.get_input_limits_rad();
+        let step_rad = 1e-3;
+        let steps = ((0.0 - min_rad) / step_rad).ceil() as i64 + 10;
+
+        let mut last_output_rad = four_bar.get_output_deflection_rad();
+        let mut max_jump_rad: f64 = 0.0;
+        for i in (0..steps).chain((0..steps).rev()){
+            four_bar.set_servo_angle_rad(-(i as f64) * step_rad);
+            let output_rad = four_bar.get_output_deflection_rad();
+            max_jump_rad = max_jump_rad.max((output_rad - last_output_rad).abs());
+            last_output_rad = output_rad;
+        }
+        assert!(max_jump_rad < 0.05);
+        assert_relative_eq!(last_output_rad, 0.0, epsilon = 1e-9);
+
+        // Servo on the other side, the starting pose is on the second
+        // solution and must stay there
+        let mut four_bar = FourBarLinkage::new_basic(-1.5, 0.5, -1.0, 3.0 * PI);
+        four_bar.set_servo_angle_rad(0.0);
+        assert_relative_eq!(four_bar.b.end_x_m, 0.0, epsilon = 1e-9);
+        assert_relative_eq!(four_bar.b.end_y_m, -2.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn servo_dynamics(){
+        // 0.3 rad step, slews at 2 rad/s until the lag asks for less than
+        // that at 0.1 rad of error, then decays with tau
+        let (tau_s, rate_radps, step_rad, dt) = (0.05, 2.0, 0.3, 1e-4);
+        let mut four_bar = FourBarLinkage::new_basic(-1.5, 0.5, 1.0, 3.0 * PI)
+            .with_dynamics(tau_s, rate_radps);
+        let mut instant = FourBarLinkage::new_basic(-1.5, 0.5, 1.0, 3.0 * PI);
+        instant.set_servo_angle_rad(step_rad);
+        assert_relative_eq!(four_bar.get_servo_angle_rad(), 0.0, epsilon = 1e-12);
+
+        let mut time_s = 0.0;
+        let mut last_rad = 0.0;
+        while time_s < 1.0{
+            four_bar.step(step_rad, dt);
+            time_s += dt;
+
+            let servo_rad = four_bar.get_servo_angle_rad();
+            assert!(servo_rad - last_rad <= (rate_radps * dt) + 1e-12);
+            last_rad = servo_rad;
+
+            if (time_s - 0.05).abs() < dt / 2.0{
+                // Still slewing
+                assert_relative_eq!(servo_rad, rate_radps * 0.05, max_relative = 1e-6);
+                assert!(four_bar.get_output_deflection_rad().abs() < instant.get_output_deflection_rad().abs());
+            } else if (time_s - 0.2).abs() < dt / 2.0{
+                // One time constant past the end of the slew
+                let expected_rad = step_rad - (tau_s * rate_radps * (-(0.2 - 0.1) / tau_s).exp());
+                assert_relative_eq!(servo_rad, expected_rad, max_relative = 1e-3);
+            }
+        }
+
+        assert_relative_eq!(four_bar.get_servo_angle_rad(), step_rad, epsilon = 1e-5);
+        assert_relative_eq!(
+            four_bar.get_output_deflection_rad(),
+            instant.get_output_deflection_rad(),
+            epsilon = 1e-5
+        );
+    }
+
+    #[test]
+    fn velocity_kinematics(){
+        let mut four_bar = FourBarLinkage::new_basic(-1.5, 0.5, 1.0, 3.0 * PI);
+
+        // Input and output links are parallel, the coupler moves in pure
+        // translation so w_b / w_a = a / b
+        assert_relative_eq!(
+            four_bar.get_output_velocity_radps(1.0),
+            four_bar.a.length_m() / four_bar.b.length_m(),
+            max_relative = 1e-9
+        );
+
+        // Compare against a central difference over a sweep
+        let h = 1e-5;
+        let mut input_angle_rad = -1.0;
+        while input_angle_rad <= 1.0{
+            four_bar.set_servo_angle_rad(input_angle_rad - h);
+            let before = output_link_angle_rad(&four_bar);
+            four_bar.set_servo_angle_rad(input_angle_rad + h);
+            let after = output_link_angle_rad(&four_bar);
+            four_bar.set_servo_angle_rad(input_angle_rad);
+
+            let numerical = (after - before) / (2.0 * h);
+            assert_relative_eq!(
+                four_bar.get_output_velocity_radps(1.0),
+                numerical,
+                epsilon = 1e-6,
+                max_relative = 1e-4
+            );
+
+            input_angle_rad += 0.05;
+        }
+    }
+
     #[test]
     fn sin_sweep(){
         let mut runtime = sim::Runtime::new(PI * 2.0, 1e-2, "angle [rad]");
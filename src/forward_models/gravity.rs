@@ -0,0 +1,99 @@
+use crate::geo;
+
+// Source:
+//    https://en.wikipedia.org/wiki/Standard_gravitational_parameter
+pub const EARTH_MU_M3PS2: f64 = 3.986004418e14;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gravity{
+    Constant(geo::Vector3),
+    PointMass(f64)
+}
+
+impl Gravity{
+    pub fn new_constant(g_mps2: geo::Vector3) -> Gravity{
+        // Flat earth, the same acceleration everywhere
+        return Gravity::Constant(g_mps2)
+    }
+
+    pub fn new_point_mass(mu_m3ps2: f64) -> Gravity{
+        // Inverse square toward the origin of the inertial frame
+        if mu_m3ps2 <= 0.0{
+            panic!("    ERROR| Gravitational parameter [{}] must be positive", mu_m3ps2)
+        }
+        return Gravity::PointMass(mu_m3ps2)
+    }
+
+    pub fn accel_at(&self, pos_m: geo::Vector3) -> geo::Vector3{
+        match self{
+            Gravity::Constant(g_mps2) => return *g_mps2,
+            Gravity::PointMass(mu_m3ps2) => {
+                // -mu * r / |r|^3, undefined at the origin
+                let radius_m = pos_m.norm();
+                if radius_m == 0.0{
+                    panic!("    ERROR| Point mass gravity is undefined at the origin")
+                }
+                return pos_m * (-mu_m3ps2 / radius_m.powi(3))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::RigidBody;
+    use crate::sim::Integrate;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn circular_orbit(){
+        let gravity = Gravity::new_point_mass(EARTH_MU_M3PS2);
+        let radius_m = 7.0e6;
+        let speed_mps = (EARTH_MU_M3PS2 / radius_m).sqrt();
+
+        // Centripetal v^2 / r toward the center
+        let accel_mps2 = gravity.accel_at(geo::Vector3::new(0.0, radius_m, 0.0));
+        assert_relative_eq!(accel_mps2.norm(), speed_mps.powi(2) / radius_m, max_relative = 1e-12);
+        assert_relative_eq!(accel_mps2.j, -accel_mps2.norm());
+
+        // Quarter of an orbit keeps the radius, the force is held over each
+        // step so this is only first order in dt
+        let mut body = RigidBody::new(
+            [0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0],
+            [radius_m, 0.0, 0.0],
+            [0.0, speed_mps, 0.0],
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0], [0.0, 0.0, 0.0],
+            10.0,
+            [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]
+        );
+        let period_s = 2.0 * std::f64::consts::PI * radius_m / speed_mps;
+        let dt = 0.1;
+        for _ in 0..(period_s / 4.0 / dt) as usize{
+            body.intertial_force_n = gravity.accel_at(body.get_intertial_pos_m()) * body.mass_cg_kg;
+            body = body.rk4(dt);
+        }
+        assert_relative_eq!(body.get_intertial_pos_m().norm(), radius_m, max_relative = 1e-4);
+        assert!(body.get_intertial_pos_m().j > 0.99 * radius_m);
+    }
+
+    #[test]
+    fn constant_equivalence(){
+        // Same as a fixed -9.8 N on a 1 kg body
+        let gravity = Gravity::new_constant(geo::Vector3::new(0.0, 0.0, -9.8));
+        assert_eq!(gravity.accel_at(geo::Vector3::new(1e6, -3.0, 2.0)), geo::Vector3::new(0.0, 0.0, -9.8));
+
+        let mut fixed = RigidBody::identity();
+        fixed.intertial_force_n = geo::Vector3::new(0.0, 0.0, -9.8);
+        let mut field = RigidBody::identity();
+        for _ in 0..100{
+            fixed = fixed.rk4(1e-2);
+            field.intertial_force_n = gravity.accel_at(field.get_intertial_pos_m()) * field.mass_cg_kg;
+            field = field.rk4(1e-2);
+        }
+        assert_eq!(field.get_intertial_pos_m(), fixed.get_intertial_pos_m());
+        assert_relative_eq!(field.get_intertial_pos_m().k, -0.5 * 9.8, max_relative = 1e-9);
+    }
+}
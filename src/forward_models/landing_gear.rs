@@ -0,0 +1,217 @@
+use crate::{geo, sim, physics::RigidBody};
+use super::terrain::{Terrain, FlatTerrain};
+
+struct LandingLeg{
+    attach_m: geo::Vector3,
+    direction: geo::Vector3,
+    length_m: f64,
+    stiffness_npm: f64,
+    damping_nspm: f64,
+    compression_m: f64,
+    normal_force_n: f64
+}
+
+pub struct LandingGear{
+    legs: Vec<LandingLeg>,
+    ground_height_m: f64,
+    force_n: geo::Vector3,
+    moment_nm: geo::Vector3
+}
+
+impl LandingGear{
+    pub fn new(ground_height_m: f64) -> LandingGear{
+        // Flat ground at a fixed inertial z
+        return LandingGear{
+            legs: Vec::new(),
+            ground_height_m,
+            force_n: geo::Vector3::zeros(),
+            moment_nm: geo::Vector3::zeros()
+        }
+    }
+
+    pub fn add_leg(
+        &mut self,
+        attach_m: [f64; 3],
+        direction: [f64; 3],
+        length_m: f64,
+        stiffness_npm: f64,
+        damping_nspm: f64
+    ) -> usize{
+        // Attachment and leg direction are in the body frame relative to the cg
+        let direction = geo::Vector3::from_array(direction);
+        if direction.norm() == 0.0{
+            panic!("    ERROR| Landing leg direction must be non-zero")
+        }
+
+        self.legs.push(LandingLeg{
+            attach_m: geo::Vector3::from_array(attach_m),
+            direction: direction.to_unit(),
+            length_m,
+            stiffness_npm,
+            damping_nspm,
+            compression_m: 0.0,
+            normal_force_n: 0.0
+        });
+        return self.legs.len() - 1
+    }
+
+    pub fn update(&mut self, body: &RigidBody){
+        // Each foot is a spring-damper normal to the ground, the ground can
+        // only push and there is no friction
+        self.update_over(body, &FlatTerrain::new(self.ground_height_m));
+    }
+
+    pub fn update_over(&mut self, body: &RigidBody, terrain: &dyn Terrain){
+        // Ground height is taken under each foot, the force stays vertical
+        // so this is only a fair model for gentle slopes
+        let quat = body.get_quat();
+        let up = geo::Vector3::new(0.0, 0.0, 1.0);
+
+        self.force_n = geo::Vector3::zeros();
+        self.moment_nm = geo::Vector3::zeros();
+
+        for leg in self.legs.iter_mut(){
+            let foot_body_m = leg.attach_m + (leg.direction * leg.length_m);
+            let foot_offset_m = quat.transform(foot_body_m);
+            let foot_pos_m = body.get_intertial_pos_m() + foot_offset_m;
+
+            // v_p = v + (w x r)
+            let foot_vel_mps = body.get_intertial_vel_mps()
+                + quat.transform(body.get_body_ang_vel_radps().cross(&foot_body_m));
+
+            let ground_height_m = terrain.height_at(foot_pos_m.i, foot_pos_m.j);
+            leg.compression_m = (ground_height_m - foot_pos_m.k).max(0.0);
+            leg.normal_force_n = if leg.compression_m > 0.0{
+                ((leg.stiffness_npm * leg.compression_m)
+                    - (leg.damping_nspm * foot_vel_mps.k)).max(0.0)
+            } else{
+                0.0
+            };
+
+            let force_n = up * leg.normal_force_n;
+            self.force_n += force_n;
+            self.moment_nm += foot_offset_m.cross(&force_n);
+        }
+    }
+
+    pub fn get_force_n(&self) -> geo::Vector3{
+        // Inertial frame
+        return self.force_n
+    }
+
+    pub fn get_moment_nm(&self) -> geo::Vector3{
+        // Inertial frame, about the cg
+        return self.moment_nm
+    }
+
+    pub fn is_in_contact(&self) -> bool{
+        return self.legs.iter().any(|leg| leg.compression_m > 0.0)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for LandingGear{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        runtime.add_vector3(format!("{node_name}.force").as_str(), "N", self.force_n);
+        runtime.add_vector3(format!("{node_name}.moment").as_str(), "Nm", self.moment_nm);
+    }
+
+    fn save_data_verbose(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        self.save_data(node_name, runtime);
+        for (i, leg) in self.legs.iter().enumerate(){
+            runtime.add_or_set(format!(
+                "{node_name}.leg{i}.compression [m]").as_str(),leg.compression_m
+            );
+            runtime.add_or_set(format!(
+                "{node_name}.leg{i}.normal_force [N]").as_str(),leg.normal_force_n
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forward_models::SlopedTerrain;
+    use crate::sim::Integrate;
+    use approx::assert_relative_eq;
+
+    fn four_legs() -> LandingGear{
+        let mut gear = LandingGear::new(0.0);
+        for attach in [[0.5, 0.5, 0.0], [-0.5, 0.5, 0.0], [-0.5, -0.5, 0.0], [0.5, -0.5, 0.0]]{
+            gear.add_leg(attach, [0.0, 0.0, -1.0], 1.0, 1000.0, 50.0);
+        }
+        return gear
+    }
+
+    fn body_at(height_m: f64, mass_kg: f64) -> RigidBody{
+        let mut state = [0.0; 13];
+        state[2] = height_m;
+        state[6] = 1.0;
+        return RigidBody::from_state_array(
+            state,
+            mass_kg,
+            geo::Matrix3x3::identity().to_array()
+        )
+    }
+
+    #[test]
+    fn static_compression(){
+        let mut gear = four_legs();
+        let body = body_at(0.9, 1.0);
+
+        gear.update(&body);
+        assert!(gear.is_in_contact());
+        assert_relative_eq!(gear.get_force_n().k, 4.0 * 1000.0 * 0.1, max_relative = 1e-9);
+        assert_relative_eq!(gear.get_moment_nm().norm(), 0.0, epsilon = 1e-12);
+
+        // Off the ground
+        gear.update(&body_at(1.5, 1.0));
+        assert!(!gear.is_in_contact());
+        assert_eq!(gear.get_force_n().norm(), 0.0);
+    }
+
+    #[test]
+    fn sloped_ground(){
+        // Ground rising in x, the +x feet touch down first
+        let mut gear = four_legs();
+        let terrain = SlopedTerrain::new(0.0, 0.1, 0.0);
+        gear.update_over(&body_at(1.0, 1.0), &terrain);
+        assert!(gear.is_in_contact());
+        assert_relative_eq!(gear.get_force_n().k, 2.0 * 1000.0 * 0.05, max_relative = 1e-9);
+        assert!(gear.get_moment_nm().j < 0.0);
+
+        // Flat terrain is the same as the fixed ground height
+        let mut flat = four_legs();
+        flat.update_over(&body_at(0.9, 1.0), &FlatTerrain::new(0.0));
+        gear.update(&body_at(0.9, 1.0));
+        assert_eq!(flat.get_force_n(), gear.get_force_n());
+    }
+
+    #[test]
+    fn symmetric_landing(){
+        let mass_kg = 10.0;
+        let gravity_n = geo::Vector3::new(0.0, 0.0, -9.8 * mass_kg);
+        let dt = 1e-3;
+
+        let mut gear = four_legs();
+        let mut body = body_at(1.2, mass_kg);
+
+        for _ in 0..(10.0 / dt) as usize{
+            gear.update(&body);
+            body.intertial_force_n = gravity_n + gear.get_force_n();
+            body.intertial_moment_nm = gear.get_moment_nm();
+            assert_relative_eq!(gear.get_moment_nm().norm(), 0.0, epsilon = 1e-9);
+
+            body = body.rk4(dt);
+        }
+
+        // At rest the legs hold the weight
+        gear.update(&body);
+        assert_relative_eq!(gear.get_force_n().k, -gravity_n.k, max_relative = 1e-6);
+        assert_relative_eq!(body.get_intertial_pos_m().k, 1.0 - (98.0 / 4000.0), max_relative = 1e-6);
+    }
+}
@@ -3,4 +3,28 @@
 pub mod four_bar_linkage;
 pub use four_bar_linkage::FourBarLinkage;
 pub mod basic_tvc;
-pub use basic_tvc::BasicTVC;
\ No newline at end of file
+pub use basic_tvc::BasicTVC;
+pub mod tabulated_thrust;
+pub use tabulated_thrust::TabulatedThrust;
+pub mod landing_gear;
+pub use landing_gear::LandingGear;
+pub mod dual_axis_tvc;
+pub use dual_axis_tvc::DualAxisTVC;
+pub mod thrust_curve;
+pub use thrust_curve::ThrustCurve;
+pub mod propellant_model;
+pub use propellant_model::PropellantModel;
+pub mod atmosphere;
+pub use atmosphere::Atmosphere;
+pub mod drag_model;
+pub use drag_model::DragModel;
+pub mod gravity;
+pub use gravity::Gravity;
+pub mod nutation_damper;
+pub use nutation_damper::NutationDamper;
+pub mod reaction_wheel;
+pub use reaction_wheel::ReactionWheel;
+pub mod terrain;
+pub use terrain::{Terrain, FlatTerrain, SlopedTerrain};
+pub mod cold_gas_thruster;
+pub use cold_gas_thruster::ColdGasThruster;
\ No newline at end of file
@@ -0,0 +1,108 @@
+use crate::{geo, sim, physics::RigidBody};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NutationDamper{
+    spin_axis: geo::Vector3,
+    damping_nms: f64,
+    moment_nm: geo::Vector3
+}
+
+impl NutationDamper{
+    pub fn new(spin_axis: [f64; 3], damping_nms: f64) -> NutationDamper{
+        // Viscous damper about the body frame spin axis, only the rate
+        // transverse to the axis is damped
+        let spin_axis = geo::Vector3::from_array(spin_axis);
+        if spin_axis.norm() == 0.0{
+            panic!("    ERROR| Nutation damper spin axis must be non-zero")
+        }
+        if damping_nms < 0.0{
+            panic!("    ERROR| Nutation damper coefficient [{}] must be non-negative", damping_nms)
+        }
+        return NutationDamper{
+            spin_axis: spin_axis.to_unit(),
+            damping_nms,
+            moment_nm: geo::Vector3::zeros()
+        }
+    }
+
+    pub fn update(&mut self, body: &RigidBody) -> geo::Vector3{
+        // -c * w_t with w_t = w - (w . s) s
+        let ang_vel_radps = body.get_body_ang_vel_radps();
+        let transverse_radps = ang_vel_radps - (self.spin_axis * ang_vel_radps.dot(&self.spin_axis));
+        self.moment_nm = transverse_radps * -self.damping_nms;
+        return self.moment_nm
+    }
+
+    pub fn get_moment_nm(&self) -> geo::Vector3{
+        // Body frame
+        return self.moment_nm
+    }
+
+    pub fn get_power_w(&self, body: &RigidBody) -> f64{
+        // Rate energy is removed at, never positive
+        return self.moment_nm.dot(&body.get_body_ang_vel_radps())
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for NutationDamper{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        runtime.add_vector3(format!("{node_name}.moment").as_str(), "Nm", self.moment_nm);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::Integrate;
+    use approx::assert_relative_eq;
+
+    fn spinner(ang_vel_radps: [f64; 3]) -> RigidBody{
+        // Oblate, spin about the major axis is stable
+        let mut state = [0.0; 13];
+        state[6] = 1.0;
+        state[10..13].copy_from_slice(&ang_vel_radps);
+        return RigidBody::from_state_array(
+            state,
+            10.0,
+            [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 2.0]
+        )
+    }
+
+    fn transverse_radps(body: &RigidBody) -> f64{
+        let ang_vel_radps = body.get_body_ang_vel_radps();
+        return (ang_vel_radps.i.powi(2) + ang_vel_radps.j.powi(2)).sqrt()
+    }
+
+    #[test]
+    fn pure_spin(){
+        let mut damper = NutationDamper::new([0.0, 0.0, 1.0], 0.1);
+        damper.update(&spinner([0.0, 0.0, 5.0]));
+        assert_eq!(damper.get_moment_nm(), geo::Vector3::zeros());
+    }
+
+    #[test]
+    fn nutation_decay(){
+        let mut damper = NutationDamper::new([0.0, 0.0, 1.0], 0.1);
+        let mut body = spinner([0.1, 0.0, 5.0]);
+        let initial_transverse_radps = transverse_radps(&body);
+
+        // Transverse rate decays as exp(-c / I_t * t) = exp(-2) over 20 s
+        let dt = 1e-3;
+        for _ in 0..(20.0 / dt) as usize{
+            body.body_moment_nm = damper.update(&body);
+            assert!(damper.get_power_w(&body) <= 0.0);
+            body = body.rk4(dt);
+        }
+
+        assert_relative_eq!(
+            transverse_radps(&body) / initial_transverse_radps,
+            (-2.0_f64).exp(),
+            max_relative = 1e-3
+        );
+        assert_relative_eq!(body.get_body_ang_vel_radps().k, 5.0, max_relative = 1e-9);
+    }
+}
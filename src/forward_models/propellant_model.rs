@@ -0,0 +1,118 @@
+use crate::{geo, sim};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PropellantModel{
+    initial_prop_kg: f64,
+    mdot_kgps: f64
+}
+
+impl PropellantModel{
+    pub fn new(initial_prop_kg: f64, mdot_kgps: f64) -> PropellantModel{
+        // Constant mass flow from t = 0 until the propellant runs out
+        if initial_prop_kg < 0.0 || mdot_kgps < 0.0{
+            panic!(
+                "    ERROR| Propellant mass [{}] and mass flow [{}] must be non-negative",
+                initial_prop_kg,
+                mdot_kgps
+            )
+        }
+        return PropellantModel{initial_prop_kg, mdot_kgps}
+    }
+
+    pub fn mass_at(&self, time_s: f64) -> f64{
+        // Propellant remaining, full before t = 0 and empty after burnout
+        return (self.initial_prop_kg - (self.mdot_kgps * time_s.max(0.0))).max(0.0)
+    }
+
+    pub fn get_burn_time_s(&self) -> f64{
+        return self.initial_prop_kg / self.mdot_kgps
+    }
+
+    pub fn is_depleted(&self, time_s: f64) -> bool{
+        return self.mass_at(time_s) == 0.0
+    }
+
+    pub fn mass_properties_at(
+        &self,
+        time_s: f64,
+        dry_mass_kg: f64,
+        wet_i_tensor_kgpm2: geo::Matrix3x3
+    ) -> (f64, geo::Matrix3x3){
+        // Total mass and inertia tensor, the tensor is scaled by mass from
+        // its fully fuelled value so the cg and mass distribution are fixed
+        let wet_mass_kg = dry_mass_kg + self.initial_prop_kg;
+        let mass_kg = dry_mass_kg + self.mass_at(time_s);
+        return (mass_kg, wet_i_tensor_kgpm2 * (mass_kg / wet_mass_kg))
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for PropellantModel{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        // Sampled at the runtime's current x
+        runtime.add_or_set(format!(
+            "{node_name}.prop_mass [kg]").as_str(),self.mass_at(runtime.get_x())
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::RigidBody;
+    use crate::sim::Integrate;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn mass_depletion(){
+        let propellant = PropellantModel::new(2.0, 0.5);
+        assert_eq!(propellant.mass_at(-1.0), 2.0);
+        assert_eq!(propellant.mass_at(1.0), 1.5);
+        assert_eq!(propellant.mass_at(10.0), 0.0);
+        assert_eq!(propellant.get_burn_time_s(), 4.0);
+        assert!(propellant.is_depleted(4.0));
+
+        let wet_i_tensor = geo::Matrix3x3::identity() * 3.0;
+        let (mass_kg, i_tensor) = propellant.mass_properties_at(2.0, 1.0, wet_i_tensor);
+        assert_eq!(mass_kg, 2.0);
+        assert_relative_eq!(i_tensor.c11, 2.0);
+        assert_eq!(i_tensor.c12, 0.0);
+    }
+
+    #[test]
+    fn constant_thrust_burn(){
+        let dt = 1e-2;
+        let thrust_n = geo::Vector3::new(0.0, 0.0, 50.0);
+        let dry_mass_kg = 1.0;
+        let wet_i_tensor = geo::Matrix3x3::identity();
+        let propellant = PropellantModel::new(2.0, 0.5);
+
+        let mut body = RigidBody::identity();
+        let mut last_accel_mps2 = 0.0;
+        let mut time_s = 0.0;
+        while time_s < 6.0{
+            let (mass_kg, i_tensor) = propellant.mass_properties_at(time_s, dry_mass_kg, wet_i_tensor);
+            body.set_mass_properties(mass_kg, i_tensor);
+            body.intertial_force_n = thrust_n;
+            body = body.rk4(dt);
+
+            // Rises during the burn and holds once the propellant is gone
+            let accel_mps2 = body.get_accel_mps2().k;
+            if propellant.is_depleted(time_s){
+                assert_relative_eq!(accel_mps2, thrust_n.k / dry_mass_kg);
+            } else{
+                assert!(accel_mps2 > last_accel_mps2);
+            }
+            assert_relative_eq!(accel_mps2, thrust_n.k / mass_kg);
+            assert_relative_eq!(body.get_i_tensor_cg_kgpm2().c11, mass_kg / 3.0);
+
+            last_accel_mps2 = accel_mps2;
+            time_s += dt;
+        }
+
+        assert_relative_eq!(last_accel_mps2, thrust_n.k / dry_mass_kg);
+    }
+}
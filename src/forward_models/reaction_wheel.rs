@@ -0,0 +1,156 @@
+use crate::{geo, sim, control};
+
+pub struct ReactionWheel{
+    axis: geo::Vector3,
+    inertia_kgpm2: f64,
+    max_torque_nm: f64,
+    max_speed_radps: f64,
+    speed_radps: f64,
+    torque_clamped: control::ClampedValue,
+    torque_nm: f64
+}
+
+impl ReactionWheel{
+    pub fn new(
+        axis: geo::Vector3,
+        inertia_kgpm2: f64,
+        max_torque_nm: f64,
+        max_speed_radps: f64
+    ) -> ReactionWheel{
+        // Spin axis in the body frame, the wheel starts at rest
+        if axis.norm() == 0.0{
+            panic!("    ERROR| Reaction wheel axis must be non-zero")
+        }
+        if inertia_kgpm2 <= 0.0 || max_torque_nm <= 0.0 || max_speed_radps <= 0.0{
+            panic!(
+                "    ERROR| Reaction wheel inertia [{}], torque [{}] and speed [{}] limits must be positive",
+                inertia_kgpm2,
+                max_torque_nm,
+                max_speed_radps
+            )
+        }
+        return ReactionWheel{
+            axis: axis.to_unit(),
+            inertia_kgpm2,
+            max_torque_nm,
+            max_speed_radps,
+            speed_radps: 0.0,
+            torque_clamped: control::ClampedValue{value: 0.0, saturated_high: false, saturated_low: false},
+            torque_nm: 0.0
+        }
+    }
+
+    pub fn set_torque_nm(&mut self, torque_nm: f64){
+        // Torque on the wheel about its axis, clamped to the motor limit
+        self.torque_clamped = control::clamp_ex(torque_nm, self.max_torque_nm, -self.max_torque_nm);
+    }
+
+    pub fn step(&mut self, dt: f64){
+        // No torque that would spin the wheel past its max speed
+        let torque_nm = self.torque_clamped.value;
+        let at_limit = (self.speed_radps >= self.max_speed_radps && torque_nm > 0.0)
+            || (self.speed_radps <= -self.max_speed_radps && torque_nm < 0.0);
+        self.torque_nm = if at_limit {0.0} else {torque_nm};
+
+        self.speed_radps += self.torque_nm / self.inertia_kgpm2 * dt;
+    }
+
+    pub fn get_reaction_moment_nm(&self) -> geo::Vector3{
+        // Equal and opposite moment on the body, body frame
+        return self.axis * -self.torque_nm
+    }
+
+    pub fn get_speed_radps(&self) -> f64{
+        return self.speed_radps
+    }
+
+    pub fn get_momentum_nms(&self) -> geo::Vector3{
+        // Wheel angular momentum relative to the body, body frame
+        return self.axis * (self.inertia_kgpm2 * self.speed_radps)
+    }
+
+    pub fn is_saturated(&self) -> bool{
+        // Either the torque command or the speed is at its limit
+        return self.torque_clamped.is_saturated() || self.torque_nm != self.torque_clamped.value
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for ReactionWheel{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        runtime.add_or_set(format!(
+            "{node_name}.torque [Nm]").as_str(),self.torque_nm
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.speed [rad/s]").as_str(),self.speed_radps
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.saturated [-]").as_str(),self.is_saturated() as u8 as f64
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::RigidBody;
+    use crate::sim::Integrate;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn momentum_exchange(){
+        // Spin about a principal axis so there is no gyroscopic coupling
+        let mut wheel = ReactionWheel::new(geo::Vector3::new(0.0, 0.0, 2.0), 0.01, 0.05, 500.0);
+        let mut body = RigidBody::identity();
+        body.set_mass_properties(5.0, geo::Matrix3x3::new(
+            0.4, 0.0, 0.0,
+            0.0, 0.4, 0.0,
+            0.0, 0.0, 0.2
+        ));
+
+        let dt = 1e-3;
+        wheel.set_torque_nm(0.02);
+        for _ in 0..5000{
+            wheel.step(dt);
+            body.body_moment_nm = wheel.get_reaction_moment_nm();
+            body = body.rk4(dt);
+
+            let total_nms = body.angular_momentum_nms() + wheel.get_momentum_nms();
+            assert!(total_nms.norm() < 1e-12);
+        }
+
+        // 0.1 Nms shared out
+        assert_relative_eq!(wheel.get_speed_radps(), 0.02 * 5.0 / 0.01, max_relative = 1e-9);
+        assert_relative_eq!(body.get_body_ang_vel_radps().k, -0.02 * 5.0 / 0.2, max_relative = 1e-9);
+        assert!(!wheel.is_saturated());
+    }
+
+    #[test]
+    fn saturation(){
+        let mut wheel = ReactionWheel::new(geo::Vector3::new(1.0, 0.0, 0.0), 0.01, 0.05, 10.0);
+
+        // Clamped command
+        wheel.set_torque_nm(1.0);
+        wheel.step(0.1);
+        assert_relative_eq!(wheel.get_speed_radps(), 0.5, max_relative = 1e-12);
+        assert_eq!(wheel.get_reaction_moment_nm(), geo::Vector3::new(-0.05, 0.0, 0.0));
+        assert!(wheel.is_saturated());
+
+        // Speed limit holds the wheel and drops the body moment
+        for _ in 0..100{
+            wheel.step(0.1);
+        }
+        assert!(wheel.get_speed_radps() >= 10.0);
+        assert!(wheel.get_speed_radps() < 10.0 + 0.5);
+        assert_eq!(wheel.get_reaction_moment_nm(), geo::Vector3::zeros());
+
+        // Braking is still allowed
+        wheel.set_torque_nm(-0.01);
+        wheel.step(0.1);
+        assert_relative_eq!(wheel.get_reaction_moment_nm().i, 0.01);
+        assert!(!wheel.is_saturated());
+    }
+}
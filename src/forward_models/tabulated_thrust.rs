@@ -0,0 +1,117 @@
+use crate::{sim, units, forward_models::BasicTVC};
+
+pub struct TabulatedThrust{
+    curve: units::Lookup1D,
+    ignition_time_s: Option<f64>,
+    time_since_ignition_s: f64,
+    thrust_n: f64
+}
+
+impl TabulatedThrust{
+    pub fn new(curve: units::Lookup1D) -> TabulatedThrust{
+        // Curve of thrust [N] against time since ignition [s]
+        return TabulatedThrust{
+            curve,
+            ignition_time_s: None,
+            time_since_ignition_s: 0.0,
+            thrust_n: 0.0
+        }
+    }
+
+    pub fn ignite(&mut self, time_s: f64){
+        self.ignition_time_s = Some(time_s);
+    }
+
+    pub fn update(&mut self, time_s: f64) -> f64{
+        // No thrust before ignition or after the end of the curve
+        self.thrust_n = match self.ignition_time_s{
+            Some(ignition_time_s) if time_s >= ignition_time_s => {
+                self.time_since_ignition_s = time_s - ignition_time_s;
+                if self.is_burnt_out(){
+                    0.0
+                } else{
+                    self.curve.sample(self.time_since_ignition_s)
+                }
+            },
+            _ => 0.0
+        };
+        return self.thrust_n
+    }
+
+    pub fn apply(&self, tvc: &mut BasicTVC){
+        tvc.set_thrust_n(self.thrust_n);
+    }
+
+    pub fn is_burnt_out(&self) -> bool{
+        let burn_time_s = self.curve.get_x()[self.curve.get_x().len() - 1];
+        return self.ignition_time_s.is_some() && self.time_since_ignition_s > burn_time_s
+    }
+
+    pub fn get_thrust_n(&self) -> f64{
+        return self.thrust_n
+    }
+
+    pub fn get_total_impulse_ns(&self) -> f64{
+        return self.curve.integrate()
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for TabulatedThrust{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        runtime.add_or_set(format!(
+            "{node_name}.thrust [N]").as_str(),self.thrust_n
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.time_since_ignition [s]").as_str(),self.time_since_ignition_s
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::Save;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn total_impulse(){
+        // Approximate hobby C motor
+        let curve = units::Lookup1D::new(
+            vec![0.0, 0.05, 0.2, 0.3, 0.5, 1.0, 1.7, 1.85],
+            vec![0.0, 5.0, 14.0, 6.0, 4.5, 4.5, 4.2, 0.0]
+        ).unwrap();
+
+        let mut motor = TabulatedThrust::new(curve);
+        let mut tvc = BasicTVC::new(0.0, [0.0, 0.0, -0.5], 0.0, 0.0, 0.1, 20.0);
+        let mut runtime = sim::Runtime::new(4.0, 1e-3, "time [s]");
+        let dt = runtime.get_dx();
+
+        // Trapezoidal integration of the thrust seen by the TVC
+        let mut impulse_ns = 0.0;
+        let mut last_thrust_n = 0.0;
+        while runtime.is_running{
+            let time_s = runtime.get_x();
+            if time_s >= 1.0 && motor.ignition_time_s.is_none(){
+                motor.ignite(time_s);
+            }
+
+            motor.update(time_s);
+            motor.apply(&mut tvc);
+
+            let thrust_n = tvc.get_thrust_vec_n().norm();
+            impulse_ns += dt * (thrust_n + last_thrust_n) / 2.0;
+            last_thrust_n = thrust_n;
+
+            motor.save_data("motor", &mut runtime);
+            runtime.increment();
+        }
+
+        assert!(motor.is_burnt_out());
+        assert_eq!(motor.get_thrust_n(), 0.0);
+        assert_relative_eq!(impulse_ns, motor.get_total_impulse_ns(), max_relative = 1e-3);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::physics::RigidBody;
+
+pub trait Terrain{
+    // Ground height in the inertial z at an inertial x, y
+    fn height_at(&self, x_m: f64, y_m: f64) -> f64;
+
+    fn height_above_m(&self, body: &RigidBody) -> f64{
+        // Vertical distance from the ground to the cg, negative below it
+        let pos_m = body.get_intertial_pos_m();
+        return pos_m.k - self.height_at(pos_m.i, pos_m.j)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlatTerrain{
+    pub height_m: f64
+}
+
+impl FlatTerrain{
+    pub fn new(height_m: f64) -> FlatTerrain{
+        return FlatTerrain{height_m}
+    }
+}
+
+impl Terrain for FlatTerrain{
+    fn height_at(&self, _x_m: f64, _y_m: f64) -> f64{
+        return self.height_m
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlopedTerrain{
+    pub height_m: f64,
+    pub slope_x: f64,
+    pub slope_y: f64
+}
+
+impl SlopedTerrain{
+    pub fn new(height_m: f64, slope_x: f64, slope_y: f64) -> SlopedTerrain{
+        // Plane through height_m at the origin, slopes are rise over run
+        return SlopedTerrain{height_m, slope_x, slope_y}
+    }
+}
+
+impl Terrain for SlopedTerrain{
+    fn height_at(&self, x_m: f64, y_m: f64) -> f64{
+        return self.height_m + (self.slope_x * x_m) + (self.slope_y * y_m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn body_at(pos_m: [f64; 3]) -> RigidBody{
+        let mut state = [0.0; 13];
+        state[..3].copy_from_slice(&pos_m);
+        state[6] = 1.0;
+        return RigidBody::from_state_array(state, 1.0, [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0])
+    }
+
+    #[test]
+    fn flat(){
+        let terrain = FlatTerrain::new(0.0);
+        for pos_m in [[0.0, 0.0, 3.0], [150.0, -20.0, 12.5], [-4.0, 8.0, -1.0]]{
+            assert_eq!(terrain.height_above_m(&body_at(pos_m)), pos_m[2]);
+        }
+        assert_eq!(FlatTerrain::new(2.0).height_above_m(&body_at([5.0, 5.0, 3.0])), 1.0);
+    }
+
+    #[test]
+    fn slope(){
+        // Rises 1 m per 10 m in x and falls 1 m per 20 m in y
+        let terrain = SlopedTerrain::new(1.0, 0.1, -0.05);
+        assert_relative_eq!(terrain.height_at(10.0, 0.0), 2.0);
+        assert_relative_eq!(terrain.height_at(0.0, 20.0), 0.0);
+
+        let body = body_at([30.0, 40.0, 10.0]);
+        assert_relative_eq!(terrain.height_above_m(&body), 10.0 - (1.0 + 3.0 - 2.0), epsilon = 1e-12);
+
+        // Same absolute altitude, different clearance
+        assert!(terrain.height_above_m(&body_at([50.0, 0.0, 10.0])) < terrain.height_above_m(&body_at([0.0, 0.0, 10.0])));
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{sim, units};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThrustCurve{
+    table: units::Lookup1D
+}
+
+impl ThrustCurve{
+    pub fn new(time_s: Vec<f64>, thrust_n: Vec<f64>) -> Result<ThrustCurve, units::LookupError>{
+        // Times must be strictly increasing, empty curves are rejected
+        return Ok(ThrustCurve{
+            table: units::Lookup1D::new(time_s, thrust_n)?
+        })
+    }
+
+    pub fn thrust_at(&self, time_s: f64) -> f64{
+        // Linear interpolation, clamped to the first and last points
+        return self.table.sample(time_s)
+    }
+
+    pub fn get_burn_time_s(&self) -> f64{
+        let time_s = self.table.get_x();
+        return time_s[time_s.len() - 1] - time_s[0]
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for ThrustCurve{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        // Sampled at the runtime's current x
+        runtime.add_or_set(format!(
+            "{node_name}.thrust [N]").as_str(),self.thrust_at(runtime.get_x())
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::Save;
+    use approx::assert_relative_eq;
+
+    fn curve() -> ThrustCurve{
+        return ThrustCurve::new(
+            vec![0.0, 0.1, 0.5, 1.0],
+            vec![0.0, 20.0, 10.0, 8.0]
+        ).unwrap()
+    }
+
+    #[test]
+    fn interpolation(){
+        let curve = curve();
+        assert_eq!(curve.thrust_at(0.1), 20.0);
+        assert_relative_eq!(curve.thrust_at(0.05), 10.0);
+        assert_relative_eq!(curve.thrust_at(0.3), 15.0);
+        assert_relative_eq!(curve.thrust_at(0.75), 9.0);
+        assert_relative_eq!(curve.get_burn_time_s(), 1.0);
+
+        let mut runtime = sim::Runtime::new(1.0, 0.25, "time [s]");
+        runtime.increment();
+        curve.save_data("motor", &mut runtime);
+        assert_relative_eq!(runtime.get_value("motor.thrust [N]"), curve.thrust_at(runtime.get_x()));
+    }
+
+    #[test]
+    fn end_clamping(){
+        let curve = curve();
+        assert_eq!(curve.thrust_at(-1.0), 0.0);
+        assert_eq!(curve.thrust_at(1.0), 8.0);
+        assert_eq!(curve.thrust_at(100.0), 8.0);
+    }
+
+    #[test]
+    fn empty_curve(){
+        assert!(matches!(
+            ThrustCurve::new(Vec::new(), Vec::new()),
+            Err(units::LookupError::BadShape(_))
+        ));
+    }
+}
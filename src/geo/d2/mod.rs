@@ -77,7 +77,28 @@ impl Vector2{
     }
 
     pub fn get_perpendicular(&self) -> Vector2{
-        // Returns a perpindicular vector
+        // Returns a perpindicular vector, same as rotate_90_cw
+        return Vector2::new(self.j, -self.i)
+    }
+
+    pub fn rotate(&self, angle_rad: f64) -> Vector2{
+        // Counter-clockwise rotation [cos, -sin; sin, cos]
+        // Source:
+        //    https://en.wikipedia.org/wiki/Rotation_matrix
+        let cos = angle_rad.cos();
+        let sin = angle_rad.sin();
+
+        return Vector2::new(
+            (cos * self.i) - (sin * self.j),
+            (sin * self.i) + (cos * self.j)
+        )
+    }
+
+    pub fn rotate_90_ccw(&self) -> Vector2{
+        return Vector2::new(-self.j, self.i)
+    }
+
+    pub fn rotate_90_cw(&self) -> Vector2{
         return Vector2::new(self.j, -self.i)
     }
 
@@ -97,9 +118,20 @@ impl Vector2{
         return (self.dot(vec) / (self.norm() * vec.norm())).acos()
     }
 
+    pub fn signed_angle_rad(&self, vec: &Vector2) -> f64{
+        // Angle in radians from self to vec, positive is counter-clockwise
+        let cross = (self.i * vec.j) - (self.j * vec.i);
+        return cross.atan2(self.dot(vec))
+    }
+
     pub fn angle_x_rad(&self) -> f64{
-        // Postive angle in radians from the i axis
-        return  self.angle_rad(&Vector2::new(1.0, 0.0))
+        // Signed angle in radians from the i axis, in (-pi, pi]
+        return self.j.atan2(self.i)
+    }
+
+    pub fn unsigned_angle_rad(&self) -> f64{
+        // Postive angle in radians from the i axis, in [0, pi]
+        return self.angle_rad(&Vector2::new(1.0, 0.0))
     }
 
     pub fn angle_y_rad(&self) -> f64{
@@ -192,10 +224,18 @@ impl Line2{
         return self.to_vector2().angle_rad(&line22.to_vector2())
     }
 
+    pub fn signed_angle_rad(&self, line2: &Line2) -> f64{
+        return self.to_vector2().signed_angle_rad(&line2.to_vector2())
+    }
+
     pub fn angle_x_rad(&self) -> f64{
         return self.to_vector2().angle_x_rad()
     }
 
+    pub fn unsigned_angle_rad(&self) -> f64{
+        return self.to_vector2().unsigned_angle_rad()
+    }
+
     pub fn angle_y_rad(&self) -> f64{
         return self.to_vector2().angle_y_rad()
     }
@@ -285,7 +325,7 @@ impl Circle{
         return PI_DOUBLE * self.radius_m
     }
 
-    pub fn intersect_circle(&self, circle2: &Circle) -> Option<Vector2>{
+    pub fn intersect_circle(&self, circle2: &Circle) -> CircleIntersection{
 
         // Source:
         // Intersection of Linear and Circular Components in 2D David Eberly
@@ -293,26 +333,66 @@ impl Circle{
         let r0 = self.radius_m;
         let r1 = circle2.radius_m;
 
-
         let u = circle2.center_to_vector2() - self.center_to_vector2();
+        if u.norm_sqr() == 0.0{
+            // Concentric, either no points or the whole circle
+            return CircleIntersection::Contained
+        }
+
         let v = u.get_perpendicular();
         let s = (((r0.powf(2.0) - r1.powf(2.0)) / u.norm_sqr()) + 1.0) / 2.0;
-        let t = ((r0.powf(2.0) / u.norm_sqr()) - s.powf(2.0)).sqrt();
+        let t_sqr = (r0.powf(2.0) / u.norm_sqr()) - s.powf(2.0);
+
+        // t_sqr is relative to |U|, small negatives are rounding at tangency
+        if t_sqr < -TANGENT_TOL{
+            if u.norm() > (r0 + r1){
+                return CircleIntersection::Separate
+            }
+            return CircleIntersection::Contained
+        }
 
-        // Edge cases 
-        if u.norm() <= (r0 + r1) && u.norm() >= (r0 - r1) {
-            // If |U| = |r0 + r1| there is one solution and c1 is outside c0
-            // If |U| = |r0 - r1| there is one solution and c1 is inside c0
-            return Some(self.center_to_vector2() + (u * s) + (v * t));
+        let point = self.center_to_vector2() + (u * s);
+        if t_sqr <= TANGENT_TOL{
+            // |U| = r0 + r1 outside or |U| = |r0 - r1| inside
+            return CircleIntersection::Tangent(point)
         }
 
-        return None
-    
+        let t = t_sqr.sqrt();
+        return CircleIntersection::Two(point + (v * t), point - (v * t))
     }
 
 }
 
 
+const TANGENT_TOL: f64 = 1e-12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CircleIntersection{
+    // Two points, the first is on the clockwise side of the line from the
+    // first center to the second
+    Two(Vector2, Vector2),
+    Tangent(Vector2),
+    Separate,
+    Contained
+}
+
+impl CircleIntersection{
+    pub fn points(&self) -> Vec<Vector2>{
+        match self{
+            CircleIntersection::Two(a, b) => return vec![*a, *b],
+            CircleIntersection::Tangent(a) => return vec![*a],
+            _ => return Vec::new()
+        }
+    }
+
+    pub fn closest_to(&self, point: Vector2) -> Option<Vector2>{
+        // Picks the solution nearest a previous point to stay on one branch
+        return self.points().into_iter().min_by(
+            |a, b| (*a - point).norm_sqr().total_cmp(&(*b - point).norm_sqr())
+        )
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Vector Tests
 // ----------------------------------------------------------------------------
@@ -476,6 +556,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn vector2_signed_angle_quadrants(){
+        let x_axis = Vector2::new(1.0, 0.0);
+        let cases = [
+            (Vector2::new(1.0, 1.0), PI_QUARTER),
+            (Vector2::new(-1.0, 1.0), 3.0 * PI_QUARTER),
+            (Vector2::new(-1.0, -1.0), -3.0 * PI_QUARTER),
+            (Vector2::new(1.0, -1.0), -PI_QUARTER),
+            (Vector2::new(2.0, 0.0), 0.0),
+            (Vector2::new(0.0, 2.0), PI / 2.0),
+            (Vector2::new(-2.0, 0.0), PI),
+            (Vector2::new(0.0, -2.0), -PI / 2.0)
+        ];
+
+        for (vec, expected) in cases{
+            assert_relative_eq!(vec.angle_x_rad(), expected, epsilon = 1e-12);
+            assert_relative_eq!(x_axis.signed_angle_rad(&vec), expected, epsilon = 1e-12);
+            assert_relative_eq!(vec.signed_angle_rad(&x_axis), -expected, epsilon = 1e-12);
+            assert_relative_eq!(vec.unsigned_angle_rad(), expected.abs(), epsilon = 1e-12);
+        }
+
+        // Independent of the reference direction
+        let a = Vector2::new(0.0, 1.0);
+        let b = Vector2::new(-1.0, -1.0);
+        assert_relative_eq!(a.signed_angle_rad(&b), 3.0 * PI_QUARTER, epsilon = 1e-12);
+        assert_relative_eq!(a.signed_angle_rad(&b).abs(), a.angle_rad(&b), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn vector2_rotate(){
+        let a = Vector2::new(1.0, 0.0);
+
+        let quarter = a.rotate(PI / 2.0);
+        assert_relative_eq!(quarter.i, 0.0, epsilon = 1e-10);
+        assert_relative_eq!(quarter.j, 1.0, epsilon = 1e-10);
+
+        let b = Vector2::new(0.3, -2.0);
+        let full = b.rotate(2.0 * PI);
+        assert_relative_eq!(full.i, b.i, epsilon = 1e-10);
+        assert_relative_eq!(full.j, b.j, epsilon = 1e-10);
+
+        // Fixed quarter turns match the general rotation
+        let ccw = b.rotate(PI / 2.0);
+        let cw = b.rotate(-PI / 2.0);
+        assert_relative_eq!(b.rotate_90_ccw().i, ccw.i, epsilon = 1e-10);
+        assert_relative_eq!(b.rotate_90_ccw().j, ccw.j, epsilon = 1e-10);
+        assert_relative_eq!(b.rotate_90_cw().i, cw.i, epsilon = 1e-10);
+        assert_relative_eq!(b.rotate_90_cw().j, cw.j, epsilon = 1e-10);
+        assert_eq!(b.get_perpendicular(), b.rotate_90_cw());
+    }
+
     #[test]
     fn vecto2_to_line2(){
         let a = Vector2::new(1.0, 1.0);
@@ -589,7 +720,7 @@ mod tests {
         let a = Circle::new(0.5, 0.0, 1.0);
         let b = Circle::new(-0.5, 0.0, 1.0);
 
-        let intersect = a.intersect_circle(&b).unwrap();
+        let intersect = a.intersect_circle(&b).points()[0];
 
         assert_relative_eq!(
             intersect.j, 
@@ -603,4 +734,51 @@ mod tests {
         );
 
     }
-}
\ No newline at end of file
+
+    # [test]
+    fn circle2_intersect_cases(){
+        let a = Circle::new(0.0, 0.0, 2.0);
+
+        // Two
+        match a.intersect_circle(&Circle::new(2.0, 0.0, 2.0)){
+            CircleIntersection::Two(p, q) => {
+                assert_relative_eq!(p.i, 1.0);
+                assert_relative_eq!(q.i, 1.0);
+                assert_relative_eq!(p.j, -q.j);
+                assert_relative_eq!(p.j.abs(), 3.0_f64.sqrt());
+            },
+            other => panic!("{:?}", other)
+        }
+
+        // Tangent outside and inside, including r1 > r0
+        assert_eq!(
+            a.intersect_circle(&Circle::new(0.0, 3.0, 1.0)),
+            CircleIntersection::Tangent(Vector2::new(0.0, 2.0))
+        );
+        assert_eq!(
+            a.intersect_circle(&Circle::new(0.0, -1.0, 1.0)),
+            CircleIntersection::Tangent(Vector2::new(0.0, -2.0))
+        );
+        assert_eq!(
+            Circle::new(0.0, -1.0, 1.0).intersect_circle(&a),
+            CircleIntersection::Tangent(Vector2::new(0.0, -2.0))
+        );
+
+        // Rounding at tangency does not lose the point
+        let tangent = Circle::new(0.1, 0.2, 0.3).intersect_circle(&Circle::new(0.1 + 0.3 + 0.7, 0.2, 0.7));
+        assert_eq!(tangent.points().len(), 1);
+        assert!(tangent.points()[0].i.is_finite());
+
+        // None
+        assert_eq!(a.intersect_circle(&Circle::new(5.0, 0.0, 1.0)), CircleIntersection::Separate);
+        assert_eq!(a.intersect_circle(&Circle::new(0.5, 0.0, 0.5)), CircleIntersection::Contained);
+        assert_eq!(Circle::new(0.5, 0.0, 0.5).intersect_circle(&a), CircleIntersection::Contained);
+        assert_eq!(a.intersect_circle(&a), CircleIntersection::Contained);
+
+        // Branch selection
+        let two = a.intersect_circle(&Circle::new(2.0, 0.0, 2.0));
+        assert!(two.closest_to(Vector2::new(1.0, 5.0)).unwrap().j > 0.0);
+        assert!(two.closest_to(Vector2::new(1.0, -5.0)).unwrap().j < 0.0);
+        assert_eq!(CircleIntersection::Separate.closest_to(Vector2::new(0.0, 0.0)), None);
+    }
+}
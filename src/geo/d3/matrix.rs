@@ -40,6 +40,14 @@ pub struct Matrix3x3{
     pub c33: f64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EulerSequence{
+    // Intrinsic body axis rotations, Euler angle vectors hold the angles
+    // in the order they are applied, e.g. Zyx is (yaw, pitch, roll)
+    Xyz,
+    Zyx
+}
+
 impl Matrix3x3{
 
     pub fn new(
@@ -92,8 +100,8 @@ impl Matrix3x3{
         //    https://en.wikipedia.org/wiki/Determinant
         return
             (self.c11 * (self.c22 * self.c33 - self.c23 * self.c32))
-            - (self.c12 * (self.c31 * self.c33 - self.c23 * self.c31))
-            + (self.c13 * (self.c31 * self.c32 - self.c22 * self.c31))
+            - (self.c12 * (self.c21 * self.c33 - self.c23 * self.c31))
+            + (self.c13 * (self.c21 * self.c32 - self.c22 * self.c31))
 
     }
 
@@ -122,6 +130,11 @@ impl Matrix3x3{
         return Some(self.adjugate() / self.det())
     }
 
+    pub fn solve(&self, b: Vector3) -> Option<Vector3>{
+        // x for Ax = b, None when A is singular
+        return self.inv().map(|inv| inv * b)
+    }
+
     pub fn to_array(&self) -> [f64; 9]{
         return [
             self.c11, self.c12, self.c13,
@@ -130,6 +143,48 @@ impl Matrix3x3{
         ]
     }
 
+    pub fn is_finite(&self) -> bool{
+        // False if any component is NaN or infinite
+        return self.to_array().iter().all(|x| x.is_finite())
+    }
+
+    pub fn orthonormalize(&self) -> Matrix3x3{
+        // Bjorck iteration, M = M(3I - M^T M) / 2, repeated until it settles
+        // Source:
+        //    Bjorck and Bowie, An Iterative Algorithm for Computing the Best
+        //    Estimate of an Orthogonal Matrix, 1971
+        let identity = Matrix3x3::identity();
+        let mut matrix = *self;
+        for _ in 0..10{
+            let next = matrix * ((identity * 3.0) - (matrix.transpose() * matrix)) / 2.0;
+            let change = (next - matrix).norm();
+            matrix = next;
+            if change < 1e-15{
+                break
+            }
+        }
+        return matrix
+    }
+
+    pub fn is_orthonormal(&self, tol: f64) -> bool{
+        // M^T M = I within tol, true for any rotation or reflection
+        return ((self.transpose() * *self) - Matrix3x3::identity()).norm() < tol
+    }
+
+    pub fn to_euler_zyx(&self) -> (f64, f64, f64){
+        // (yaw, pitch, roll) of Rz(yaw) Ry(pitch) Rx(roll), the aerospace
+        // sequence used by Strapdown Analytics
+        let euler = self.to_euler_sequence(EulerSequence::Zyx);
+        return (euler.i, euler.j, euler.k)
+    }
+
+    pub fn to_euler_xyz(&self) -> (f64, f64, f64){
+        // (roll, pitch, yaw) of Rx(roll) Ry(pitch) Rz(yaw)
+        let euler = self.to_euler_sequence(EulerSequence::Xyz);
+        return (euler.i, euler.j, euler.k)
+    }
+
+    #[deprecated(note = "ambiguous order, use to_euler_zyx or to_euler_xyz")]
     pub fn to_euler(&self) -> Vector3{
         // Eq 3.2.3.2-1, Pg 3-34
 
@@ -155,51 +210,82 @@ impl Matrix3x3{
     pub fn to_quat(&self) -> Quaternion{
 
         // Eq 3.2.4.3-2, Pg 3-46
-        let tr = self.c11 + self.c22 + self.c33;
+        let tr = self.trace();
 
         // Eq 3.2.4.3-7, Pg 3-47
         let pa = 1.0 + tr;
         let pb = 1.0 + (2.0 * self.c11) - tr;
         let pc = 1.0 + (2.0 * self.c22) - tr;
         let pd = 1.0 + (2.0 * self.c33) - tr;
-        let max_p = pa.max(pb).max(pc).max(pd);
 
-        let mut quat = Quaternion::of(404.0);
+        // Shepperd, divide by the largest of the four so the square root is
+        // never taken of a small or negative number
+        let (max_index, _) = [pa, pb, pc, pd].iter()
+            .enumerate()
+            .max_by(|(_, x), (_, y)| x.total_cmp(y))
+            .unwrap();
 
         // 3.2.4.3-9, Pg 3-47
-        if pa == max_p{
-            quat.a = pa.sqrt() / 2.0;
-            quat.b = (self.c32 - self.c23) / (4.0 * quat.a);
-            quat.c = (self.c13 - self.c31) / (4.0 * quat.a);
-            quat.d = (self.c21 - self.c12) / (4.0 * quat.a);
-
-        } else if pb == max_p{
-            quat.b = pb.sqrt() / 2.0;
-            quat.c = (self.c21 + self.c12) / (4.0 * quat.b);
-            quat.d = (self.c13 + self.c31) / (4.0 * quat.b);
-            quat.a = (self.c32 - self.c23) / (4.0 * quat.b);
-
-        } else if pc == max_p{
-            quat.c = pc.sqrt() / 2.0;
-            quat.d = (self.c32 + self.c23) / (4.0 * quat.c);
-            quat.a = (self.c13 - self.c31) / (4.0 * quat.c);
-            quat.b = (self.c21 + self.c12) / (4.0 * quat.c);
-
-        } else if pd == max_p{
-            quat.d = pd.sqrt() / 2.0;
-            quat.a = (self.c21 - self.c12) / (4.0 * quat.d);
-            quat.b = (self.c13 + self.c31) / (4.0 * quat.d);
-            quat.c = (self.c32 + self.c23) / (4.0 * quat.d);
-
+        let mut quat = Quaternion::of(0.0);
+        match max_index{
+            0 => {
+                quat.a = pa.sqrt() / 2.0;
+                quat.b = (self.c32 - self.c23) / (4.0 * quat.a);
+                quat.c = (self.c13 - self.c31) / (4.0 * quat.a);
+                quat.d = (self.c21 - self.c12) / (4.0 * quat.a);
+            },
+            1 => {
+                quat.b = pb.sqrt() / 2.0;
+                quat.c = (self.c21 + self.c12) / (4.0 * quat.b);
+                quat.d = (self.c13 + self.c31) / (4.0 * quat.b);
+                quat.a = (self.c32 - self.c23) / (4.0 * quat.b);
+            },
+            2 => {
+                quat.c = pc.sqrt() / 2.0;
+                quat.d = (self.c32 + self.c23) / (4.0 * quat.c);
+                quat.a = (self.c13 - self.c31) / (4.0 * quat.c);
+                quat.b = (self.c21 + self.c12) / (4.0 * quat.c);
+            },
+            _ => {
+                quat.d = pd.sqrt() / 2.0;
+                quat.a = (self.c21 - self.c12) / (4.0 * quat.d);
+                quat.b = (self.c13 + self.c31) / (4.0 * quat.d);
+                quat.c = (self.c32 + self.c23) / (4.0 * quat.d);
+            }
         };
 
         if quat.a <= 0.0{
             quat = -quat;
         };
-        return quat
+        // A drifted DCM gives a slightly off unit quaternion
+        return quat.normalized()
 
     }
 
+    pub fn to_euler_sequence(&self, seq: EulerSequence) -> Vector3{
+        // Inverse of Vector3::to_dcm_sequence. The middle angle is in
+        // [-pi/2, pi/2], at gimbal lock the last angle is set to zero.
+        let gimbal_lock = 1.0 - 1e-12;
+        match seq{
+            EulerSequence::Zyx => {
+                // Rz(a) Ry(b) Rx(c)
+                let b = (-self.c31).clamp(-1.0, 1.0).asin();
+                if self.c31.abs() >= gimbal_lock{
+                    return Vector3::new((-self.c12).atan2(self.c22), b, 0.0)
+                }
+                return Vector3::new(self.c21.atan2(self.c11), b, self.c32.atan2(self.c33))
+            },
+            EulerSequence::Xyz => {
+                // Rx(a) Ry(b) Rz(c)
+                let b = self.c13.clamp(-1.0, 1.0).asin();
+                if self.c13.abs() >= gimbal_lock{
+                    return Vector3::new(self.c32.atan2(self.c22), b, 0.0)
+                }
+                return Vector3::new((-self.c23).atan2(self.c33), b, (-self.c12).atan2(self.c11))
+            }
+        }
+    }
+
     pub fn transpose(&self) -> Matrix3x3{
         // Source:
         //     https://en.wikipedia.org/wiki/Transpose
@@ -210,6 +296,56 @@ impl Matrix3x3{
         )
     }
 
+    pub fn trace(&self) -> f64{
+        return self.c11 + self.c22 + self.c33
+    }
+
+    pub fn is_symmetric(&self, tol: f64) -> bool{
+        return (*self - self.transpose()).norm() < tol
+    }
+
+    pub fn symmetric_part(&self) -> Matrix3x3{
+        // (M + M^T) / 2
+        return (*self + self.transpose()) / 2.0
+    }
+
+    pub fn skew_part(&self) -> Matrix3x3{
+        // (M - M^T) / 2, symmetric_part + skew_part = M
+        return (*self - self.transpose()) / 2.0
+    }
+
+    pub fn symmetric_eigenvalues(&self) -> [f64; 3]{
+        // Closed form for a symmetric matrix, sorted ascending. Only the
+        // upper triangle is read.
+        // Source:
+        //    https://en.wikipedia.org/wiki/Eigenvalue_algorithm#3%C3%973_matrices
+        let off_diagonal = self.c12.powi(2) + self.c13.powi(2) + self.c23.powi(2);
+        if off_diagonal == 0.0{
+            let mut eigenvalues = [self.c11, self.c22, self.c33];
+            eigenvalues.sort_by(|a, b| a.total_cmp(b));
+            return eigenvalues
+        }
+
+        let q = self.trace() / 3.0;
+        let p = ((
+            (self.c11 - q).powi(2) + (self.c22 - q).powi(2) + (self.c33 - q).powi(2)
+            + (2.0 * off_diagonal)
+        ) / 6.0).sqrt();
+
+        let symmetric = Matrix3x3::new(
+            self.c11, self.c12, self.c13,
+            self.c12, self.c22, self.c23,
+            self.c13, self.c23, self.c33,
+        );
+        let b = (symmetric - (Matrix3x3::identity() * q)) / p;
+        let r = (b.det() / 2.0).clamp(-1.0, 1.0);
+        let phi = r.acos() / 3.0;
+
+        let largest = q + (2.0 * p * phi.cos());
+        let smallest = q + (2.0 * p * (phi + (2.0 * PI / 3.0)).cos());
+        return [smallest, (3.0 * q) - largest - smallest, largest]
+    }
+
     pub fn transform(self, vec: Vector3) -> Vector3{
         // Eq 3.1-11, Pg 3-4
         return self * vec
@@ -232,6 +368,54 @@ impl Matrix3x3{
         return target - self
     }
 
+    pub fn from_rotation_x(angle_rad: f64) -> Matrix3x3{
+        // Source:
+        //    https://en.wikipedia.org/wiki/Rotation_matrix#Basic_3D_rotations
+        let (sin, cos) = angle_rad.sin_cos();
+        return Matrix3x3::new(
+            1.0, 0.0, 0.0,
+            0.0, cos, -sin,
+            0.0, sin, cos,
+        )
+    }
+
+    pub fn from_rotation_y(angle_rad: f64) -> Matrix3x3{
+        let (sin, cos) = angle_rad.sin_cos();
+        return Matrix3x3::new(
+            cos, 0.0, sin,
+            0.0, 1.0, 0.0,
+            -sin, 0.0, cos,
+        )
+    }
+
+    pub fn from_rotation_z(angle_rad: f64) -> Matrix3x3{
+        let (sin, cos) = angle_rad.sin_cos();
+        return Matrix3x3::new(
+            cos, -sin, 0.0,
+            sin, cos, 0.0,
+            0.0, 0.0, 1.0,
+        )
+    }
+
+    pub fn from_axis_angle(axis: Vector3, angle_rad: f64) -> Matrix3x3{
+        // Rodrigues' rotation formula, R = I + sin(a)K + (1 - cos(a))K^2
+        // Source:
+        //    https://en.wikipedia.org/wiki/Rodrigues%27_rotation_formula
+        if axis.norm() == 0.0{
+            return Matrix3x3::identity()
+        }
+        let axis = axis.to_unit();
+        let k = Matrix3x3::new(
+               0.0, -axis.k,  axis.j,
+             axis.k,    0.0, -axis.i,
+            -axis.j,  axis.i,    0.0,
+        );
+
+        return Matrix3x3::identity()
+            + (k * angle_rad.sin())
+            + ((k * k) * (1.0 - angle_rad.cos()))
+    }
+
     pub fn from_xyz_euler(x: f64, y: f64, z: f64) -> Matrix3x3{
         // Source:
         // https://en.wikipedia.org/wiki/Euler_angles#Angles_of_a_given_frame
@@ -308,9 +492,322 @@ impl Mul<Vector3> for Matrix3x3{
 mod tests {
     use super::*;
     use crate::test::almost_equal_array;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use rand_distr::{Uniform, Distribution};
 
     // Matrix Operations
 
+    #[test]
+    fn trace(){
+        let matrix = Matrix3x3::new(
+            2.0, -3.0, 1.0,
+            2.0, 0.0, -1.0,
+            1.0, 4.0, 5.0
+        );
+        assert_eq!(matrix.trace(), 7.0);
+        assert_eq!(Matrix3x3::identity().trace(), 3.0);
+    }
+
+    #[test]
+    fn symmetric_skew_parts(){
+        let matrix = Matrix3x3::new(
+            2.0, -3.0, 1.0,
+            2.0, 0.0, -1.0,
+            1.0, 4.0, 5.0
+        );
+        let symmetric = matrix.symmetric_part();
+        let skew = matrix.skew_part();
+
+        assert_eq!(symmetric, symmetric.transpose());
+        assert!(symmetric.is_symmetric(1e-12));
+        assert!(!matrix.is_symmetric(1e-6));
+        assert!(Matrix3x3::identity().is_symmetric(1e-12));
+        assert_eq!(skew, -skew.transpose());
+        assert_eq!(skew.trace(), 0.0);
+        almost_equal_array(&(symmetric + skew).to_array(), &matrix.to_array());
+    }
+
+    #[test]
+    fn solve(){
+        let matrix = Matrix3x3::new(
+            2.0, -3.0, 1.0,
+            2.0, 0.0, -1.0,
+            1.0, 4.0, 5.0
+        );
+        let x = matrix.solve(Vector3::new(-1.0, -1.0, 24.0)).unwrap();
+        almost_equal_array(&x.to_array(), &[1.0, 2.0, 3.0]);
+
+        // Singular, the third row is the sum of the first two
+        let singular = Matrix3x3::new(
+            1.0, 2.0, 3.0,
+            4.0, 5.0, 6.0,
+            5.0, 7.0, 9.0
+        );
+        assert!(singular.solve(Vector3::new(1.0, 2.0, 3.0)).is_none());
+    }
+
+    #[test]
+    fn symmetric_eigenvalues(){
+        // Diagonal
+        let matrix = Matrix3x3::new(
+            3.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 2.0
+        );
+        assert_eq!(matrix.symmetric_eigenvalues(), [1.0, 2.0, 3.0]);
+
+        // Second difference matrix, 2 - sqrt(2), 2, 2 + sqrt(2)
+        let matrix = Matrix3x3::new(
+            2.0, -1.0, 0.0,
+            -1.0, 2.0, -1.0,
+            0.0, -1.0, 2.0
+        );
+        let root_2 = 2.0_f64.sqrt();
+        almost_equal_array(&matrix.symmetric_eigenvalues(), &[2.0 - root_2, 2.0, 2.0 + root_2]);
+
+        // Repeated, rotated inertia tensor keeps its principal moments
+        let rotation = Matrix3x3::from_axis_angle(Vector3::new(1.0, 2.0, 3.0), 0.7);
+        let inertia = Matrix3x3::new(
+            1.0, 0.0, 0.0,
+            0.0, 4.0, 0.0,
+            0.0, 0.0, 4.0
+        );
+        let rotated = rotation * inertia * rotation.transpose();
+        almost_equal_array(&rotated.symmetric_eigenvalues(), &[1.0, 4.0, 4.0]);
+    }
+
+    #[test]
+    fn determinant(){
+        let matrix = Matrix3x3::new(
+            2.0, -3.0, 1.0,
+            2.0, 0.0, -1.0,
+            1.0, 4.0, 5.0
+        );
+        assert!((matrix.det() - 49.0).abs() < 1e-12);
+        assert!((Matrix3x3::from_rotation_z(0.3).det() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn orthonormalize(){
+        let drifted = Matrix3x3::identity() + Matrix3x3::new(
+            1e-3, -2e-3, 5e-4,
+            3e-3, -1e-3, 2e-3,
+            -1e-3, 4e-4, 2e-3
+        );
+        let fixed = drifted.orthonormalize();
+
+        assert!((fixed.det() - 1.0).abs() < 1e-12);
+        almost_equal_array(
+            &(fixed * fixed.transpose()).to_array(),
+            &Matrix3x3::identity().to_array()
+        );
+
+        // Already orthonormal is left alone
+        let rotation = Matrix3x3::from_axis_angle(Vector3::new(1.0, 2.0, 3.0), 0.5);
+        almost_equal_array(
+            &rotation.orthonormalize().to_array(),
+            &rotation.to_array()
+        );
+    }
+
+    #[test]
+    fn orthonormalize_noise(){
+        let mut rng = StdRng::seed_from_u64(7);
+        let noise = Uniform::new(-1e-3, 1e-3);
+
+        for _ in 0..50{
+            let mut array = Matrix3x3::identity().to_array();
+            for value in array.iter_mut(){
+                *value += noise.sample(&mut rng);
+            }
+            let drifted = Matrix3x3::from_array(array);
+            assert!(!drifted.is_orthonormal(1e-6));
+
+            let fixed = drifted.orthonormalize();
+            let error = (fixed.transpose() * fixed) - Matrix3x3::identity();
+            assert!(error.to_array().iter().all(|x| x.abs() < 1e-12));
+            assert!(fixed.is_orthonormal(1e-12));
+
+            // Still a unit quaternion before the fix
+            assert!(drifted.to_quat().is_normalized(1e-12));
+        }
+    }
+
+    #[test]
+    fn orthonormalize_rate_integration(){
+        // Euler steps of the DCM rate against the exact quaternion
+        let rate = Vector3::new(0.3, -0.5, 0.8);
+        let dt = 1e-3;
+
+        let mut drifting = Matrix3x3::identity();
+        let mut corrected = Matrix3x3::identity();
+        let mut quat = Quaternion::identity();
+        for i in 0..20000{
+            drifting += drifting.derivative(rate) * dt;
+            corrected += corrected.derivative(rate) * dt;
+            if i % 10 == 0{
+                corrected = corrected.orthonormalize();
+            }
+            quat = quat.integrate_rate(rate, dt);
+        }
+        corrected = corrected.orthonormalize();
+
+        assert!(!drifting.is_orthonormal(1e-3));
+        assert!(corrected.is_orthonormal(1e-12));
+        assert!((corrected - quat.to_dcm()).norm() < 1e-5);
+        assert!((drifting - quat.to_dcm()).norm() > 1e-3);
+        assert!(corrected.to_quat().angle(quat) < 1e-5);
+    }
+
+    #[test]
+    fn euler_sequence_round_trip(){
+        let angles = Vector3::new(0.3, -0.5, 1.1);
+
+        for seq in [EulerSequence::Xyz, EulerSequence::Zyx]{
+            let dcm = angles.to_dcm_sequence(seq);
+            assert!(dcm.is_orthonormal(1e-12));
+            almost_equal_array(&dcm.to_euler_sequence(seq).to_array(), &angles.to_array());
+        }
+
+        // Zyx is the existing to_dcm, the sequences give different attitudes
+        // for the same asymmetric angles
+        almost_equal_array(
+            &angles.to_dcm_sequence(EulerSequence::Zyx).to_array(),
+            &angles.to_dcm().to_array()
+        );
+        let xyz = angles.to_dcm_sequence(EulerSequence::Xyz);
+        let zyx = angles.to_dcm_sequence(EulerSequence::Zyx);
+        assert!((xyz - zyx).norm() > 0.1);
+
+        // Matches the single axis rotations applied in order
+        let expected = Matrix3x3::from_rotation_x(angles.i)
+            * Matrix3x3::from_rotation_y(angles.j)
+            * Matrix3x3::from_rotation_z(angles.k);
+        almost_equal_array(&xyz.to_array(), &expected.to_array());
+    }
+
+    #[test]
+    fn explicit_euler_orders(){
+        let (roll, pitch, yaw) = (0.3, -0.5, 1.1);
+        let zyx = Matrix3x3::from_rotation_z(yaw)
+            * Matrix3x3::from_rotation_y(pitch)
+            * Matrix3x3::from_rotation_x(roll);
+        let xyz = Matrix3x3::from_rotation_x(roll)
+            * Matrix3x3::from_rotation_y(pitch)
+            * Matrix3x3::from_rotation_z(yaw);
+
+        let (a, b, c) = zyx.to_euler_zyx();
+        almost_equal_array(&[a, b, c], &[yaw, pitch, roll]);
+        let (a, b, c) = xyz.to_euler_xyz();
+        almost_equal_array(&[a, b, c], &[roll, pitch, yaw]);
+
+        // Same angles as the deprecated form, in its roll, pitch, yaw order
+        #[allow(deprecated)]
+        let legacy = zyx.to_euler();
+        almost_equal_array(&legacy.to_array(), &[roll, pitch, yaw]);
+    }
+
+    #[test]
+    fn euler_sequence_gimbal_lock(){
+        // Only the combined first and last angle is observable
+        for seq in [EulerSequence::Xyz, EulerSequence::Zyx]{
+            let angles = Vector3::new(0.4, PI / 2.0, 0.0);
+            let dcm = angles.to_dcm_sequence(seq);
+            let recovered = dcm.to_euler_sequence(seq);
+            assert!(recovered.is_finite());
+            almost_equal_array(&recovered.to_dcm_sequence(seq).to_array(), &dcm.to_array());
+        }
+    }
+
+    #[test]
+    fn from_axis_angle(){
+        let angle = 0.7;
+
+        // Principal axes
+        almost_equal_array(
+            &Matrix3x3::from_axis_angle(Vector3::new(2.0, 0.0, 0.0), angle).to_array(),
+            &Matrix3x3::from_rotation_x(angle).to_array()
+        );
+        almost_equal_array(
+            &Matrix3x3::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), angle).to_array(),
+            &Matrix3x3::from_rotation_y(angle).to_array()
+        );
+        almost_equal_array(
+            &Matrix3x3::from_axis_angle(Vector3::new(0.0, 0.0, 0.5), angle).to_array(),
+            &Matrix3x3::from_rotation_z(angle).to_array()
+        );
+
+        // General axis agrees with the quaternion and is orthogonal
+        let axis = Vector3::new(1.0, -2.0, 0.5);
+        let matrix = Matrix3x3::from_axis_angle(axis, angle);
+        let quat_dcm = Quaternion::from_axis_angle(axis, angle).to_dcm();
+        for (a, b) in matrix.to_array().iter().zip(quat_dcm.to_array().iter()){
+            assert!((a - b).abs() < 1e-12);
+        }
+        almost_equal_array(
+            &(matrix * matrix.transpose()).to_array(),
+            &Matrix3x3::identity().to_array()
+        );
+        almost_equal_array(
+            &(matrix * axis).to_array(),
+            &axis.to_array()
+        );
+    }
+
+    #[test]
+    fn rotation_constructors(){
+        let angle = PI / 2.0;
+        let x = Vector3::new(1.0, 0.0, 0.0);
+        let y = Vector3::new(0.0, 1.0, 0.0);
+        let z = Vector3::new(0.0, 0.0, 1.0);
+
+        // Right handed, x -> y -> z -> x
+        almost_equal_array(&(Matrix3x3::from_rotation_x(angle) * y).to_array(), &z.to_array());
+        almost_equal_array(&(Matrix3x3::from_rotation_y(angle) * z).to_array(), &x.to_array());
+        almost_equal_array(&(Matrix3x3::from_rotation_z(angle) * x).to_array(), &y.to_array());
+
+        // Axis of rotation is unchanged
+        almost_equal_array(&(Matrix3x3::from_rotation_x(angle) * x).to_array(), &x.to_array());
+        almost_equal_array(&(Matrix3x3::from_rotation_y(angle) * y).to_array(), &y.to_array());
+        almost_equal_array(&(Matrix3x3::from_rotation_z(angle) * z).to_array(), &z.to_array());
+
+        // Rz(a) * Rz(b) == Rz(a + b)
+        let (a, b) = (0.3, -1.1);
+        almost_equal_array(
+            &(Matrix3x3::from_rotation_z(a) * Matrix3x3::from_rotation_z(b)).to_array(),
+            &Matrix3x3::from_rotation_z(a + b).to_array()
+        );
+
+        // Single axis euler, to_dcm takes (yaw, pitch, roll)
+        let angle = 0.4;
+        almost_equal_array(
+            &Vector3::new(0.0, 0.0, angle).to_dcm().to_array(),
+            &Matrix3x3::from_rotation_x(angle).to_array()
+        );
+        almost_equal_array(
+            &Vector3::new(0.0, angle, 0.0).to_dcm().to_array(),
+            &Matrix3x3::from_rotation_y(angle).to_array()
+        );
+        almost_equal_array(
+            &Vector3::new(angle, 0.0, 0.0).to_dcm().to_array(),
+            &Matrix3x3::from_rotation_z(angle).to_array()
+        );
+    }
+
+    #[test]
+    fn is_finite(){
+        assert!(Matrix3x3::identity().is_finite());
+
+        let mut matrix = Matrix3x3::identity();
+        matrix.c23 = f64::NAN;
+        assert!(!matrix.is_finite());
+
+        // Division by zero
+        let matrix = Matrix3x3::of(1.0) / 0.0;
+        assert!(!matrix.is_finite());
+    }
+
     # [test]
     fn adjugate_from_example(){
         // https://en.wikipedia.org/wiki/Adjugate_matrix
@@ -375,6 +872,7 @@ mod tests {
     // DCM Conversions
 
     #[test]
+    #[allow(deprecated)]
     fn dcm_to_euler(){
         // Identity check
         let dcm = Matrix3x3::identity();
@@ -421,6 +919,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn dcm_derivative_x(){
         let mut dcm = Matrix3x3::identity();
 
@@ -439,6 +938,7 @@ mod tests {
         );
     }
     #[test]
+    #[allow(deprecated)]
     fn dcm_derivative_y(){
         let mut dcm = Matrix3x3::identity();
 
@@ -457,6 +957,7 @@ mod tests {
         );
     }
     #[test]
+    #[allow(deprecated)]
     fn dcm_derivative_z(){
         let mut dcm = Matrix3x3::identity();
 
@@ -4,7 +4,9 @@
 
 // 3rd Party
 use std::ops::Mul;
+use std::f64::consts::PI;
 use derive_more;
+use rand::Rng;
 
 // Crate
 use super::{Vector3, Matrix3x3};
@@ -48,6 +50,78 @@ impl Quaternion{
         return Quaternion::new(1.0, 0.0, 0.0, 0.0)
     }
 
+    pub fn try_new(a: f64, b: f64, c: f64, d: f64) -> Option<Quaternion>{
+        // Normalized for use as a rotation, None when it is too close to
+        // zero or not finite to have a direction
+        let quat = Quaternion::new(a, b, c, d);
+        if !quat.is_finite() || quat.norm() < 1e-12{
+            return None
+        }
+        return Some(quat.normalized())
+    }
+
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Quaternion{
+        // Uniform over all rotations, Shoemake's method
+        // Source:
+        //    Shoemake, K. "Uniform Random Rotations", Graphics Gems III, 1992
+        let (u1, u2, u3): (f64, f64, f64) = (rng.gen(), rng.gen(), rng.gen());
+        let (r1, r2) = ((1.0 - u1).sqrt(), u1.sqrt());
+        let (s1, c1) = (2.0 * PI * u2).sin_cos();
+        let (s2, c2) = (2.0 * PI * u3).sin_cos();
+        return Quaternion::new(r2 * c2, r1 * s1, r1 * c1, r2 * s2)
+    }
+
+    pub fn from_euler(roll_rad: f64, pitch_rad: f64, yaw_rad: f64) -> Quaternion{
+        // Z-Y-X sequence, yaw then pitch then roll about the body axes, the
+        // inverse of to_euler_zyx
+        // Source:
+        //    https://en.wikipedia.org/wiki/Conversion_between_quaternions_and_Euler_angles
+        let (sr, cr) = (roll_rad / 2.0).sin_cos();
+        let (sp, cp) = (pitch_rad / 2.0).sin_cos();
+        let (sy, cy) = (yaw_rad / 2.0).sin_cos();
+        return Quaternion::new(
+            (cr * cp * cy) + (sr * sp * sy),
+            (sr * cp * cy) - (cr * sp * sy),
+            (cr * sp * cy) + (sr * cp * sy),
+            (cr * cp * sy) - (sr * sp * cy)
+        )
+    }
+
+    pub fn from_axis_angle(axis: Vector3, angle_rad: f64) -> Quaternion{
+        // Source:
+        //    https://en.wikipedia.org/wiki/Axis%E2%80%93angle_representation
+        if axis.norm() == 0.0{
+            return Quaternion::identity()
+        }
+        let axis = axis.to_unit();
+        let (sin, cos) = (angle_rad / 2.0).sin_cos();
+        return Quaternion::new(cos, axis.i * sin, axis.j * sin, axis.k * sin)
+    }
+
+    pub fn from_two_vectors(from: Vector3, to: Vector3) -> Quaternion{
+        // Shortest arc rotation taking the direction of from onto to. For
+        // opposite vectors any perpendicular axis works, zero vectors give
+        // the identity.
+        if from.norm() == 0.0 || to.norm() == 0.0{
+            return Quaternion::identity()
+        }
+        let (from, to) = (from.to_unit(), to.to_unit());
+        let dot = from.dot(&to);
+
+        if dot < -1.0 + 1e-12{
+            let trial = if from.i.abs() < 0.9 {Vector3::new(1.0, 0.0, 0.0)} else {Vector3::new(0.0, 1.0, 0.0)};
+            return Quaternion::from_axis_angle(from.cross(&trial), std::f64::consts::PI)
+        }
+
+        let axis = from.cross(&to);
+        return Quaternion::new(1.0 + dot, axis.i, axis.j, axis.k).normalized()
+    }
+
+    pub fn from_dcm(dcm: Matrix3x3) -> Quaternion{
+        // Scalar part is returned non-negative
+        return dcm.to_quat()
+    }
+
     pub fn from_array(array: [f64; 4]) -> Quaternion{
         return Quaternion::new(array[0], array[1], array[2], array[3])
     }
@@ -56,6 +130,34 @@ impl Quaternion{
         return [self.a, self.b, self.c, self.d]
     }
 
+    pub fn is_finite(&self) -> bool{
+        // False if any component is NaN or infinite
+        return self.to_array().iter().all(|x| x.is_finite())
+    }
+
+    pub fn norm(&self) -> f64{
+        return (
+            self.a.powf(2.0) + self.b.powf(2.0) + self.c.powf(2.0) + self.d.powf(2.0)
+        ).sqrt()
+    }
+
+    pub fn normalized(&self) -> Quaternion{
+        // A zero quaternion is returned unchanged
+        let norm = self.norm();
+        if norm == 0.0{
+            return *self
+        }
+        return *self / norm
+    }
+
+    pub fn normalize_mut(&mut self){
+        *self = self.normalized();
+    }
+
+    pub fn is_normalized(&self, tol: f64) -> bool{
+        return (self.norm() - 1.0).abs() <= tol
+    }
+
     pub fn conjugate(&self) -> Quaternion{
         // Source:
         //    https://en.wikipedia.org/wiki/Quaternion
@@ -64,7 +166,7 @@ impl Quaternion{
 
     pub fn transform(self, vec: Vector3) -> Vector3{
         // w = uvu*
-        let quat = (self * vec) * self.conjugate();
+        let quat = (self * vec).product(self.conjugate());
         return Vector3::new(quat.b, quat.c, quat.d)
     }
 
@@ -73,10 +175,111 @@ impl Quaternion{
         return self * vec / 2.0
     }
 
+    pub fn exp(&self) -> Quaternion{
+        // e^a * (cos|v|, sin|v| * v / |v|)
+        // Source:
+        //    https://en.wikipedia.org/wiki/Quaternion#Exponential,_logarithm,_and_power_functions
+        let vector = Vector3::new(self.b, self.c, self.d);
+        let angle_rad = vector.norm();
+
+        // sin(x) / x, series below where it loses precision
+        let sinc = if angle_rad < 1e-4{
+            1.0 - (angle_rad.powi(2) / 6.0)
+        } else{
+            angle_rad.sin() / angle_rad
+        };
+        let vector = vector * sinc;
+        return Quaternion::new(angle_rad.cos(), vector.i, vector.j, vector.k) * self.a.exp()
+    }
+
+    pub fn log(&self) -> Quaternion{
+        // (ln|q|, acos(a / |q|) * v / |v|), the inverse of exp for angles
+        // up to pi. The identity gives zero.
+        let norm = self.norm();
+        let vector = Vector3::new(self.b, self.c, self.d);
+        let vector_norm = vector.norm();
+        if vector_norm == 0.0{
+            return Quaternion::new(norm.ln(), 0.0, 0.0, 0.0)
+        }
+        let vector = vector * (vector_norm.atan2(self.a) / vector_norm);
+        return Quaternion::new(norm.ln(), vector.i, vector.j, vector.k)
+    }
+
+    pub fn rotation_vector(&self) -> Vector3{
+        // Axis * angle taking the short way around, twice the vector part of
+        // the log of the canonical form
+        let log = self.normalized().canonical().log();
+        return Vector3::new(log.b, log.c, log.d) * 2.0
+    }
+
+    pub fn integrate_rate(&self, omega: Vector3, dt: f64) -> Quaternion{
+        // Exact for a constant body rate, q * exp(w * dt / 2) to match
+        // derivative
+        return *self * (omega * (dt / 2.0)).quat_form().exp()
+    }
+
+    pub fn angular_velocity_between(q1: Quaternion, q2: Quaternion, dt: f64) -> Vector3{
+        // Constant body rate taking q1 to q2 over dt, the inverse of
+        // integrate_rate. Uses the shorter of the two rotations.
+        if dt <= 0.0{
+            panic!("    ERROR| Time step [{}] must be positive", dt)
+        }
+        return (q1.conjugate() * q2).rotation_vector() / dt
+    }
+
+    pub fn slerp(&self, target: Quaternion, t: f64) -> Quaternion{
+        // Constant rate rotation along the shorter arc, self at t = 0 and
+        // target at t = 1
+        // Source:
+        //    https://en.wikipedia.org/wiki/Slerp
+        let delta = (self.conjugate() * target).normalized().canonical();
+        return *self * (delta.log() * t).exp()
+    }
+
+    pub fn derivative_matrix_form(self, vec: Vector3) -> Quaternion{
+        // q_dot = [W] q / 2 with the 4x4 skew form of the body rate, same
+        // result as derivative
+        // Eq 3.3.4-19
+        let (x, y, z) = (vec.i, vec.j, vec.k);
+        let omega = [
+            [0.0, -x, -y, -z],
+            [x, 0.0, z, -y],
+            [y, -z, 0.0, x],
+            [z, y, -x, 0.0]
+        ];
+
+        let quat = self.to_array();
+        let mut quat_dot = [0.0; 4];
+        for (row, value) in omega.iter().zip(quat_dot.iter_mut()){
+            *value = row.iter().zip(quat.iter()).map(|(w, q)| w * q).sum::<f64>() / 2.0;
+        }
+        return Quaternion::from_array(quat_dot)
+    }
+
     pub fn error(&self, target: Quaternion) -> Quaternion{
         return target * self.conjugate()
     }
 
+    pub fn dot(&self, quat: Quaternion) -> f64{
+        return (self.a * quat.a) + (self.b * quat.b) + (self.c * quat.c) + (self.d * quat.d)
+    }
+
+    pub fn angle(&self, quat: Quaternion) -> f64{
+        // Geodesic angle [0, pi] between the two orientations, q and -q are
+        // the same orientation. atan2 keeps small angles accurate.
+        let error = self.normalized().error(quat.normalized());
+        let vector_norm = (error.b.powi(2) + error.c.powi(2) + error.d.powi(2)).sqrt();
+        return 2.0 * vector_norm.atan2(error.a.abs())
+    }
+
+    pub fn canonical(&self) -> Quaternion{
+        // Same rotation with a non-negative scalar part
+        if self.a < 0.0{
+            return -*self
+        }
+        return *self
+    }
+
     pub fn to_dcm(&self) -> Matrix3x3{
         let _c11 =
             self.a.powf(2.0)
@@ -110,10 +313,29 @@ impl Quaternion{
 
     }
 
+    pub fn to_euler_zyx(&self) -> (f64, f64, f64){
+        // (yaw, pitch, roll) of Rz(yaw) Ry(pitch) Rx(roll)
+        return self.to_dcm().to_euler_zyx()
+    }
+
+    pub fn to_euler_xyz(&self) -> (f64, f64, f64){
+        // (roll, pitch, yaw) of Rx(roll) Ry(pitch) Rz(yaw)
+        return self.to_dcm().to_euler_xyz()
+    }
+
+    #[deprecated(note = "ambiguous order, use to_euler_zyx or to_euler_xyz")]
+    #[allow(deprecated)]
     pub fn to_euler(&self) -> Vector3{
         return self.to_dcm().to_euler()
     }
 
+    pub fn to_euler_checked(&self) -> ((f64, f64, f64), bool){
+        // (yaw, pitch, roll) as in to_euler_zyx, flagged within about 2.5 deg
+        // of +-90 deg pitch where yaw and roll stop being separable
+        let (yaw, pitch, roll) = self.to_euler_zyx();
+        return ((yaw, pitch, roll), pitch.sin().abs() >= 0.999)
+    }
+
 }
 
 impl Mul<Vector3> for Quaternion{
@@ -129,9 +351,8 @@ impl Mul<Vector3> for Quaternion{
     }
 }
 
-impl Mul<Quaternion> for Quaternion{
-    type Output = Quaternion;
-    fn mul(self, quat: Quaternion) -> Quaternion{
+impl Quaternion{
+    fn product(self, quat: Quaternion) -> Quaternion{
         // Eq 3.2.4-10, Pg 3-41
         return Quaternion::new(
           (self.a * quat.a) + (-self.b * quat.b) + (-self.c * quat.c) + (-self.d * quat.d),
@@ -142,6 +363,20 @@ impl Mul<Quaternion> for Quaternion{
     }
 }
 
+impl Mul<Quaternion> for Quaternion{
+    type Output = Quaternion;
+    fn mul(self, quat: Quaternion) -> Quaternion{
+        let product = self.product(quat);
+
+        // Note: adds a sqrt and divide to every product, only enable when
+        // drift from scaled quaternions is a concern
+        #[cfg(feature = "quat_auto_normalize")]
+        let product = product.normalized();
+
+        return product
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Tests
 // ----------------------------------------------------------------------------
@@ -150,7 +385,11 @@ impl Mul<Quaternion> for Quaternion{
 mod tests {
 
     use super::*;
-    use crate::test::almost_equal_array;
+    use crate::test::{almost_equal_array, assert_transform_agree};
+    use std::f64::consts::PI;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use rand_distr::{Uniform, Distribution};
 
     // Math
     # [test]
@@ -170,9 +409,322 @@ mod tests {
         );
     }
 
+    #[test]
+    fn quat_angle(){
+        let quat_90 = Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), PI / 2.0);
+        assert!((Quaternion::identity().angle(quat_90) - (PI / 2.0)).abs() < 1e-12);
+        assert!((quat_90.angle(Quaternion::identity()) - (PI / 2.0)).abs() < 1e-12);
+
+        // Sign does not change the orientation
+        assert!(quat_90.angle(-quat_90).abs() < 1e-12);
+        assert!((Quaternion::identity().dot(quat_90) - (PI / 4.0).cos()).abs() < 1e-12);
+
+        // Small angles and the far side of pi
+        let quat_small = Quaternion::from_axis_angle(Vector3::new(1.0, 1.0, 0.0), 1e-9);
+        assert!((Quaternion::identity().angle(quat_small) - 1e-9).abs() < 1e-20);
+        let quat_270 = Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), 1.5 * PI);
+        assert!((Quaternion::identity().angle(quat_270) - (PI / 2.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn quat_from_two_vectors(){
+        let x = Vector3::new(1.0, 0.0, 0.0);
+        let y = Vector3::new(0.0, 1.0, 0.0);
+
+        // 90 deg about z
+        let quat = Quaternion::from_two_vectors(x, y * 2.0);
+        almost_equal_array(
+            &quat.to_array(),
+            &Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), PI / 2.0).to_array()
+        );
+        almost_equal_array(&quat.transform(x).to_array(), &y.to_array());
+
+        assert_eq!(Quaternion::from_two_vectors(x, x), Quaternion::identity());
+
+        // 180 deg, axis is arbitrary but must be perpendicular
+        let quat = Quaternion::from_two_vectors(x, -x);
+        assert!(quat.is_finite() && quat.is_normalized(1e-12));
+        assert!(quat.a.abs() < 1e-12);
+        almost_equal_array(&quat.transform(x).to_array(), &(-x).to_array());
+
+        // Arbitrary directions
+        let from = Vector3::new(0.3, -1.2, 2.0);
+        let to = Vector3::new(-4.0, 0.5, 0.1);
+        let rotated = Quaternion::from_two_vectors(from, to).transform(from);
+        almost_equal_array(&rotated.to_unit().to_array(), &to.to_unit().to_array());
+        assert!((rotated.norm() - from.norm()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn quat_dcm_transform_grid(){
+        // Quaternion built up from elementary rotations against the euler
+        // DCM, yaw then pitch then roll
+        let x = Vector3::new(1.0, 0.0, 0.0);
+        let y = Vector3::new(0.0, 1.0, 0.0);
+        let z = Vector3::new(0.0, 0.0, 1.0);
+        let vecs = [x, y, z, Vector3::new(0.3, -1.7, 2.2)];
+
+        let steps = 9;
+        let angle = |i: usize| -PI + (2.0 * PI * i as f64 / (steps - 1) as f64);
+        for (yaw, pitch, roll) in (0..steps).flat_map(
+            |i| (0..steps).flat_map(move |j| (0..steps).map(move |k| (angle(i), angle(j) / 2.0, angle(k))))
+        ){
+            let quat = Quaternion::from_axis_angle(z, yaw)
+                * Quaternion::from_axis_angle(y, pitch)
+                * Quaternion::from_axis_angle(x, roll);
+            let dcm = Vector3::new(yaw, pitch, roll).to_dcm();
+
+            for vec in vecs.iter(){
+                assert_transform_agree(quat, dcm, *vec, 1e-12);
+                assert_transform_agree(quat, quat.to_dcm(), *vec, 1e-12);
+                assert_transform_agree(dcm.to_quat(), dcm, *vec, 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn quat_exp_log(){
+        // Round trips
+        let quat = Quaternion::from_axis_angle(Vector3::new(1.0, -2.0, 0.5), 2.5);
+        almost_equal_array(&quat.log().exp().to_array(), &quat.to_array());
+        let pure = Quaternion::new(0.0, 0.3, -0.1, 0.2);
+        almost_equal_array(&pure.exp().log().to_array(), &pure.to_array());
+
+        assert_eq!(Quaternion::identity().log(), Quaternion::of(0.0));
+        assert_eq!(Quaternion::of(0.0).exp(), Quaternion::identity());
+
+        // Half angle of the rotation along the axis
+        let log = Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), 1.0).log();
+        assert!((log.d - 0.5).abs() < 1e-12 && log.a.abs() < 1e-12);
+
+        // Small angle branch is continuous
+        let small = Quaternion::new(0.0, 1e-4 * (1.0 - 1e-9), 0.0, 0.0).exp();
+        let above = Quaternion::new(0.0, 1e-4 * (1.0 + 1e-9), 0.0, 0.0).exp();
+        assert!((small - above).norm() < 1e-12);
+    }
+
+    #[test]
+    fn quat_rotation_vector(){
+        let axis = Vector3::new(0.6, 0.0, -0.8);
+        let quat = Quaternion::from_axis_angle(axis, 2.5);
+        almost_equal_array(&quat.rotation_vector().to_array(), &(axis * 2.5).to_array());
+
+        // Past 180 deg goes the short way, either sign of the quaternion
+        let quat = Quaternion::from_axis_angle(axis, 1.5 * PI);
+        almost_equal_array(&quat.rotation_vector().to_array(), &(axis * (-0.5 * PI)).to_array());
+        almost_equal_array(&(-quat).rotation_vector().to_array(), &(axis * (-0.5 * PI)).to_array());
+
+        assert_eq!(Quaternion::identity().rotation_vector(), Vector3::zeros());
+    }
+
+    #[test]
+    fn quat_angular_velocity_between(){
+        let omega = Vector3::new(0.4, -1.1, 2.3);
+        let start = Quaternion::from_axis_angle(Vector3::new(1.0, 1.0, 0.0), 0.3);
+
+        // First order step from the derivative lands close to the exact one
+        let dt = 1e-3;
+        let euler = start + (start.derivative(omega) * dt);
+        let exact = start.integrate_rate(omega, dt);
+        assert!((euler - exact).norm() < 1e-5);
+
+        almost_equal_array(
+            &Quaternion::angular_velocity_between(start, exact, dt).to_array(),
+            &omega.to_array()
+        );
+        let recovered = Quaternion::angular_velocity_between(start, euler.normalized(), dt);
+        assert!((recovered - omega).norm() < 1e-3);
+
+        // Large steps and the sign ambiguity of q2
+        let exact = start.integrate_rate(omega, 0.5);
+        almost_equal_array(
+            &Quaternion::angular_velocity_between(start, exact * -1.0, 0.5).to_array(),
+            &omega.to_array()
+        );
+
+        assert_eq!(Quaternion::angular_velocity_between(start, start, dt), Vector3::zeros());
+    }
+
+    #[test]
+    fn quat_slerp(){
+        let start = Quaternion::from_axis_angle(Vector3::new(1.0, 1.0, 0.0), 0.3);
+        let axis = Vector3::new(0.2, -1.0, 0.5);
+        let target = start * Quaternion::from_axis_angle(axis, 1.2);
+
+        almost_equal_array(&start.slerp(target, 0.0).to_array(), &start.to_array());
+        almost_equal_array(&start.slerp(target, 1.0).to_array(), &target.to_array());
+
+        // Even steps along a single axis
+        let quarter = start * Quaternion::from_axis_angle(axis, 0.3);
+        almost_equal_array(&start.slerp(target, 0.25).to_array(), &quarter.to_array());
+        assert!((start.slerp(target, 0.5).angle(start) - 0.6).abs() < 1e-12);
+
+        // Same path for either sign of the target
+        almost_equal_array(&start.slerp(target * -1.0, 0.5).to_array(), &start.slerp(target, 0.5).to_array());
+    }
+
+    #[test]
+    fn quat_integrate_rate(){
+        // Exp map against fine RK4 of the derivative for 1 s
+        let omega = Vector3::new(0.4, -1.1, 2.3);
+        let start = Quaternion::from_axis_angle(Vector3::new(1.0, 1.0, 0.0), 0.3);
+
+        let dt = 1e-4;
+        let mut quat = start;
+        for _ in 0..10000{
+            let k1 = quat.derivative(omega);
+            let k2 = (quat + (k1 * (dt / 2.0))).derivative(omega);
+            let k3 = (quat + (k2 * (dt / 2.0))).derivative(omega);
+            let k4 = (quat + (k3 * dt)).derivative(omega);
+            quat = quat + ((k1 + (k2 * 2.0) + (k3 * 2.0) + k4) * (dt / 6.0));
+        }
+
+        let exact = start.integrate_rate(omega, 1.0);
+        assert!((exact - quat).norm() < 1e-10);
+        assert!(exact.is_normalized(1e-12));
+
+        // Steps compose for a constant rate
+        let stepped = (0..10).fold(start, |quat, _| quat.integrate_rate(omega, 0.1));
+        assert!((stepped - exact).norm() < 1e-12);
+    }
+
+    #[test]
+    fn quat_derivative_matrix_form(){
+        let mut rng = StdRng::seed_from_u64(7);
+        let uniform = Uniform::new(-3.0, 3.0);
+
+        for _ in 0..1000{
+            let quat = Quaternion::new(
+                uniform.sample(&mut rng),
+                uniform.sample(&mut rng),
+                uniform.sample(&mut rng),
+                uniform.sample(&mut rng)
+            ).normalized();
+            let rate = Vector3::new(
+                uniform.sample(&mut rng),
+                uniform.sample(&mut rng),
+                uniform.sample(&mut rng)
+            );
+
+            let difference = quat.derivative_matrix_form(rate) - quat.derivative(rate);
+            assert!(difference.norm() < 1e-14);
+        }
+    }
+
+    #[test]
+    fn quat_canonical(){
+        let quat = Quaternion::new(0.5, -0.5, 0.5, 0.5);
+        assert_eq!((-quat).canonical(), quat);
+        assert_eq!(quat.canonical(), quat);
+    }
+
+    #[test]
+    fn quat_is_finite(){
+        assert!(Quaternion::identity().is_finite());
+        assert!(!Quaternion::new(f64::NAN, 0.0, 0.0, 0.0).is_finite());
+        assert!(!Quaternion::new(1.0, 0.0, f64::INFINITY, 0.0).is_finite());
+    }
+
+    #[test]
+    fn quat_normalize(){
+        let mut quat = Quaternion::new(1.0, 2.0, -2.0, 4.0);
+        assert_eq!(quat.norm(), 5.0);
+        assert!(!quat.is_normalized(1e-12));
+
+        let unit = quat.normalized();
+        almost_equal_array(&unit.to_array(), &[0.2, 0.4, -0.4, 0.8]);
+        assert!(unit.is_normalized(1e-12));
+
+        quat.normalize_mut();
+        assert_eq!(quat, unit);
+
+        // Zero is left alone
+        assert_eq!(Quaternion::of(0.0).normalized(), Quaternion::of(0.0));
+    }
+
+    #[test]
+    #[cfg(feature = "quat_auto_normalize")]
+    fn quat_auto_normalize(){
+        let quat = Quaternion::new(2.0, 0.0, 0.0, 0.0) * Quaternion::new(0.0, 3.0, 0.0, 0.0);
+        assert!(quat.is_normalized(1e-12));
+
+        // Vector transforms keep their magnitude
+        let vec = Quaternion::identity().transform(Vector3::new(0.0, 3.0, 4.0));
+        assert_eq!(vec.norm(), 5.0);
+    }
+
     // Conversions
 
     #[test]
+    fn quat_explicit_euler_orders(){
+        let (roll, pitch, yaw) = (-0.7, 0.2, 2.5);
+        let x = Quaternion::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), roll);
+        let y = Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), pitch);
+        let z = Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), yaw);
+
+        let (a, b, c) = (z * y * x).to_euler_zyx();
+        almost_equal_array(&[a, b, c], &[yaw, pitch, roll]);
+        let (a, b, c) = (x * y * z).to_euler_xyz();
+        almost_equal_array(&[a, b, c], &[roll, pitch, yaw]);
+    }
+
+    #[test]
+    fn quat_from_euler(){
+        // Grid avoiding pitch of +-90 deg
+        for roll_deg in (-170..=170).step_by(34){
+            for pitch_deg in (-80..=80).step_by(20){
+                for yaw_deg in (-170..=170).step_by(34){
+                    let (roll, pitch, yaw) = (
+                        (roll_deg as f64).to_radians(),
+                        (pitch_deg as f64).to_radians(),
+                        (yaw_deg as f64).to_radians()
+                    );
+                    let quat = Quaternion::from_euler(roll, pitch, yaw);
+                    assert!(quat.is_normalized(1e-12));
+
+                    // Angles cross zero, so compare absolutely
+                    let (a, b, c) = quat.to_euler_zyx();
+                    assert!((Vector3::new(a, b, c) - Vector3::new(yaw, pitch, roll)).norm() < 1e-9);
+                    let dcm_error = quat.to_dcm() - Vector3::new(yaw, pitch, roll).to_dcm();
+                    assert!(dcm_error.norm() < 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn quat_random(){
+        let mut rng = StdRng::seed_from_u64(7);
+        let quats: Vec<Quaternion> = (0..1000).map(|_| Quaternion::random(&mut rng)).collect();
+        assert!(quats.iter().all(|quat| quat.is_normalized(1e-12)));
+
+        // Same seed, same sequence
+        let mut rng = StdRng::seed_from_u64(7);
+        assert!(quats.iter().all(|quat| *quat == Quaternion::random(&mut rng)));
+
+        // Uniform rotations leave no preferred direction
+        let x = Vector3::new(1.0, 0.0, 0.0);
+        let mean = quats.iter().fold(Vector3::zeros(), |sum, quat| sum + quat.transform(x)) / 1000.0;
+        assert!(mean.norm() < 0.1);
+
+        // Round trip through the dcm over many attitudes
+        for quat in quats{
+            let quat_back = quat.to_dcm().to_quat();
+            assert!(quat.angle(quat_back) < 1e-6);
+        }
+    }
+
+    #[test]
+    fn quat_try_new(){
+        let quat = Quaternion::try_new(2.0, 0.0, 0.0, 0.0).unwrap();
+        assert_eq!(quat, Quaternion::identity());
+        assert!(Quaternion::try_new(0.0, 1e-13, 0.0, 0.0).is_none());
+        assert!(Quaternion::try_new(f64::NAN, 1.0, 0.0, 0.0).is_none());
+        assert!(Quaternion::try_new(0.0, 3.0, 0.0, 4.0).unwrap().is_normalized(1e-15));
+    }
+
+    #[test]
+    #[allow(deprecated)]
     fn quat_to_euler(){
         // Identity
         let quat = Quaternion::identity();
@@ -184,6 +736,64 @@ mod tests {
 
     }
 
+    #[test]
+    fn quat_to_euler_checked(){
+        // Level
+        let (_, is_singular) = Quaternion::identity().to_euler_checked();
+        assert!(!is_singular);
+
+        // Same angles and order as to_euler_zyx
+        let ((yaw, pitch, roll), is_singular) = Quaternion::from_euler(0.3, -0.2, 1.1)
+            .to_euler_checked();
+        almost_equal_array(&[yaw, pitch, roll], &[1.1, -0.2, 0.3]);
+        assert!(!is_singular);
+
+        // Pitched 45 deg
+        let half = std::f64::consts::PI / 8.0;
+        let (_, is_singular) = Quaternion::new(half.cos(), 0.0, half.sin(), 0.0)
+            .to_euler_checked();
+        assert!(!is_singular);
+
+        // Pitched 89.9 deg
+        let half = 89.9_f64.to_radians() / 2.0;
+        let (_, is_singular) = Quaternion::new(half.cos(), 0.0, half.sin(), 0.0)
+            .to_euler_checked();
+        assert!(is_singular);
+    }
+
+    #[test]
+    fn quat_from_dcm_fuzz(){
+        let mut rng = StdRng::seed_from_u64(0);
+        let unit = Uniform::new(-1.0, 1.0);
+        let angle = Uniform::new(-PI, PI);
+
+        for _ in 0..10_000{
+            let axis = Vector3::new(unit.sample(&mut rng), unit.sample(&mut rng), unit.sample(&mut rng));
+            let angle_rad = angle.sample(&mut rng);
+            let dcm = Matrix3x3::from_axis_angle(axis, angle_rad);
+
+            // Same rotation up to sign
+            let quat = Quaternion::from_dcm(dcm);
+            let expected = Quaternion::from_axis_angle(axis, angle_rad);
+            let error = (quat - expected).norm().min((quat + expected).norm());
+            assert!(error < 1e-12);
+            assert!(quat.a >= 0.0);
+
+            let round_trip = quat.to_dcm().to_array();
+            for (x, y) in round_trip.iter().zip(dcm.to_array().iter()){
+                assert!((x - y).abs() < 1e-12);
+            }
+        }
+
+        // All four candidates equal, the cyclic permutation matrix
+        let dcm = Matrix3x3::new(0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0);
+        let quat = Quaternion::from_dcm(dcm);
+        assert!(quat.is_normalized(1e-12));
+        for (x, y) in quat.to_dcm().to_array().iter().zip(dcm.to_array().iter()){
+            assert!((x - y).abs() < 1e-12);
+        }
+    }
+
     #[test]
     fn quat_to_dcm(){
         // Identity
@@ -211,6 +821,7 @@ mod tests {
     // Derivative
 
     #[test]
+    #[allow(deprecated)]
     fn quat_derivative_x(){
         let mut quat = Quaternion::identity();
         let rate = Vector3::new(0.1, 0.0, 0.0);
@@ -229,6 +840,7 @@ mod tests {
 
     }
     #[test]
+    #[allow(deprecated)]
     fn quat_derivative_y(){
         let mut quat = Quaternion::identity();
         let rate = Vector3::new(0.0, 0.1, 0.0);
@@ -247,6 +859,7 @@ mod tests {
 
     }
     #[test]
+    #[allow(deprecated)]
     fn quat_derivative_z(){
         let mut quat = Quaternion::identity();
         let rate = Vector3::new(0.0, 0.0, 0.1);
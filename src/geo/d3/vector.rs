@@ -3,11 +3,13 @@
 // ----------------------------------------------------------------------------
 
 // 3rd Party
+use std::f64::consts::PI;
 use derive_more;
+use rand::Rng;
 
 use crate::geo::{Vector2, self};
 
-use super::{quaternion::Quaternion, matrix::Matrix3x3};
+use super::{quaternion::Quaternion, matrix::{Matrix3x3, EulerSequence}};
 
 // Crate
 
@@ -66,11 +68,24 @@ impl Vector3{
         )
     }
 
+    pub fn random_unit<R: Rng + ?Sized>(rng: &mut R) -> Vector3{
+        // Uniform on the unit sphere, uniform height and azimuth
+        let z: f64 = rng.gen_range(-1.0..=1.0);
+        let azimuth: f64 = rng.gen_range(0.0..(2.0 * PI));
+        let r = (1.0 - (z * z)).max(0.0).sqrt();
+        return Vector3::new(r * azimuth.cos(), r * azimuth.sin(), z)
+    }
+
     pub fn to_array(self) -> [f64; 3]{
         // Eq: 3.1-10, Pg 3-3
         return [self.i, self.j, self.k]
     }
 
+    pub fn is_finite(&self) -> bool{
+        // False if any component is NaN or infinite
+        return self.i.is_finite() && self.j.is_finite() && self.k.is_finite()
+    }
+
     pub fn to_unit(self) -> Vector3{
         return self.clone() / self.norm()
     }
@@ -99,12 +114,20 @@ impl Vector3{
 
 
         return Matrix3x3::new(
-            _c11, _c12, _c12,
+            _c11, _c12, _c13,
             _c21, _c22, _c23,
             _c31, _c32, _c33,
         )
     }
 
+    pub fn to_dcm_sequence(self, seq: EulerSequence) -> Matrix3x3{
+        // Angles in the order they are applied, to_dcm is the same as Zyx
+        match seq{
+            EulerSequence::Zyx => return self.to_dcm(),
+            EulerSequence::Xyz => return Matrix3x3::from_xyz_euler(self.i, self.j, self.k)
+        }
+    }
+
     pub fn to_quat(self)-> Quaternion{
         return self.to_dcm().to_quat()
     }
@@ -135,9 +158,89 @@ impl Vector3{
         )
     }
 
+    pub fn angle_between(self, other: &Vector3) -> f64{
+        // [0, pi], atan2 keeps precision near parallel and antiparallel
+        return self.cross(other).norm().atan2(self.dot(other))
+    }
+
+    pub fn angle_between_signed(self, other: &Vector3, normal: &Vector3) -> f64{
+        // (-pi, pi], positive when self x other points along normal
+        let cross = self.cross(other);
+        let angle_rad = cross.norm().atan2(self.dot(other));
+        if cross.dot(normal) < 0.0{
+            return -angle_rad
+        }
+        return angle_rad
+    }
+
     pub fn error(self, target: Vector3) -> Vector3{
         return target - self
     }
+
+    pub fn project_onto(self, onto: &Vector3) -> Vector3{
+        // Source:
+        //    https://en.wikipedia.org/wiki/Vector_projection
+        let onto_norm_sqr = onto.dot(onto);
+        if onto_norm_sqr == 0.0{
+            return Vector3::zeros()
+        }
+        return *onto * (self.dot(onto) / onto_norm_sqr)
+    }
+
+    pub fn reject_from(self, from: &Vector3) -> Vector3{
+        // Component perpendicular to from, project + reject = self
+        return self - self.project_onto(from)
+    }
+
+    pub fn radial_transverse(self, position: &Vector3) -> (Vector3, Vector3){
+        // Split into components along and perpendicular to the position
+        // from the origin, returns (radial, transverse)
+        let radial = self.project_onto(position);
+        return (radial, self - radial)
+    }
+
+    pub fn component_min(self, vec: &Vector3) -> Vector3{
+        return Vector3::new(self.i.min(vec.i), self.j.min(vec.j), self.k.min(vec.k))
+    }
+
+    pub fn component_max(self, vec: &Vector3) -> Vector3{
+        return Vector3::new(self.i.max(vec.i), self.j.max(vec.j), self.k.max(vec.k))
+    }
+
+    pub fn abs(self) -> Vector3{
+        return Vector3::new(self.i.abs(), self.j.abs(), self.k.abs())
+    }
+
+    pub fn clamp_norm(self, max_norm: f64) -> Vector3{
+        // Scales down to max_norm, shorter vectors are unchanged
+        let norm = self.norm();
+        if norm <= max_norm{
+            return self
+        }
+        return self * (max_norm / norm)
+    }
+
+    pub fn lerp(self, other: Vector3, t: f64) -> Vector3{
+        // Linear interpolation with t clamped to [0, 1]
+        let t = t.clamp(0.0, 1.0);
+        return self + ((other - self) * t)
+    }
+
+    pub fn rotate_about_axis(self, axis: Vector3, angle_rad: f64) -> Vector3{
+        // Rodrigues' rotation formula
+        // Source:
+        //    https://en.wikipedia.org/wiki/Rodrigues%27_rotation_formula
+        if axis.norm() == 0.0{
+            return self
+        }
+        let k = axis.to_unit();
+        let cos = angle_rad.cos();
+        let sin = angle_rad.sin();
+
+        return (self * cos)
+            + (k.cross(&self) * sin)
+            + (k * (k.dot(&self) * (1.0 - cos)))
+    }
 }
 
 #[cfg(test)]
@@ -204,6 +307,163 @@ mod tests {
         );
     }
 
+    #[test]
+    fn project_reject(){
+        let vec = Vector3::new(1.0, 2.0, 3.0);
+        let onto = Vector3::new(-2.0, 0.5, 1.0);
+
+        let projection = vec.project_onto(&onto);
+        let rejection = vec.reject_from(&onto);
+
+        almost_equal_array(
+            &(projection + rejection).to_array(),
+            &vec.to_array()
+        );
+
+        // Parallel to onto and perpendicular to the rejection
+        assert_relative_eq!(
+            projection.cross(&onto).norm(),
+            0.0,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            rejection.dot(&onto),
+            0.0,
+            epsilon = 1e-12
+        );
+
+        // Zero denominator
+        almost_equal_array(
+            &vec.project_onto(&Vector3::zeros()).to_array(),
+            &[0.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn componentwise(){
+        let a = Vector3::new(1.0, -2.0, 3.0);
+        let b = Vector3::new(-1.0, 5.0, 3.0);
+
+        almost_equal_array(&a.component_min(&b).to_array(), &[-1.0, -2.0, 3.0]);
+        almost_equal_array(&a.component_max(&b).to_array(), &[1.0, 5.0, 3.0]);
+        almost_equal_array(&a.abs().to_array(), &[1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn clamp_norm(){
+        // Inside the limit
+        let inside = Vector3::new(1.0, 2.0, 2.0);
+        almost_equal_array(&inside.clamp_norm(5.0).to_array(), &inside.to_array());
+
+        // Outside the limit keeps the direction
+        let outside = Vector3::new(0.0, 6.0, 8.0);
+        let clamped = outside.clamp_norm(5.0);
+        almost_equal_array(&clamped.to_array(), &[0.0, 3.0, 4.0]);
+        assert_relative_eq!(clamped.norm(), 5.0);
+    }
+
+    #[test]
+    fn lerp(){
+        let start = Vector3::new(1.0, 2.0, 3.0);
+        let end = Vector3::new(3.0, -2.0, 4.0);
+
+        almost_equal_array(&start.lerp(end, 0.0).to_array(), &start.to_array());
+        almost_equal_array(&start.lerp(end, 1.0).to_array(), &end.to_array());
+        almost_equal_array(&start.lerp(end, 0.5).to_array(), &[2.0, 0.0, 3.5]);
+
+        // Clamped outside of [0, 1]
+        almost_equal_array(&start.lerp(end, -1.0).to_array(), &start.to_array());
+        almost_equal_array(&start.lerp(end, 2.0).to_array(), &end.to_array());
+    }
+
+    #[test]
+    fn rotate_about_axis(){
+        let x = Vector3::new(1.0, 0.0, 0.0);
+        let rotated = x.rotate_about_axis(
+            Vector3::new(0.0, 0.0, 2.0),
+            std::f64::consts::PI / 2.0
+        );
+
+        assert_relative_eq!(rotated.i, 0.0, epsilon = 1e-12);
+        assert_relative_eq!(rotated.j, 1.0, epsilon = 1e-12);
+        assert_relative_eq!(rotated.k, 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn radial_transverse(){
+        // Circular motion about the origin has no radial velocity
+        let radius_m = 3.0;
+        let rate_radps = 0.7;
+        for i in 0..16{
+            let angle_rad = i as f64 * 0.4;
+            let position = Vector3::new(
+                radius_m * angle_rad.cos(),
+                radius_m * angle_rad.sin(),
+                0.0
+            );
+            let velocity = Vector3::new(
+                -radius_m * rate_radps * angle_rad.sin(),
+                radius_m * rate_radps * angle_rad.cos(),
+                0.0
+            );
+
+            let (radial, transverse) = velocity.radial_transverse(&position);
+            assert_relative_eq!(radial.norm(), 0.0, epsilon = 1e-12);
+            assert_relative_eq!(transverse.norm(), radius_m * rate_radps, epsilon = 1e-12);
+        }
+
+        // Components sum back to the original vector
+        let velocity = Vector3::new(1.0, -2.0, 0.5);
+        let (radial, transverse) = velocity.radial_transverse(&Vector3::new(2.0, 1.0, 1.0));
+        almost_equal_array(&(radial + transverse).to_array(), &velocity.to_array());
+        assert_relative_eq!(radial.dot(&transverse), 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn angle_between(){
+        use std::f64::consts::PI;
+        let x = Vector3::new(1.0, 0.0, 0.0);
+        let y = Vector3::new(0.0, 1.0, 0.0);
+        let z = Vector3::new(0.0, 0.0, 1.0);
+
+        assert_relative_eq!(x.angle_between(&y), PI / 2.0);
+        assert_eq!(x.angle_between(&(x * 3.0)), 0.0);
+        assert_eq!(x.angle_between(&-x), PI);
+
+        // acos of the normalized dot product rounds to zero here
+        let nearly_x = Vector3::new(1.0, 1e-9, 0.0);
+        assert_relative_eq!(x.angle_between(&nearly_x), 1e-9, max_relative = 1e-9);
+
+        assert_relative_eq!(x.angle_between_signed(&y, &z), PI / 2.0);
+        assert_relative_eq!(y.angle_between_signed(&x, &z), -PI / 2.0);
+        assert_relative_eq!(x.angle_between_signed(&y, &-z), -PI / 2.0);
+    }
+
+    #[test]
+    fn is_finite(){
+        assert!(Vector3::new(1.0, -2.0, 3.0).is_finite());
+        assert!(!Vector3::new(1.0, f64::NAN, 3.0).is_finite());
+        assert!(!Vector3::new(1.0, 2.0, f64::NEG_INFINITY).is_finite());
+    }
+
+    #[test]
+    fn random_unit(){
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let mut rng = StdRng::seed_from_u64(11);
+        let vecs: Vec<Vector3> = (0..1000).map(|_| Vector3::random_unit(&mut rng)).collect();
+        assert!(vecs.iter().all(|vec| (vec.norm() - 1.0).abs() < 1e-12));
+
+        let mut rng = StdRng::seed_from_u64(11);
+        assert!(vecs.iter().all(|vec| *vec == Vector3::random_unit(&mut rng)));
+
+        // No preferred direction, each octant gets a share
+        let mean = vecs.iter().fold(Vector3::zeros(), |sum, vec| sum + *vec) / 1000.0;
+        assert!(mean.norm() < 0.1);
+        let upper = vecs.iter().filter(|vec| vec.k > 0.0 && vec.i > 0.0 && vec.j > 0.0).count();
+        assert!(upper > 80 && upper < 170);
+    }
+
     #[test]
     fn from_spherical(){
         // Identity check
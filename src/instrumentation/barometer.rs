@@ -0,0 +1,135 @@
+use rand_distr::{Normal, Distribution};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::sim;
+use crate::forward_models::Atmosphere;
+
+pub struct Barometer{
+    atmosphere: Atmosphere,
+    noise_std_pa: f64,
+    noise_pa: f64,
+    measured_pressure_pa: f64,
+    measured_altitude_m: f64,
+    rng: StdRng
+}
+
+impl Barometer{
+    pub fn new(noise_std_pa: f64) -> Barometer{
+        let atmosphere = Atmosphere::new();
+        return Barometer{
+            atmosphere,
+            noise_std_pa,
+            noise_pa: 0.0,
+            measured_pressure_pa: atmosphere.pressure_pa(0.0),
+            measured_altitude_m: 0.0,
+            rng: StdRng::from_entropy()
+        }
+    }
+
+    pub fn new_seeded(noise_std_pa: f64, seed: u64) -> Barometer{
+        // Bit-reproducible noise for re-runs
+        let mut barometer = Barometer::new(noise_std_pa);
+        barometer.rng = StdRng::seed_from_u64(seed);
+        return barometer
+    }
+
+    pub fn measure_pressure_pa(&mut self, true_alt_m: f64) -> f64{
+        // Noise is added in the pressure domain
+        let distr = Normal::new(0.0, self.noise_std_pa).expect(
+            "Could not create normal distribution from Barometer output"
+        );
+        self.noise_pa = distr.sample(&mut self.rng);
+        self.measured_pressure_pa = self.atmosphere.pressure_pa(true_alt_m) + self.noise_pa;
+
+        return self.measured_pressure_pa
+    }
+
+    pub fn measure_altitude_m(&mut self, true_alt_m: f64) -> f64{
+        // Altitude error grows with altitude since dP/dh shrinks with density
+        let pressure_pa = self.measure_pressure_pa(true_alt_m);
+        self.measured_altitude_m = self.atmosphere.pressure_altitude_m(pressure_pa);
+
+        return self.measured_altitude_m
+    }
+}
+
+impl sim::Save for Barometer{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        runtime.add_or_set(format!(
+            "{node_name}.measured_altitude [m]").as_str(),
+            self.measured_altitude_m
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.measured_pressure [Pa]").as_str(),
+            self.measured_pressure_pa
+        );
+        runtime.add_or_set(format!(
+            "{node_name}.noise [Pa]").as_str(),
+            self.noise_pa
+        );
+    }
+
+    fn save_data_verbose(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        self.save_data(node_name, runtime);
+
+        runtime.add_or_set(format!(
+            "{node_name}.noise_std [Pa]").as_str(),
+            self.noise_std_pa
+        );
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Tests
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::STANDARD_GRAVITY_MPS2;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn noiseless(){
+        let mut barometer = Barometer::new(0.0);
+        for alt_m in [0.0, 500.0, 2000.0, 15000.0]{
+            assert_relative_eq!(barometer.measure_altitude_m(alt_m), alt_m, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn seeded_repeatable(){
+        let mut first = Barometer::new_seeded(10.0, 42);
+        let mut second = Barometer::new_seeded(10.0, 42);
+        let mut other = Barometer::new_seeded(10.0, 43);
+
+        let mut differs = false;
+        for i in 0..100{
+            let alt_m = i as f64 * 10.0;
+            let value = first.measure_altitude_m(alt_m);
+            assert_eq!(value, second.measure_altitude_m(alt_m));
+            differs |= value != other.measure_altitude_m(alt_m);
+        }
+        assert!(differs);
+    }
+
+    #[test]
+    fn sea_level_noise(){
+        // dh = -dP / (rho * g)
+        let rho_kgpm3 = Atmosphere::new().density_kgpm3(0.0);
+
+        let mut barometer = Barometer::new(10.0);
+
+        for _ in 0..100{
+            let altitude_m = barometer.measure_altitude_m(0.0);
+            let expected_m = -barometer.noise_pa / (rho_kgpm3 * STANDARD_GRAVITY_MPS2);
+
+            assert_relative_eq!(
+                altitude_m,
+                expected_m,
+                max_relative = 1e-3
+            );
+        }
+    }
+}
@@ -1,2 +1,4 @@
 pub mod sensors;
-pub use sensors::BasicSensor;
\ No newline at end of file
+pub use sensors::BasicSensor;
+pub mod barometer;
+pub use barometer::Barometer;
\ No newline at end of file
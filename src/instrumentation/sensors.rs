@@ -1,15 +1,25 @@
 use rand_distr::{Normal, Distribution};
-use rand::thread_rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 
-use crate::sim;
+use crate::{sim, control};
 
 
+#[derive(
+    Debug,
+    Clone
+)]
+
 pub struct BasicSensor{
     std: f64,
     measured_value: f64,
     output_slope: f64,
     output_offset: f64,
-    units: String
+    units: String,
+    quantization_lsb: f64,
+    output_min: f64,
+    output_max: f64,
+    rng: StdRng
 }
 
 impl BasicSensor{
@@ -24,10 +34,27 @@ impl BasicSensor{
             measured_value: 0.0,
             output_slope,
             output_offset,
-            units: units.to_string()
+            units: units.to_string(),
+            quantization_lsb: 0.0,
+            output_min: f64::NEG_INFINITY,
+            output_max: f64::INFINITY,
+            rng: StdRng::from_entropy()
         }
     }
 
+    pub fn new_std_seeded(
+        std: f64,
+        output_slope: f64,
+        output_offset: f64,
+        units: &str,
+        seed: u64
+    ) -> BasicSensor{
+        // Bit-reproducible noise for re-runs
+        let mut sensor = BasicSensor::new_std(std, output_slope, output_offset, units);
+        sensor.rng = StdRng::seed_from_u64(seed);
+        return sensor
+    }
+
     pub fn new_simple_from_std(std: f64, units: &str) -> BasicSensor{
         return BasicSensor::new_std(std, 1.0, 0.0, units)
     }
@@ -36,11 +63,33 @@ impl BasicSensor{
         return BasicSensor::new_std(variance.sqrt(), 1.0, 0.0, units)
     }
 
+    pub fn set_quantization_lsb(&mut self, lsb: f64){
+        // Outputs are rounded to the nearest multiple of lsb, 0 disables
+        if lsb < 0.0{
+            panic!("    ERROR| Quantization lsb [{}] must be non-negative", lsb)
+        }
+        self.quantization_lsb = lsb;
+    }
+
+    pub fn set_saturation(&mut self, min: f64, max: f64){
+        if min > max{
+            panic!("    ERROR| Saturation min [{}] is greater than max [{}]", min, max)
+        }
+        self.output_min = min;
+        self.output_max = max;
+    }
+
     pub fn output(&mut self, actual_value: f64) -> f64{
+        // Noise, then quantization, then saturation
         let distr = Normal::new(actual_value, self.std).expect(
             "Could not create normal distribution from BasicSensor output"
         );
-        self.measured_value =  distr.sample(&mut thread_rng());
+        let mut measured_value = distr.sample(&mut self.rng);
+
+        if self.quantization_lsb > 0.0{
+            measured_value = (measured_value / self.quantization_lsb).round() * self.quantization_lsb;
+        }
+        self.measured_value = control::clamp_ex(measured_value, self.output_max, self.output_min).value;
 
         return self.measured_value
     }
@@ -69,5 +118,60 @@ impl sim::Save for BasicSensor{
             "{node_name}.output_offset [-]").as_str(),
             self.output_offset
         );
+        runtime.add_or_set(format!(
+            "{}.quantization_lsb [{}]", node_name, self.units).as_str(),
+            self.quantization_lsb
+        );
+        runtime.add_or_set(format!(
+            "{}.output_min [{}]", node_name, self.units).as_str(),
+            self.output_min
+        );
+        runtime.add_or_set(format!(
+            "{}.output_max [{}]", node_name, self.units).as_str(),
+            self.output_max
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::Save;
+
+    #[test]
+    fn quantization(){
+        let lsb = 0.1;
+        let mut sensor = BasicSensor::new_std_seeded(0.05, 1.0, 0.0, "m", 0);
+        sensor.set_quantization_lsb(lsb);
+
+        for i in 0..=1000{
+            let output = sensor.output(i as f64 / 1000.0);
+            let counts = (output / lsb).round();
+            assert_eq!(output, counts * lsb);
+        }
+    }
+
+    #[test]
+    fn saturation(){
+        let (min, max) = (-2.0, 2.0);
+        let mut sensor = BasicSensor::new_std_seeded(1.0, 1.0, 0.0, "m", 0);
+        sensor.set_saturation(min, max);
+
+        // With 10000 samples some are past 3 sigma
+        let mut clipped = 0;
+        for _ in 0..10000{
+            let output = sensor.output(0.0);
+            assert!(output >= min && output <= max);
+            if output == max || output == min{
+                clipped += 1;
+            }
+        }
+        assert!(clipped > 0);
+
+        // Settings are recorded in verbose mode
+        let mut runtime = sim::Runtime::new(1.0, 0.1, "time [s]");
+        sensor.save_data_verbose("sensor", &mut runtime);
+        assert_eq!(runtime.get_value("sensor.output_max [m]"), max);
+        assert_eq!(runtime.get_value("sensor.quantization_lsb [m]"), 0.0);
     }
 }
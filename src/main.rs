@@ -11,8 +11,68 @@ mod instrumentation;
 mod geo;
 mod forward_models;
 
-fn main() {
-    let mut runtime = sim::Runtime::new(20.0, 1e-3, "time [s]");
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario{
+    pub name: String,
+    pub duration_s: f64,
+    pub dt_s: f64,
+    pub target_altitude_m: f64,
+    pub climb_rate_mps: f64,
+    pub max_thrust_n: f64,
+    pub drag_cd: f64,
+    pub drag_area_m2: f64
+}
+
+impl Default for Scenario{
+    fn default() -> Scenario{
+        // The original hardcoded hopper demo
+        return Scenario{
+            name: "test".to_string(),
+            duration_s: 20.0,
+            dt_s: 1e-3,
+            target_altitude_m: 5.0,
+            climb_rate_mps: 0.3,
+            max_thrust_n: 20.0,
+            drag_cd: 0.8,
+            drag_area_m2: 0.05
+        }
+    }
+}
+
+impl Scenario{
+    pub fn from_args(args: &[String]) -> Scenario{
+        // key=value overrides of the default scenario
+        let mut scenario = Scenario::default();
+        for arg in args.iter(){
+            let (key, value) = match arg.split_once('='){
+                Some(pair) => pair,
+                None => panic!("    ERROR| Scenario argument [{}] is not key=value", arg)
+            };
+            if key == "name"{
+                scenario.name = value.to_string();
+                continue
+            }
+
+            let value: f64 = value.parse().unwrap_or_else(
+                |_| panic!("    ERROR| Scenario value [{}] for [{}] is not a number", value, key)
+            );
+            match key{
+                "duration_s" => scenario.duration_s = value,
+                "dt_s" => scenario.dt_s = value,
+                "target_altitude_m" => scenario.target_altitude_m = value,
+                "climb_rate_mps" => scenario.climb_rate_mps = value,
+                "max_thrust_n" => scenario.max_thrust_n = value,
+                "drag_cd" => scenario.drag_cd = value,
+                "drag_area_m2" => scenario.drag_area_m2 = value,
+                _ => panic!("    ERROR| Unknown scenario key [{}]", key)
+            }
+        }
+        return scenario
+    }
+}
+
+pub fn run_scenario(scenario: &Scenario) -> Runtime{
+    let mut runtime = sim::Runtime::new(scenario.duration_s, scenario.dt_s, "time [s]");
 
     let mut test_object = physics::RigidBody::new(
         [0.0, 0.0, -9.8],
@@ -22,7 +82,7 @@ fn main() {
         [0.0, 0.0, 0.0],
         [0.0, 0.0, 0.0],
         [0.0, 0.0, 0.0],
-        [1.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0, 0.0],
         [0.0, 0.0, 0.0],
         [0.0, 0.0, 0.0],
         1.0,
@@ -36,14 +96,20 @@ fn main() {
     let i_quat = geo::Quaternion::identity();
 
     // PID
-    let mut altitude_ramp = control::Ramp::new(0.0, 5.0, 0.3);
+    let mut altitude_ramp = control::Ramp::new(0.0, scenario.target_altitude_m, scenario.climb_rate_mps);
     let mut pid_alt = control::PID::new(0.25, 0.001, 0.0, 0.0);
+    pid_alt.set_initial_output(9.8 * test_object.mass_cg_kg);
     let mut pid_x = control::PID::new(0.1, 0.0, 0.0, 0.0);
     let mut pid_y = control::PID::new(0.1, 0.0, 0.0, 0.0);
     let mut pid_z = control::PID::new(0.01, 0.0, 0.0, 0.0);
 
     // Servo TVC
-    let mut tvc = forward_models::BasicTVC::new(14.5, [0.0,0.0,-0.1], 0.0, 0.0, 0.5, 20.0);
+    let mut tvc = forward_models::BasicTVC::new(14.5, [0.0,0.0,-0.1], 0.0, 0.0, 0.5, scenario.max_thrust_n);
+
+    // Environment
+    let gravity = forward_models::Gravity::new_constant(geo::Vector3::new(0.0, 0.0, -9.8));
+    let atmosphere = forward_models::Atmosphere::new();
+    let mut drag = forward_models::DragModel::new(scenario.drag_cd, scenario.drag_area_m2);
     
 
     // Instrumentation
@@ -57,24 +123,88 @@ fn main() {
         pid_y.save_data_verbose("pid_y", &mut runtime);
         pid_z.save_data_verbose("pid_z", &mut runtime);
         tvc.save_data_verbose("tvc", &mut runtime);
+        drag.save_data("drag", &mut runtime);
 
         // Pid Controllers
         pid_alt.setpoint = altitude_ramp.output(dt);
 
-        let euler_error = (test_object.get_quat().error(i_quat)).to_euler();
+        let (_, pitch_error, roll_error) = test_object.get_quat().error(i_quat).to_euler_zyx();
 
-        tvc.set_thrust_n(pid_alt.ouput(test_object.get_intertial_pos_m().k, dt) + 9.8);
-        tvc.set_theta_rad(pid_x.ouput(euler_error.i, dt));
-        tvc.set_phi_rad(pid_y.ouput(euler_error.j, dt));
+        tvc.set_thrust_n(pid_alt.ouput(test_object.get_intertial_pos_m().k, dt));
+        tvc.set_theta_rad(pid_x.ouput(roll_error, dt));
+        tvc.set_phi_rad(pid_y.ouput(pitch_error, dt));
 
         // Apply force and moments
         test_object.body_force_n = tvc.get_thrust_vec_n();
         test_object.body_moment_nm = tvc.get_moment_vec_nm();
+        test_object.intertial_force_n = drag.update(
+            test_object.get_intertial_vel_mps(),
+            atmosphere.density_kgpm3(test_object.get_intertial_pos_m().k)
+        ) + (gravity.accel_at(test_object.get_intertial_pos_m()) * test_object.mass_cg_kg);
 
         // Integrate and increment sim
         test_object = test_object.rk4(dt);
         runtime.increment();
     }
 
-    runtime.export_to_csv("results/data/test.csv");
+    // The loop stops on the last row, record the final state there. The
+    // controllers and models hold their last outputs.
+    test_object.save_data_verbose("hopper", &mut runtime);
+
+    return runtime
+}
+
+pub fn run_to_csv(scenario: &Scenario, output_dir: &str) -> String{
+    // Writes {output_dir}/{name}.csv and returns its path
+    if let Err(err) = std::fs::create_dir_all(output_dir){
+        panic!("    ERROR| Could not create output dir [{}]: {}", output_dir, err)
+    }
+    let file_path = std::path::Path::new(output_dir).join(format!("{}.csv", scenario.name));
+    let file_path = file_path.to_string_lossy().to_string();
+
+    let mut runtime = run_scenario(scenario);
+    runtime.export_to_csv(&file_path);
+    return file_path
+}
+
+fn main() {
+    // slippy [output_dir] [key=value ...]
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let output_dir = args.first().map(|arg| arg.as_str()).unwrap_or("results/data");
+    let scenario = Scenario::from_args(args.get(1..).unwrap_or(&[]));
+
+    let file_path = run_to_csv(&scenario, output_dir);
+    println!("Wrote [{}] to [{}]", scenario.name, file_path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scenario_to_csv(){
+        let scenario = Scenario::from_args(&[
+            "name=short_hop".to_string(),
+            "duration_s=0.5".to_string(),
+            "dt_s=0.01".to_string()
+        ]);
+        assert_eq!(scenario.target_altitude_m, Scenario::default().target_altitude_m);
+
+        let dir = std::env::temp_dir().join("slippy_scenario");
+        let file_path = run_to_csv(&scenario, dir.to_str().unwrap());
+        assert!(file_path.ends_with("short_hop.csv"));
+
+        let runtime = Runtime::from_csv(&file_path, "time [s]").unwrap();
+        let keys = runtime.list_keys();
+        for key in ["hopper.inertial_pos.z [m]", "tvc.total_thrust [N]", "drag.force.z [N]", "pid_alt.output [-]"]{
+            assert!(keys.iter().any(|k| k == key), "missing {key}");
+        }
+        assert!(runtime.get_max_x() >= 0.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn scenario_unknown_key(){
+        Scenario::from_args(&["altitude=3".to_string()]);
+    }
 }
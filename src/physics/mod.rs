@@ -1,2 +1,6 @@
 pub mod rigidbody;
-pub use rigidbody::RigidBody;
\ No newline at end of file
+pub use rigidbody::RigidBody;
+pub mod system;
+pub use system::{ForceElement, System};
+pub mod tether;
+pub use tether::SpringDamperTether;
\ No newline at end of file
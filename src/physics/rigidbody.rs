@@ -55,8 +55,13 @@ impl RigidBody{
         i_tensor_cg_kgpm2: [f64; 9]
     ) -> RigidBody{
 
+        // Any non-zero quaternion is accepted and normalized
+        let quat_b2i = geo::Quaternion::try_new(quat_b2i[0], quat_b2i[1], quat_b2i[2], quat_b2i[3])
+            .unwrap_or_else(|| panic!("    ERROR| Attitude quaternion {:?} is not a rotation", quat_b2i));
+
         // Precompute inverse of Inertia tensor
         let i_tensor_cg_kgpm2 = geo::Matrix3x3::from_array(i_tensor_cg_kgpm2);
+        check_i_tensor(i_tensor_cg_kgpm2);
         let inv_i_tensor_cg_kgpm2 = i_tensor_cg_kgpm2.inv()
             .expect("i_tensor_cg_kgpm2 was not invertible");
 
@@ -69,7 +74,7 @@ impl RigidBody{
             inertial_pos_m: geo::Vector3::from_array(inertial_pos_m),
             inertial_vel_mps: geo::Vector3::from_array(inertial_vel_mps),
             inertial_accel_mps2: geo::Vector3::from_array(inertial_accel_mps2),
-            quat_b2i: geo::Quaternion::from_array(quat_b2i),
+            quat_b2i,
             body_ang_vel_radps: geo::Vector3::from_array(body_ang_vel_radps),
             body_ang_accel_radps2: geo::Vector3::from_array(body_ang_accel_radps2),
             mass_cg_kg,
@@ -114,6 +119,74 @@ impl RigidBody{
         }
     }
 
+    pub fn from_state_array(
+        state: [f64; 13],
+        mass_cg_kg: f64,
+        i_tensor_cg_kgpm2: [f64; 9]
+    ) -> RigidBody{
+        // Inverse of to_state_array, loads and accelerations start at zero
+        return RigidBody::new(
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [state[0], state[1], state[2]],
+            [state[3], state[4], state[5]],
+            [0.0, 0.0, 0.0],
+            [state[6], state[7], state[8], state[9]],
+            [state[10], state[11], state[12]],
+            [0.0, 0.0, 0.0],
+            mass_cg_kg,
+            i_tensor_cg_kgpm2
+        )
+    }
+
+    pub fn with_attitude_euler(self, roll_rad: f64, pitch_rad: f64, yaw_rad: f64) -> RigidBody{
+        // Yaw, pitch then roll from the inertial frame
+        let dcm = geo::Vector3::new(yaw_rad, pitch_rad, roll_rad).to_dcm();
+        return self.with_attitude_dcm(dcm)
+    }
+
+    pub fn with_attitude_quat(mut self, quat_b2i: geo::Quaternion) -> RigidBody{
+        // Normalized with a non-negative scalar part
+        if !quat_b2i.is_finite() || quat_b2i.norm() == 0.0{
+            panic!("    ERROR| Attitude quaternion {:?} is not a rotation", quat_b2i)
+        }
+        self.quat_b2i = quat_b2i.normalized().canonical();
+        return self
+    }
+
+    pub fn with_attitude_dcm(mut self, dcm_b2i: geo::Matrix3x3) -> RigidBody{
+        // Must be a proper rotation to within 1e-6, the remaining error is
+        // removed before conversion
+        let orthogonality = (dcm_b2i * dcm_b2i.transpose()) - geo::Matrix3x3::identity();
+        if !dcm_b2i.is_finite() || orthogonality.norm() > 1e-6 || (dcm_b2i.det() - 1.0).abs() > 1e-6{
+            panic!("    ERROR| Attitude dcm {:?} is not a rotation", dcm_b2i)
+        }
+        self.quat_b2i = dcm_b2i.orthonormalize().to_quat();
+        return self
+    }
+
+    pub fn is_finite(&self) -> bool{
+        // False if any part of the state is NaN or infinite
+        return self.to_state_array().iter().all(|x| x.is_finite())
+    }
+
+    pub fn to_state_array(&self) -> [f64; 13]{
+        // [position, velocity, quaternion, body rates]
+        let pos = self.inertial_pos_m;
+        let vel = self.inertial_vel_mps;
+        let quat = self.quat_b2i;
+        let rate = self.body_ang_vel_radps;
+
+        return [
+            pos.i, pos.j, pos.k,
+            vel.i, vel.j, vel.k,
+            quat.a, quat.b, quat.c, quat.d,
+            rate.i, rate.j, rate.k
+        ]
+    }
+
     pub fn get_intertial_pos_m(&self) -> geo::Vector3{
         return self.inertial_pos_m
     }
@@ -137,6 +210,136 @@ impl RigidBody{
     pub fn get_body_ang_accel_radps2(&self) -> geo::Vector3{
         return self.body_ang_accel_radps2
     }
+
+    pub fn get_i_tensor_cg_kgpm2(&self) -> geo::Matrix3x3{
+        return self.i_tensor_cg_kgpm2
+    }
+
+    pub fn set_mass_properties(&mut self, mass_cg_kg: f64, i_tensor_cg_kgpm2: geo::Matrix3x3){
+        // For bodies that lose mass, the state is left unchanged
+        if mass_cg_kg <= 0.0{
+            panic!("    ERROR| Rigidbody mass must be positive, got [{}]", mass_cg_kg)
+        }
+        check_i_tensor(i_tensor_cg_kgpm2);
+        self.inv_i_tensor_cg_kgpm2 = i_tensor_cg_kgpm2.inv()
+            .expect("i_tensor_cg_kgpm2 was not invertible");
+        self.i_tensor_cg_kgpm2 = i_tensor_cg_kgpm2;
+        self.mass_cg_kg = mass_cg_kg;
+    }
+
+    pub fn apply_body_force_at(&mut self, force_n: geo::Vector3, offset_from_cg_m: geo::Vector3){
+        // Body frame force at a point, adds to the force and the induced
+        // moment r x F. Accumulates, clear body_force_n and body_moment_nm
+        // each step.
+        self.body_force_n = self.body_force_n + force_n;
+        self.body_moment_nm = self.body_moment_nm + offset_from_cg_m.cross(&force_n);
+    }
+
+    pub fn merge(&self, other: &RigidBody, contact_offset: geo::Vector3) -> RigidBody{
+        // Docks other onto self, contact_offset is the vector from self's cg
+        // to other's cg in self's body frame. The merged body keeps self's
+        // attitude, loads are cleared and must be reapplied.
+        let mass_cg_kg = self.mass_cg_kg + other.mass_cg_kg;
+
+        // Offsets from the combined cg, body frame
+        let combined_cg_m = contact_offset * (other.mass_cg_kg / mass_cg_kg);
+        let self_offset_m = -combined_cg_m;
+        let other_offset_m = contact_offset - combined_cg_m;
+
+        // Rotate the other tensor into self's body frame
+        let other_to_self = self.quat_b2i.to_dcm().transpose()
+            * other.quat_b2i.to_dcm();
+        let other_i_tensor =
+            other_to_self * other.i_tensor_cg_kgpm2 * other_to_self.transpose();
+
+        let i_tensor_cg_kgpm2 =
+            parallel_axis(self.i_tensor_cg_kgpm2, self.mass_cg_kg, self_offset_m)
+            + parallel_axis(other_i_tensor, other.mass_cg_kg, other_offset_m);
+        let inv_i_tensor_cg_kgpm2 = i_tensor_cg_kgpm2.inv()
+            .expect("merged i_tensor_cg_kgpm2 was not invertible");
+
+        // Conservation of linear momentum
+        let inertial_pos_m =
+            self.inertial_pos_m + self.quat_b2i.transform(combined_cg_m);
+        let inertial_vel_mps = (
+            (self.inertial_vel_mps * self.mass_cg_kg)
+            + (other.inertial_vel_mps * other.mass_cg_kg)
+        ) / mass_cg_kg;
+
+        // Conservation of angular momentum about the combined cg
+        let momentum_nms =
+            self.angular_momentum_about(inertial_pos_m, inertial_vel_mps)
+            + other.angular_momentum_about(inertial_pos_m, inertial_vel_mps);
+        let body_ang_vel_radps = inv_i_tensor_cg_kgpm2
+            * self.quat_b2i.conjugate().transform(momentum_nms);
+
+        return RigidBody {
+            intertial_force_n: geo::Vector3::zeros(),
+            intertial_moment_nm: geo::Vector3::zeros(),
+            body_force_n: geo::Vector3::zeros(),
+            body_moment_nm: geo::Vector3::zeros(),
+            inertial_pos_m,
+            inertial_vel_mps,
+            inertial_accel_mps2: geo::Vector3::zeros(),
+            quat_b2i: self.quat_b2i,
+            body_ang_vel_radps,
+            body_ang_accel_radps2: geo::Vector3::zeros(),
+            mass_cg_kg,
+            i_tensor_cg_kgpm2,
+            inv_i_tensor_cg_kgpm2
+        }
+    }
+
+    pub fn kinetic_energy_j(&self) -> f64{
+        // Translation plus rotation, 0.5 * m * |v|^2 + 0.5 * w . (I * w)
+        let translation_j = 0.5 * self.mass_cg_kg * self.inertial_vel_mps.norm().powi(2);
+        let rotation_j = 0.5 * self.body_ang_vel_radps.dot(&(self.i_tensor_cg_kgpm2 * self.body_ang_vel_radps));
+        return translation_j + rotation_j
+    }
+
+    pub fn angular_momentum_nms(&self) -> geo::Vector3{
+        // I * w about the cg, body frame. Only its magnitude is conserved
+        // torque-free, rotate into the inertial frame for the full vector.
+        return self.i_tensor_cg_kgpm2 * self.body_ang_vel_radps
+    }
+
+    fn angular_momentum_about(
+        &self,
+        point_m: geo::Vector3,
+        point_vel_mps: geo::Vector3
+    ) -> geo::Vector3{
+        // H = R * I * w + r x (m * v), inertial frame
+        let spin_nms = self.quat_b2i.transform(
+            self.i_tensor_cg_kgpm2 * self.body_ang_vel_radps
+        );
+        let offset_m = self.inertial_pos_m - point_m;
+        let orbit_nms = offset_m.cross(
+            &((self.inertial_vel_mps - point_vel_mps) * self.mass_cg_kg)
+        );
+
+        return spin_nms + orbit_nms
+    }
+}
+
+fn check_i_tensor(i_tensor: geo::Matrix3x3){
+    // Inertia tensors are symmetric, an asymmetric one is usually a typo in
+    // the products of inertia
+    if !i_tensor.is_symmetric(1e-6){
+        panic!("    ERROR| Inertia tensor {:?} is not symmetric", i_tensor)
+    }
+}
+
+fn parallel_axis(i_tensor: geo::Matrix3x3, mass_kg: f64, offset_m: geo::Vector3) -> geo::Matrix3x3{
+    // Source:
+    //    https://en.wikipedia.org/wiki/Parallel_axis_theorem
+    let r = offset_m;
+    let r_sqr = r.dot(&r);
+
+    return i_tensor + geo::Matrix3x3::new(
+        r_sqr - (r.i * r.i), -(r.i * r.j), -(r.i * r.k),
+        -(r.j * r.i), r_sqr - (r.j * r.j), -(r.j * r.k),
+        -(r.k * r.i), -(r.k * r.j), r_sqr - (r.k * r.k)
+    ) * mass_kg
 }
 
 impl sim::Integrate for RigidBody{
@@ -149,13 +352,20 @@ impl sim::Integrate for RigidBody{
         // Notes:
         //     accelerations act about nav frame
 
+        debug_assert!(
+            self.is_finite(),
+            "    ERROR| Non-finite rigidbody state: {:?}",
+            self.to_state_array()
+        );
+
         let total_forces_n =
             self.intertial_force_n +
             self.quat_b2i.transform(self.body_force_n);
 
+        // Euler's equations are in the body frame, so the moments are too
         let total_moments_nm =
-            self.intertial_moment_nm +
-            self.quat_b2i.transform(self.body_moment_nm);
+            self.quat_b2i.conjugate().transform(self.intertial_moment_nm) +
+            self.body_moment_nm;
 
         // F = ma
         self.inertial_accel_mps2 = total_forces_n / self.mass_cg_kg;
@@ -179,11 +389,16 @@ impl sim::Integrate for RigidBody{
     fn get_derivative(&self)-> Self {
         let mut d = RigidBody::zeros();
 
+        // Accelerations are re-evaluated at each intermediate state so the
+        // w x (I * w) coupling is integrated to the full order
+        let mut state = self.clone();
+        state.effects();
+
         // State Derviative
-        d.inertial_pos_m = self.inertial_vel_mps.clone();
-        d.inertial_vel_mps = self.inertial_accel_mps2.clone();
-        d.quat_b2i = self.quat_b2i.derivative(self.body_ang_vel_radps).clone();
-        d.body_ang_vel_radps = self.body_ang_accel_radps2.clone();
+        d.inertial_pos_m = state.inertial_vel_mps;
+        d.inertial_vel_mps = state.inertial_accel_mps2;
+        d.quat_b2i = state.quat_b2i.derivative(state.body_ang_vel_radps);
+        d.body_ang_vel_radps = state.body_ang_accel_radps2;
 
         return d
 
@@ -197,145 +412,45 @@ impl sim::Integrate for RigidBody{
 impl sim::Save for RigidBody{
     fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
         // State
-        runtime.add_or_set(format!(
-            "{node_name}.inertial_pos.x [m]").as_str(),
-            self.inertial_pos_m.i.clone()
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.inertial_pos.y [m]").as_str(),
-            self.inertial_pos_m.j.clone()
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.inertial_pos.z [m]").as_str(),
-            self.inertial_pos_m.k.clone()
-        );
-
-        runtime.add_or_set(format!(
-            "{node_name}.inertial_vel.x [m/s]").as_str(),
-            self.inertial_vel_mps.i
+        runtime.add_vector3_in_frame(
+            format!("{node_name}.inertial_pos").as_str(), "m", sim::Frame::Inertial, self.inertial_pos_m
         );
-        runtime.add_or_set(format!(
-            "{node_name}.inertial_vel.y [m/s]").as_str(),
-            self.inertial_vel_mps.j
+        runtime.add_vector3_in_frame(
+            format!("{node_name}.inertial_vel").as_str(), "m/s", sim::Frame::Inertial, self.inertial_vel_mps
         );
-        runtime.add_or_set(format!(
-            "{node_name}.inertial_vel.z [m/s]").as_str(),
-            self.inertial_vel_mps.k
+        runtime.add_vector3_in_frame(
+            format!("{node_name}.inertial_accel").as_str(), "m/s^2", sim::Frame::Inertial, self.inertial_accel_mps2
         );
-
-        runtime.add_or_set(format!(
-            "{node_name}.inertial_accel.x [m/s^2]").as_str(),
-                self.inertial_accel_mps2.i
+        runtime.add_quaternion(
+            format!("{node_name}.quat_b2i").as_str(), self.quat_b2i
         );
-        runtime.add_or_set(format!(
-            "{node_name}.inertial_accel.y [m/s^2]").as_str(),
-                self.inertial_accel_mps2.j
+        runtime.add_vector3_in_frame(
+            format!("{node_name}.body_ang_vel").as_str(), "rad/s", sim::Frame::Body, self.body_ang_vel_radps
         );
-        runtime.add_or_set(format!(
-            "{node_name}.inertial_accel.z [m/s^2]").as_str(),
-                self.inertial_accel_mps2.k
-        );
-
-        runtime.add_or_set(format!(
-            "{node_name}.quat_b2i.a [-]").as_str(),
-            self.quat_b2i.a
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.quat_b2i.b [-]").as_str(),
-            self.quat_b2i.b
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.quat_b2i.c [-]").as_str(),
-            self.quat_b2i.c
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.quat_b2i.d [-]").as_str(),
-            self.quat_b2i.d
-        );
-
-
-        runtime.add_or_set(format!(
-            "{node_name}.body_ang_vel.x [rad/s]").as_str(),
-            self.body_ang_vel_radps.i
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.body_ang_vel.y [rad/s]").as_str(),
-            self.body_ang_vel_radps.j
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.body_ang_vel.z [rad/s]").as_str(),
-            self.body_ang_vel_radps.k
-        );
-
-        runtime.add_or_set(format!(
-            "{node_name}.body_ang_accel.x [rad/s^2]").as_str(),
-            self.body_ang_accel_radps2.i
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.body_ang_accel.y [rad/s^2]").as_str(),
-            self.body_ang_accel_radps2.j
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.body_ang_accel.z [rad/s^2]").as_str(),
-            self.body_ang_accel_radps2.k
+        runtime.add_vector3_in_frame(
+            format!("{node_name}.body_ang_accel").as_str(), "rad/s^2", sim::Frame::Body, self.body_ang_accel_radps2
         );
 
         // Force and Moments
-        runtime.add_or_set(format!(
-            "{node_name}.intertial_force.x [N]").as_str(),
-            self.intertial_force_n.i
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.intertial_force.y [N]").as_str(),
-            self.intertial_force_n.j
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.intertial_force.z [N]").as_str(),
-            self.intertial_force_n.k
-        );
-
-        runtime.add_or_set(format!(
-            "{node_name}.intertial_moment.x [Nm]").as_str(),
-            self.intertial_moment_nm.i
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.intertial_moment.y [Nm]").as_str(),
-            self.intertial_moment_nm.j
+        runtime.add_vector3_in_frame(
+            format!("{node_name}.intertial_force").as_str(), "N", sim::Frame::Inertial, self.intertial_force_n
         );
-        runtime.add_or_set(format!(
-            "{node_name}.intertial_moment.z [Nm]").as_str(),
-            self.intertial_moment_nm.k
-        );
-
-        runtime.add_or_set(format!(
-            "{node_name}.body_force.x [N]").as_str(),
-            self.body_force_n.i
+        runtime.add_vector3_in_frame(
+            format!("{node_name}.intertial_moment").as_str(), "Nm", sim::Frame::Inertial, self.intertial_moment_nm
         );
-        runtime.add_or_set(format!(
-            "{node_name}.body_force.y [N]").as_str(),
-            self.body_force_n.j
+        runtime.add_vector3_in_frame(
+            format!("{node_name}.body_force").as_str(), "N", sim::Frame::Body, self.body_force_n
         );
-        runtime.add_or_set(format!(
-            "{node_name}.body_force.z [N]").as_str(),
-            self.body_force_n.k
+        runtime.add_vector3_in_frame(
+            format!("{node_name}.body_moment").as_str(), "Nm", sim::Frame::Body, self.body_moment_nm
         );
 
+        // Mass properties
         runtime.add_or_set(format!(
-            "{node_name}.body_moment.x [Nm]").as_str(),
-            self.body_moment_nm.i
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.body_moment.y [Nm]").as_str(),
-            self.body_moment_nm.j
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.body_moment.z [Nm]").as_str(),
-            self.body_moment_nm.k
+            "{node_name}.mass_cg [kg]").as_str(),
+            self.mass_cg_kg
         );
 
-        // Mass properties
-        runtime.add_or_set("mass_cg [kg]", self.mass_cg_kg);
-
     }
 
     fn save_data_verbose(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
@@ -372,7 +487,7 @@ impl sim::Save for RigidBody{
             self.i_tensor_cg_kgpm2.c31
         );
         runtime.add_or_set(format!(
-            "{node_name}.Izx [kg/m^2]").as_str(),
+            "{node_name}.Izy [kg/m^2]").as_str(),
             self.i_tensor_cg_kgpm2.c32
         );
         runtime.add_or_set(format!(
@@ -382,56 +497,22 @@ impl sim::Save for RigidBody{
 
         // Dcm representation
         let dcm = self.quat_b2i.to_dcm();
-        runtime.add_or_set(format!(
-            "{node_name}.dcm.c11 [-]").as_str(),
-            dcm.c11
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.dcm.c12 [-]").as_str(),
-            dcm.c12
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.dcm.c13 [-]").as_str(),
-            dcm.c13
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.dcm.c21 [-]").as_str(),
-            dcm.c21
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.dcm.c22 [-]").as_str(),
-            dcm.c22
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.dcm.c23 [-]").as_str(),
-            dcm.c23
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.dcm.c31 [-]").as_str(),
-            dcm.c31
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.dcm.c32 [-]").as_str(),
-            dcm.c32
-        );
-        runtime.add_or_set(format!(
-            "{node_name}.dcm.c33 [-]").as_str(),
-            dcm.c33
-        );
+        runtime.add_matrix3x3(format!("{node_name}.dcm").as_str(), "-", dcm);
 
         // Attitude conversion
-        let euler = dcm.to_euler();
+        // Z-Y-X sequence, recorded as roll, pitch, yaw
+        let (yaw, pitch, roll) = dcm.to_euler_zyx();
         runtime.add_or_set(format!(
             "{node_name}.euler.i [rad]").as_str(),
-            euler.i
+            roll
         );
         runtime.add_or_set(format!(
             "{node_name}.euler.j [rad]").as_str(),
-            euler.j
+            pitch
         );
         runtime.add_or_set(format!(
             "{node_name}.euler.k [rad]").as_str(),
-            euler.k
+            yaw
         );
     }
 }
@@ -449,6 +530,195 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn attitude_init(){
+        let (roll, pitch, yaw) = (0.3, -0.2, 1.1);
+        let from_euler = RigidBody::identity().with_attitude_euler(roll, pitch, yaw);
+
+        // Same orientation as yaw, then pitch, then roll about body axes
+        let quat = geo::Quaternion::from_axis_angle(geo::Vector3::new(0.0, 0.0, 1.0), yaw)
+            * geo::Quaternion::from_axis_angle(geo::Vector3::new(0.0, 1.0, 0.0), pitch)
+            * geo::Quaternion::from_axis_angle(geo::Vector3::new(1.0, 0.0, 0.0), roll);
+        let from_quat = RigidBody::identity().with_attitude_quat(-quat * 3.0);
+        let from_dcm = RigidBody::identity().with_attitude_dcm(quat.to_dcm());
+
+        almost_equal_array(&from_euler.get_quat().to_array(), &from_quat.get_quat().to_array());
+        almost_equal_array(&from_euler.get_quat().to_array(), &from_dcm.get_quat().to_array());
+        let (yaw_out, pitch_out, roll_out) = from_euler.get_quat().to_euler_zyx();
+        almost_equal_array(&[roll_out, pitch_out, yaw_out], &[roll, pitch, yaw]);
+        assert!(from_quat.get_quat().is_normalized(1e-12));
+    }
+
+    #[test]
+    #[should_panic]
+    fn attitude_init_bad_dcm(){
+        RigidBody::identity().with_attitude_dcm(geo::Matrix3x3::identity() * 2.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "not symmetric")]
+    fn asymmetric_i_tensor(){
+        RigidBody::new(
+            [0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0], [0.0, 0.0, 0.0],
+            1.0,
+            [1.0, 0.1, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn unnormalized_quat(){
+        let new_body = |quat: [f64; 4]| RigidBody::new(
+            [0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.1, -0.2, 0.3],
+            [0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0],
+            quat,
+            [0.5, 0.0, -0.4], [0.0, 0.0, 0.0],
+            1.0,
+            [1.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 3.0]
+        );
+        let mut scaled = new_body([2.0, 0.0, 0.0, 0.0]);
+        let mut unit = new_body([1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(scaled.get_quat(), geo::Quaternion::identity());
+
+        for _ in 0..1000{
+            scaled = scaled.rk4(1e-2);
+            unit = unit.rk4(1e-2);
+        }
+        assert_eq!(scaled.to_state_array(), unit.to_state_array());
+    }
+
+    #[test]
+    #[should_panic(expected = "not a rotation")]
+    fn zero_quat(){
+        RigidBody::from_state_array([0.0; 13], 1.0, geo::Matrix3x3::identity().to_array());
+    }
+
+    #[test]
+    fn inertia_keys(){
+        let mut runtime = sim::Runtime::new(1.0, 0.1, "time [s]");
+        let mut object = RigidBody::identity();
+        object.i_tensor_cg_kgpm2 = geo::Matrix3x3::new(
+            1.0, 2.0, 3.0,
+            4.0, 5.0, 6.0,
+            7.0, 8.0, 9.0
+        );
+        object.save_data_verbose("body", &mut runtime);
+
+        // Every entry of the tensor gets its own key
+        let keys: Vec<String> = runtime.list_keys().into_iter()
+            .filter(|key| key.ends_with("[kg/m^2]"))
+            .collect();
+        assert_eq!(keys.len(), 9);
+
+        let values: Vec<f64> = keys.iter().map(|key| runtime.get_value(key)).collect();
+        let mut sorted = values.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        assert_eq!(sorted, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+        assert_eq!(runtime.get_value("body.Izy [kg/m^2]"), 8.0);
+    }
+
+    #[test]
+    fn inertia_keys_csv(){
+        // Each tensor entry survives an export and re-import as its own column
+        let mut runtime = sim::Runtime::new(0.3, 0.1, "time [s]");
+        let mut object = RigidBody::identity();
+        object.i_tensor_cg_kgpm2 = geo::Matrix3x3::new(
+            1.0, 2.0, 3.0,
+            4.0, 5.0, 6.0,
+            7.0, 8.0, 9.0
+        );
+        while runtime.is_running{
+            object.save_data_verbose("body", &mut runtime);
+            runtime.increment();
+        }
+
+        let file_path = std::env::temp_dir().join("slippy_inertia_keys.csv");
+        let file_path = file_path.to_str().unwrap();
+        runtime.export_to_csv(file_path);
+        let imported = sim::Runtime::from_csv(file_path, "time [s]").unwrap();
+
+        let names = ["Ixx", "Ixy", "Ixz", "Iyx", "Iyy", "Iyz", "Izx", "Izy", "Izz"];
+        for (name, expected) in names.iter().zip(object.i_tensor_cg_kgpm2.to_array()){
+            assert_eq!(imported.get_value(format!("body.{name} [kg/m^2]").as_str()), expected);
+        }
+    }
+
+    #[test]
+    fn frame_tagged_csv(){
+        let config = sim::RuntimeConfig{
+            frame_tags: true,
+            ..sim::RuntimeConfig::default()
+        };
+        let mut runtime = sim::Runtime::new_with_config(0.3, 0.1, "time [s]", config);
+        let mut object = RigidBody::identity();
+        object.body_force_n = geo::Vector3::new(1.0, 0.0, 0.0);
+        object.intertial_force_n = geo::Vector3::new(0.0, 0.0, -9.8);
+        while runtime.is_running{
+            object.save_data("body", &mut runtime);
+            object = object.rk4(0.1);
+            runtime.increment();
+        }
+        assert_eq!(runtime.get_frame("body.body_force.x [N]"), Some(sim::Frame::Body));
+        assert_eq!(runtime.get_frame("body.intertial_force.x [N]"), Some(sim::Frame::Inertial));
+        assert_eq!(runtime.get_frame("body.mass_cg [kg]"), None);
+
+        let file_path = std::env::temp_dir().join("slippy_frame_tags.csv");
+        let file_path = file_path.to_str().unwrap();
+        runtime.export_to_csv(file_path);
+
+        let mut reader = csv::Reader::from_path(file_path).unwrap();
+        let header = reader.headers().unwrap().clone();
+        for label in ["body.body_force.z@body [N]", "body.intertial_force.z@inertial [N]", "body.body_ang_vel.x@body [rad/s]", "body.mass_cg [kg]", "time [s]"]{
+            assert!(header.iter().any(|l| l == label), "missing {label}");
+        }
+
+        // Read back with the frames
+        let restored = sim::Runtime::from_csv(file_path, "time [s]").unwrap();
+        assert_eq!(restored.get_frame("body.body_force.z@body [N]"), Some(sim::Frame::Body));
+        assert_eq!(restored.get_frame("body.inertial_pos.z@inertial [m]"), Some(sim::Frame::Inertial));
+
+        // Untagged by default
+        let mut runtime = sim::Runtime::new(0.3, 0.1, "time [s]");
+        object.save_data("body", &mut runtime);
+        runtime.export_to_csv(file_path);
+        let mut reader = csv::Reader::from_path(file_path).unwrap();
+        assert!(reader.headers().unwrap().iter().all(|label| !label.contains('@')));
+    }
+
+    #[test]
+    fn engineering_units_csv(){
+        let config = sim::RuntimeConfig{
+            output_units: sim::OutputUnits::engineering(),
+            ..sim::RuntimeConfig::default()
+        };
+        let mut runtime = sim::Runtime::new_with_config(0.3, 0.1, "time [s]", config);
+        let mut object = RigidBody::identity().with_attitude_euler(0.1, -0.2, 0.3);
+        object.intertial_force_n = geo::Vector3::new(0.0, 0.0, -crate::units::STANDARD_GRAVITY_MPS2);
+        while runtime.is_running{
+            object = object.rk4(0.1);
+            object.save_data_verbose("body", &mut runtime);
+            runtime.increment();
+        }
+        let euler_rad = runtime.get_value("body.euler.i [rad]");
+
+        let file_path = std::env::temp_dir().join("slippy_engineering_units.csv");
+        let file_path = file_path.to_str().unwrap();
+        runtime.export_to_csv(file_path);
+
+        let mut reader = csv::Reader::from_path(file_path).unwrap();
+        let header = reader.headers().unwrap().clone();
+        assert!(header.iter().all(|label| !label.ends_with("[rad]") && !label.ends_with("[m/s^2]")));
+        assert!(header.iter().any(|label| label == "time [s]"));
+        let euler_col = header.iter().position(|label| label == "body.euler.i [deg]").unwrap();
+        let accel_col = header.iter().position(|label| label == "body.inertial_accel.z [g]").unwrap();
+
+        let last = reader.records().last().unwrap().unwrap();
+        assert!((last[euler_col].parse::<f64>().unwrap() - euler_rad.to_degrees()).abs() < 1e-9);
+        assert!((last[accel_col].parse::<f64>().unwrap() + 1.0).abs() < 1e-12);
+    }
+
     #[test]
     fn body_translation(){
         let mut object = RigidBody::identity();
@@ -513,15 +783,51 @@ mod tests {
     }
 
     #[test]
+    fn moment_frames(){
+        // At rest there is no w x (I * w) term, so alpha = I^-1 * M with M
+        // in the body frame whatever the attitude
+        let quat = geo::Quaternion::from_axis_angle(geo::Vector3::new(1.0, 2.0, -1.0), 0.7);
+        let mut object = RigidBody::identity();
+        object.quat_b2i = quat;
+        object.i_tensor_cg_kgpm2 = geo::Matrix3x3::new(
+            2.0, 0.0, 0.0,
+            0.0, 3.0, 0.0,
+            0.0, 0.0, 4.0
+        );
+        object.inv_i_tensor_cg_kgpm2 = object.i_tensor_cg_kgpm2.inv().unwrap();
+        let moment_nm = geo::Vector3::new(1.0, -2.0, 0.5);
+
+        // Body fixed moment is used as is
+        object.body_moment_nm = moment_nm;
+        object.effects();
+        almost_equal_array(
+            &object.body_ang_accel_radps2.to_array(),
+            &(object.inv_i_tensor_cg_kgpm2 * moment_nm).to_array()
+        );
+
+        // Inertial moment is rotated into the body frame, q* M q
+        object.body_moment_nm = geo::Vector3::zeros();
+        object.intertial_moment_nm = moment_nm;
+        object.effects();
+        almost_equal_array(
+            &object.body_ang_accel_radps2.to_array(),
+            &(object.inv_i_tensor_cg_kgpm2 * quat.conjugate().transform(moment_nm)).to_array()
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
     fn spin_cone_simulator(){
         // SPIN-CONE SIMULATOR from Strapdown Analytics
         // Section: 11.2.1, Pg 11-12
 
         let beta = 0.25; // Precessional axis angle
-        let omega_s = 0.025; // Intertial rotation rate
+        let omega_s = 0.025; // Body rate about the spin axis
         let omega_c = 0.03; // Intertial precessional rate
 
-        // Use an identity intertia tensor and mass
+        // Use an identity intertia tensor and mass. The spin axis is body x,
+        // coning at beta about the inertial vertical, so it starts pitched
+        // to (pi / 2) - beta
         let mut uut = RigidBody{
             intertial_force_n: geo::Vector3::zeros(),
             intertial_moment_nm: geo::Vector3::zeros(),
@@ -530,7 +836,7 @@ mod tests {
             inertial_pos_m: geo::Vector3::zeros(),
             inertial_vel_mps: geo::Vector3::zeros(),
             inertial_accel_mps2: geo::Vector3::zeros(),
-            quat_b2i: geo::Vector3::new(beta, 0.0, 0.0).to_quat(),
+            quat_b2i: geo::Quaternion::from_euler(0.0, (PI / 2.0) - beta, 0.0),
             body_ang_accel_radps2: geo::Vector3::zeros(),
             body_ang_vel_radps: geo::Vector3::new(0.0, 0.0, 0.0),
             mass_cg_kg: 1.0,
@@ -540,36 +846,116 @@ mod tests {
 
         let mut runtime = sim::Runtime::new(10.0, 1e-3, "time [s]");
         let dt = runtime.get_dx();
-        let init_euler = uut.quat_b2i.to_euler();
+        let (_, _, init_phi) = uut.quat_b2i.to_euler_zyx();
+
+        // Z-Y-X Euler rates of Eq 11.2.1.1-7, theta is constant
+        let psi_dot = -omega_c;
+        let phi_dot = omega_s - (omega_c * beta.cos());
 
         while runtime.is_running{
             uut.save_data_verbose("uut", &mut runtime);
 
-            // Update spin cone velocity
-            let omega_s_vec = geo::Vector3::new(0.0, 0.0, omega_s);
-            let omega_c_vec = uut.get_quat()
+            // Roll about the spin axis plus yaw about the inertial vertical,
+            // the body x rate is omega_s
+            let omega_phi_vec = geo::Vector3::new(phi_dot, 0.0, 0.0);
+            let omega_psi_vec = uut.get_quat()
                 .conjugate()
-                .transform(geo::Vector3::new(0.0, 0.0, omega_c));
+                .transform(geo::Vector3::new(0.0, 0.0, psi_dot));
 
-            uut.body_ang_vel_radps = omega_s_vec + omega_c_vec;
+            uut.body_ang_vel_radps = omega_phi_vec + omega_psi_vec;
 
             uut = uut.rk4(dt);
             runtime.increment();
         }
+        uut.save_data_verbose("uut", &mut runtime);
 
         runtime.export_to_csv("results/data/spin_cone.csv");
 
-        // Strapdown uses Z-Y-X, I use X-Y-Z
-        let end_psi = -omega_c * runtime.get_x();
+        let end_psi = psi_dot * runtime.get_x();
         let end_theta = (PI / 2.0) - beta;
-        let end_phi = (omega_s - (omega_c * beta.cos()))
-            * runtime.get_max_x()
-            + init_euler.k;
+        let end_phi = (phi_dot * runtime.get_x()) + init_phi;
 
-        // Eq 11.2.1.1-7
+        // Eq 11.2.1.1-7, to_euler_zyx is (psi, theta, phi)
+        let (psi, theta, phi) = uut.quat_b2i.to_euler_zyx();
+        assert!((uut.body_ang_vel_radps.i - omega_s).abs() < 1e-6);
+        almost_equal_array(&[psi, theta, phi], &[end_psi, end_theta, end_phi]);
+    }
+
+    #[test]
+    fn state_array_round_trip(){
+        let mut object = RigidBody::identity();
+        object.inertial_pos_m = geo::Vector3::new(1.0, 2.0, 3.0);
+        object.inertial_vel_mps = geo::Vector3::new(-1.0, 0.5, 0.0);
+        object.quat_b2i = geo::Vector3::new(0.1, 0.2, 0.3).to_quat();
+        object.body_ang_vel_radps = geo::Vector3::new(0.01, -0.02, 0.03);
+
+        let restored = RigidBody::from_state_array(
+            object.to_state_array(),
+            object.mass_cg_kg,
+            object.i_tensor_cg_kgpm2.to_array()
+        );
+
+        assert_eq!(restored, object);
+    }
+
+    #[test]
+    fn merge(){
+        let mut body_a = RigidBody::identity();
+        body_a.mass_cg_kg = 2.0;
+        body_a.i_tensor_cg_kgpm2 = geo::Matrix3x3::new(
+            1.0, 0.0, 0.0,
+            0.0, 2.0, 0.0,
+            0.0, 0.0, 3.0
+        );
+        body_a.inertial_vel_mps = geo::Vector3::new(1.0, 0.0, 0.0);
+        body_a.body_ang_vel_radps = geo::Vector3::new(0.0, 0.0, 0.1);
+
+        let mut body_b = RigidBody::identity();
+        body_b.mass_cg_kg = 1.0;
+        body_b.inertial_pos_m = geo::Vector3::new(0.0, 1.0, 0.0);
+        body_b.inertial_vel_mps = geo::Vector3::new(-1.0, 0.5, 0.0);
+        body_b.body_ang_vel_radps = geo::Vector3::new(0.2, 0.0, 0.0);
+
+        let contact_offset = body_b.inertial_pos_m - body_a.inertial_pos_m;
+        let merged = body_a.merge(&body_b, contact_offset);
+
+        // Linear momentum
+        let momentum_before =
+            (body_a.inertial_vel_mps * body_a.mass_cg_kg)
+            + (body_b.inertial_vel_mps * body_b.mass_cg_kg);
         almost_equal_array(
-            &uut.quat_b2i.to_euler().to_array(),
-            &geo::Vector3::new(end_phi, end_theta, end_psi).to_array()
+            &(merged.inertial_vel_mps * merged.mass_cg_kg).to_array(),
+            &momentum_before.to_array()
+        );
+
+        // Combined cg sits a third of the way toward body_b
+        almost_equal_array(
+            &merged.inertial_pos_m.to_array(),
+            &[0.0, 1.0 / 3.0, 0.0]
+        );
+
+        // Parallel axis with offsets along y of -1/3 and 2/3
+        let d_a: f64 = 1.0 / 3.0;
+        let d_b: f64 = 2.0 / 3.0;
+        let transfer = (2.0 * d_a.powf(2.0)) + (1.0 * d_b.powf(2.0));
+        almost_equal_array(
+            &merged.i_tensor_cg_kgpm2.to_array(),
+            &[
+                1.0 + 1.0 + transfer, 0.0, 0.0,
+                0.0, 2.0 + 1.0, 0.0,
+                0.0, 0.0, 3.0 + 1.0 + transfer
+            ]
+        );
+
+        // Angular momentum about the combined cg
+        let point = merged.inertial_pos_m;
+        let point_vel = merged.inertial_vel_mps;
+        almost_equal_array(
+            &merged.angular_momentum_about(point, point_vel).to_array(),
+            &(
+                body_a.angular_momentum_about(point, point_vel)
+                + body_b.angular_momentum_about(point, point_vel)
+            ).to_array()
         );
     }
 
@@ -578,4 +964,115 @@ mod tests {
         // Section 11.2.3, Pg 11-27 from strapdown analytics
 
     }
+
+    fn jacobi_elliptic(u: f64, m: f64) -> (f64, f64, f64){
+        // sn, cn, dn by the arithmetic-geometric mean
+        // Source:
+        //    Abramowitz and Stegun, 16.4
+        let mut a = vec![1.0];
+        let mut c = vec![m.sqrt()];
+        let mut b = (1.0 - m).sqrt();
+        while c[c.len() - 1].abs() > 1e-15{
+            let (a_n, b_n) = (a[a.len() - 1], b);
+            a.push((a_n + b_n) / 2.0);
+            c.push((a_n - b_n) / 2.0);
+            b = (a_n * b_n).sqrt();
+        }
+
+        let n = a.len() - 1;
+        let mut phi = 2.0_f64.powi(n as i32) * a[n] * u;
+        let mut phi_prev = phi;
+        for i in (1..=n).rev(){
+            phi_prev = phi;
+            phi = (phi + (c[i] / a[i] * phi.sin()).asin()) / 2.0;
+        }
+        return (phi.sin(), phi.cos(), phi.cos() / (phi_prev - phi).cos())
+    }
+
+    #[test]
+    fn asymmetric_torque_free(){
+        // Torque-free Euler equations for I1 < I2 < I3 with M^2 > 2 E I2
+        // Source:
+        //    Landau and Lifshitz, Mechanics, Section 37
+        let (i1, i2, i3) = (1.0, 2.0, 3.0);
+        let mut uut = RigidBody::identity();
+        uut.i_tensor_cg_kgpm2 = geo::Matrix3x3::new(
+            i1, 0.0, 0.0,
+            0.0, i2, 0.0,
+            0.0, 0.0, i3
+        );
+        uut.inv_i_tensor_cg_kgpm2 = uut.i_tensor_cg_kgpm2.inv().unwrap();
+        uut.body_ang_vel_radps = geo::Vector3::new(1.0, 0.0, 1.0);
+
+        let w0 = uut.body_ang_vel_radps;
+        let energy_2 = (i1 * w0.i.powi(2)) + (i2 * w0.j.powi(2)) + (i3 * w0.k.powi(2));
+        let momentum_2 =
+            (i1 * w0.i).powi(2) + (i2 * w0.j).powi(2) + (i3 * w0.k).powi(2);
+        assert!(momentum_2 > energy_2 * i2);
+
+        let amp_1 = ((energy_2 * i3 - momentum_2) / (i1 * (i3 - i1))).sqrt();
+        let amp_2 = ((energy_2 * i3 - momentum_2) / (i2 * (i3 - i2))).sqrt();
+        let amp_3 = ((momentum_2 - energy_2 * i1) / (i3 * (i3 - i1))).sqrt();
+        let rate = ((i3 - i2) * (momentum_2 - energy_2 * i1) / (i1 * i2 * i3)).sqrt();
+        let m = ((i2 - i1) * (energy_2 * i3 - momentum_2))
+            / ((i3 - i2) * (momentum_2 - energy_2 * i1));
+
+        let dt = 1e-3;
+        let mut time = 0.0;
+        for _ in 0..10000{
+            uut = uut.rk4(dt);
+            time += dt;
+
+            let (sn, cn, dn) = jacobi_elliptic(rate * time, m);
+            let expected = [amp_1 * cn, amp_2 * sn, amp_3 * dn];
+            let actual = uut.body_ang_vel_radps.to_array();
+            for (x, y) in actual.iter().zip(expected.iter()){
+                assert!((x - y).abs() < 1e-9, "{actual:?} != {expected:?} at {time} s");
+            }
+        }
+    }
+
+    #[test]
+    fn body_force_at_offset(){
+        let mut uut = RigidBody::identity();
+
+        // Lateral force below the cg, like a gimbaled engine
+        uut.apply_body_force_at(geo::Vector3::new(2.0, 0.0, 0.0), geo::Vector3::new(0.0, 0.0, -0.5));
+        assert_eq!(uut.body_force_n, geo::Vector3::new(2.0, 0.0, 0.0));
+        assert!((uut.body_moment_nm.norm() - (2.0 * 0.5)).abs() < 1e-12);
+        assert_eq!(uut.body_moment_nm, geo::Vector3::new(0.0, -1.0, 0.0));
+
+        // Accumulates, the equal and opposite force cancels the force only
+        uut.apply_body_force_at(geo::Vector3::new(-2.0, 0.0, 0.0), geo::Vector3::new(0.0, 0.0, 0.5));
+        assert_eq!(uut.body_force_n, geo::Vector3::zeros());
+        assert_eq!(uut.body_moment_nm, geo::Vector3::new(0.0, -2.0, 0.0));
+
+        // Through the cg gives no moment
+        let mut uut = RigidBody::identity();
+        uut.apply_body_force_at(geo::Vector3::new(0.0, 0.0, 5.0), geo::Vector3::zeros());
+        assert_eq!(uut.body_moment_nm, geo::Vector3::zeros());
+    }
+
+    #[test]
+    fn torque_free_conservation(){
+        let mut uut = RigidBody::from_state_array(
+            [0.0, 0.0, 0.0, 1.0, -2.0, 0.5, 1.0, 0.0, 0.0, 0.0, 0.3, -1.2, 0.8],
+            2.0,
+            [1.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 3.5]
+        );
+        let energy_j = uut.kinetic_energy_j();
+        let momentum_nms = uut.get_quat().transform(uut.angular_momentum_nms());
+        assert!((energy_j - (0.5 * 2.0 * 5.25) - (0.5 * (0.09 + (2.0 * 1.44) + (3.5 * 0.64)))).abs() < 1e-12);
+
+        for _ in 0..20000{
+            uut = uut.rk4(1e-3);
+            assert!((uut.kinetic_energy_j() - energy_j).abs() < 1e-9 * energy_j);
+            assert!((uut.angular_momentum_nms().norm() - momentum_nms.norm()).abs() < 1e-9);
+        }
+
+        // The inertial vector is fixed while the body frame one moves
+        let final_nms = uut.get_quat().transform(uut.angular_momentum_nms());
+        assert!((final_nms - momentum_nms).norm() < 1e-6);
+        assert!((uut.angular_momentum_nms() - momentum_nms).norm() > 1e-2);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,279 @@
+use crate::geo;
+use crate::sim::{self, Integrate};
+
+use super::RigidBody;
+
+pub trait ForceElement{
+    // Returns (force_on_a, force_on_b, moment_on_a, moment_on_b) in the
+    // inertial frame
+    fn compute(
+        &self,
+        body_a: &RigidBody,
+        body_b: &RigidBody
+    ) -> (geo::Vector3, geo::Vector3, geo::Vector3, geo::Vector3);
+}
+
+struct Connection{
+    body_a: usize,
+    body_b: usize,
+    element: Box<dyn ForceElement>
+}
+
+pub struct System{
+    pub bodies: Vec<RigidBody>,
+    connections: Vec<Connection>
+}
+
+impl System{
+    pub fn new() -> System{
+        return System{
+            bodies: Vec::new(),
+            connections: Vec::new()
+        }
+    }
+
+    pub fn add_body(&mut self, body: RigidBody) -> usize{
+        self.bodies.push(body);
+        return self.bodies.len() - 1
+    }
+
+    pub fn add_element<T: ForceElement + 'static>(
+        &mut self,
+        body_a: usize,
+        body_b: usize,
+        element: T
+    ){
+        if body_a >= self.bodies.len() || body_b >= self.bodies.len(){
+            panic!(
+                "ERROR| Element connects bodies [{}, {}] but only [{}] exist",
+                body_a,
+                body_b,
+                self.bodies.len()
+            )
+        }
+
+        self.connections.push(Connection{
+            body_a,
+            body_b,
+            element: Box::new(element)
+        });
+    }
+
+    pub fn step(&mut self, dt: f64){
+        // Element loads are computed once from the current state and held
+        // for the step, matching how RigidBody holds its own loads
+        let mut forces_n = vec![geo::Vector3::zeros(); self.bodies.len()];
+        let mut moments_nm = vec![geo::Vector3::zeros(); self.bodies.len()];
+
+        for connection in self.connections.iter(){
+            let (force_a, force_b, moment_a, moment_b) =
+                connection.element.compute(
+                    &self.bodies[connection.body_a],
+                    &self.bodies[connection.body_b]
+                );
+
+            forces_n[connection.body_a] += force_a;
+            forces_n[connection.body_b] += force_b;
+            moments_nm[connection.body_a] += moment_a;
+            moments_nm[connection.body_b] += moment_b;
+        }
+
+        for (i, body) in self.bodies.iter_mut().enumerate(){
+            let user_force_n = body.intertial_force_n;
+            let user_moment_nm = body.intertial_moment_nm;
+
+            body.intertial_force_n += forces_n[i];
+            body.intertial_moment_nm += moments_nm[i];
+
+            // Integrate, then hand the user loads back untouched
+            *body = body.rk4(dt);
+            body.intertial_force_n = user_force_n;
+            body.intertial_moment_nm = user_moment_nm;
+        }
+    }
+
+    pub fn total_momentum_nms(&self) -> geo::Vector3{
+        let mut momentum = geo::Vector3::zeros();
+        for body in self.bodies.iter(){
+            momentum += body.get_intertial_vel_mps() * body.mass_cg_kg;
+        }
+        return momentum
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data Recording
+// ----------------------------------------------------------------------------
+
+impl sim::Save for System{
+    fn save_data(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        for (i, body) in self.bodies.iter().enumerate(){
+            body.save_data(format!("{node_name}.body{i}").as_str(), runtime);
+        }
+    }
+
+    fn save_data_verbose(&self, node_name: &str, runtime: &mut sim::Runtime) where Self: Sized {
+        for (i, body) in self.bodies.iter().enumerate(){
+            body.save_data_verbose(format!("{node_name}.body{i}").as_str(), runtime);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Tests
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::physics::SpringDamperTether;
+    use crate::sim::Save;
+    use crate::test::almost_equal_array;
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn point_mass(pos_m: [f64; 3], vel_mps: [f64; 3], mass_kg: f64) -> RigidBody{
+        return RigidBody::new(
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            pos_m,
+            vel_mps,
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            mass_kg,
+            [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]
+        )
+    }
+
+    fn separation_m(system: &System) -> f64{
+        return (
+            system.bodies[1].get_intertial_pos_m()
+            - system.bodies[0].get_intertial_pos_m()
+        ).norm()
+    }
+
+    #[test]
+    fn spring_frequency(){
+        let stiffness_npm = 10.0;
+        let mass_kg = 1.0;
+        let natural_length_m = 1.0;
+        let amplitude_m = 0.1;
+
+        let mut system = System::new();
+        let a = system.add_body(point_mass([0.0, 0.0, 0.0], [0.0, 0.0, 0.0], mass_kg));
+        let b = system.add_body(
+            point_mass([natural_length_m + amplitude_m, 0.0, 0.0], [0.0, 0.0, 0.0], mass_kg)
+        );
+        system.add_element(
+            a,
+            b,
+            SpringDamperTether::new_spring(
+                [0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0],
+                stiffness_npm,
+                0.0,
+                natural_length_m
+            )
+        );
+
+        // w = sqrt(2k/m)
+        let period_s = 2.0 * std::f64::consts::PI
+            / (2.0 * stiffness_npm / mass_kg).sqrt();
+
+        let dt = 1e-5;
+        let half_steps = (period_s / 2.0 / dt).round() as usize;
+
+        for _ in 0..half_steps{
+            system.step(dt);
+        }
+        assert_relative_eq!(
+            separation_m(&system),
+            natural_length_m - amplitude_m,
+            max_relative = 1e-3
+        );
+
+        for _ in 0..half_steps{
+            system.step(dt);
+        }
+        assert_relative_eq!(
+            separation_m(&system),
+            natural_length_m + amplitude_m,
+            max_relative = 1e-3
+        );
+    }
+
+    #[test]
+    fn momentum_conservation(){
+        let mut system = System::new();
+        let a = system.add_body(point_mass([0.0, 0.0, 0.0], [1.0, 0.5, 0.0], 1.0));
+        let b = system.add_body(point_mass([2.0, 0.0, 1.0], [-0.5, 0.0, 2.0], 3.0));
+
+        // Offset attachments so the tether also spins the bodies
+        system.add_element(
+            a,
+            b,
+            SpringDamperTether::new_spring(
+                [0.1, 0.2, 0.0],
+                [0.0, -0.1, 0.3],
+                50.0,
+                2.0,
+                1.5
+            )
+        );
+
+        let initial_momentum = system.total_momentum_nms();
+
+        let mut runtime = sim::Runtime::new(2.0, 1e-3, "time [s]");
+        let dt = runtime.get_dx();
+        while runtime.is_running{
+            system.save_data("tether", &mut runtime);
+            system.step(dt);
+            runtime.increment();
+        }
+
+        almost_equal_array(
+            &system.total_momentum_nms().to_array(),
+            &initial_momentum.to_array()
+        );
+
+        // Each body is logged under its own namespace
+        assert_relative_eq!(
+            runtime.get_value("tether.body1.mass_cg [kg]"),
+            3.0
+        );
+    }
+
+    #[test]
+    fn slack_cable(){
+        let body_a = point_mass([0.0, 0.0, 0.0], [0.0, 0.0, 0.0], 1.0);
+        let body_b = point_mass([0.5, 0.0, 0.0], [-1.0, 0.0, 0.0], 1.0);
+
+        let cable = SpringDamperTether::new_cable(
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            100.0,
+            5.0,
+            1.0
+        );
+        let (force_a, force_b, moment_a, moment_b) = cable.compute(&body_a, &body_b);
+
+        for load in [force_a, force_b, moment_a, moment_b]{
+            almost_equal_array(&load.to_array(), &[0.0, 0.0, 0.0]);
+        }
+
+        // The same geometry as a spring pushes the bodies apart
+        let spring = SpringDamperTether::new_spring(
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            100.0,
+            5.0,
+            1.0
+        );
+        let (force_a, _, _, _) = spring.compute(&body_a, &body_b);
+        assert!(force_a.i < 0.0);
+    }
+}
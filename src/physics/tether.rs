@@ -0,0 +1,123 @@
+use crate::geo;
+
+use super::{ForceElement, RigidBody};
+
+pub struct SpringDamperTether{
+    attach_a_m: geo::Vector3,
+    attach_b_m: geo::Vector3,
+    stiffness_npm: f64,
+    damping_nspm: f64,
+    natural_length_m: f64,
+    is_cable: bool
+}
+
+impl SpringDamperTether{
+    pub fn new(
+        attach_a_m: [f64; 3],
+        attach_b_m: [f64; 3],
+        stiffness_npm: f64,
+        damping_nspm: f64,
+        natural_length_m: f64,
+        is_cable: bool
+    ) -> SpringDamperTether{
+        // Attachment points are in each body's frame relative to the cg
+        return SpringDamperTether{
+            attach_a_m: geo::Vector3::from_array(attach_a_m),
+            attach_b_m: geo::Vector3::from_array(attach_b_m),
+            stiffness_npm,
+            damping_nspm,
+            natural_length_m,
+            is_cable
+        }
+    }
+
+    pub fn new_spring(
+        attach_a_m: [f64; 3],
+        attach_b_m: [f64; 3],
+        stiffness_npm: f64,
+        damping_nspm: f64,
+        natural_length_m: f64
+    ) -> SpringDamperTether{
+        return SpringDamperTether::new(
+            attach_a_m,
+            attach_b_m,
+            stiffness_npm,
+            damping_nspm,
+            natural_length_m,
+            false
+        )
+    }
+
+    pub fn new_cable(
+        attach_a_m: [f64; 3],
+        attach_b_m: [f64; 3],
+        stiffness_npm: f64,
+        damping_nspm: f64,
+        natural_length_m: f64
+    ) -> SpringDamperTether{
+        // A cable can only pull
+        return SpringDamperTether::new(
+            attach_a_m,
+            attach_b_m,
+            stiffness_npm,
+            damping_nspm,
+            natural_length_m,
+            true
+        )
+    }
+}
+
+impl ForceElement for SpringDamperTether{
+    fn compute(
+        &self,
+        body_a: &RigidBody,
+        body_b: &RigidBody
+    ) -> (geo::Vector3, geo::Vector3, geo::Vector3, geo::Vector3){
+        let zeros = geo::Vector3::zeros();
+
+        // Attachment offsets in the inertial frame
+        let offset_a_m = body_a.get_quat().transform(self.attach_a_m);
+        let offset_b_m = body_b.get_quat().transform(self.attach_b_m);
+
+        let point_a_m = body_a.get_intertial_pos_m() + offset_a_m;
+        let point_b_m = body_b.get_intertial_pos_m() + offset_b_m;
+
+        // v_p = v + (w x r)
+        let vel_a_mps = body_a.get_intertial_vel_mps()
+            + body_a.get_quat().transform(
+                body_a.get_body_ang_vel_radps().cross(&self.attach_a_m)
+            );
+        let vel_b_mps = body_b.get_intertial_vel_mps()
+            + body_b.get_quat().transform(
+                body_b.get_body_ang_vel_radps().cross(&self.attach_b_m)
+            );
+
+        let a_to_b_m = point_b_m - point_a_m;
+        let length_m = a_to_b_m.norm();
+        if length_m == 0.0{
+            return (zeros, zeros, zeros, zeros)
+        }
+        let direction = a_to_b_m / length_m;
+
+        let stretch_m = length_m - self.natural_length_m;
+        let stretch_rate_mps = (vel_b_mps - vel_a_mps).dot(&direction);
+
+        let mut tension_n =
+            (self.stiffness_npm * stretch_m)
+            + (self.damping_nspm * stretch_rate_mps);
+
+        if self.is_cable && (stretch_m <= 0.0 || tension_n < 0.0){
+            tension_n = 0.0;
+        }
+
+        let force_a_n = direction * tension_n;
+        let force_b_n = -force_a_n;
+
+        return (
+            force_a_n,
+            force_b_n,
+            offset_a_m.cross(&force_a_n),
+            offset_b_m.cross(&force_b_n)
+        )
+    }
+}
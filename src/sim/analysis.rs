@@ -0,0 +1,374 @@
+// Post-processing of recorded series, e.g. from Runtime::get_history
+
+use std::f64::consts::PI;
+
+use super::Runtime;
+
+fn band_width(setpoint: f64, band: f64) -> f64{
+    // Band is a fraction of the setpoint, or absolute for a zero setpoint
+    if setpoint == 0.0{
+        return band
+    }
+    return band * setpoint.abs()
+}
+
+pub fn overshoot(series: &[f64], setpoint: f64) -> Option<f64>{
+    // Furthest excursion past the setpoint, away from the first sample, as
+    // a fraction of the setpoint. Absolute for a zero setpoint.
+    let initial = *series.first()?;
+    let direction = if setpoint >= initial {1.0} else {-1.0};
+
+    let peak = series.iter()
+        .map(|value| (value - setpoint) * direction)
+        .fold(0.0_f64, f64::max);
+
+    if setpoint == 0.0{
+        return Some(peak)
+    }
+    return Some(peak / setpoint.abs())
+}
+
+pub fn settling_time(x: &[f64], series: &[f64], setpoint: f64, band: f64) -> Option<f64>{
+    // First x after which the series stays within the band, None if the
+    // last sample is still outside
+    if x.len() != series.len(){
+        panic!("    ERROR| x [{}] and series [{}] lengths differ", x.len(), series.len())
+    }
+    let band = band_width(setpoint, band);
+
+    let last_outside = series.iter().rposition(|value| (value - setpoint).abs() > band);
+    return match last_outside{
+        None => x.first().copied(),
+        Some(index) => x.get(index + 1).copied()
+    }
+}
+
+pub fn rise_time(x: &[f64], series: &[f64], setpoint: f64, low: f64, high: f64) -> Option<f64>{
+    // Time to go from low to high fractions of the step from the first
+    // sample to the setpoint, crossings are linearly interpolated
+    if x.len() != series.len(){
+        panic!("    ERROR| x [{}] and series [{}] lengths differ", x.len(), series.len())
+    }
+    let initial = *series.first()?;
+    let step = setpoint - initial;
+    if step == 0.0{
+        return None
+    }
+
+    let crossing = |fraction: f64| -> Option<f64>{
+        let target = initial + (fraction * step);
+        for i in 1..series.len(){
+            let (prev, curr) = ((series[i - 1] - target) * step, (series[i] - target) * step);
+            if prev < 0.0 && curr >= 0.0{
+                return Some(x[i - 1] + (x[i] - x[i - 1]) * prev / (prev - curr))
+            }
+        }
+        return None
+    };
+
+    return Some(crossing(high)? - crossing(low)?)
+}
+
+pub fn rms_error(a: &[f64], b: &[f64]) -> f64{
+    if a.len() != b.len() || a.is_empty(){
+        panic!("    ERROR| Series lengths [{}] and [{}] must match and be non-zero", a.len(), b.len())
+    }
+    let sum_sq: f64 = a.iter().zip(b.iter()).map(|(a, b)| (a - b).powi(2)).sum();
+    return (sum_sq / a.len() as f64).sqrt()
+}
+
+// ----------------------------------------------------------------------------
+// Frequency response
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrequencyPoint{
+    pub freq_hz: f64,
+    pub gain_db: f64,
+    pub phase_deg: f64
+}
+
+pub struct FrequencyResponse{
+    dt: f64,
+    amplitude: f64,
+    settle_cycles: usize,
+    measure_cycles: usize,
+    points: Vec<FrequencyPoint>
+}
+
+impl FrequencyResponse{
+    pub fn new(dt: f64, amplitude: f64) -> FrequencyResponse{
+        // Sine injections of the given amplitude, stepped every dt
+        if dt <= 0.0 || amplitude == 0.0{
+            panic!("    ERROR| Time step [{}] must be positive and amplitude [{}] non-zero", dt, amplitude)
+        }
+        return FrequencyResponse{
+            dt,
+            amplitude,
+            settle_cycles: 5,
+            measure_cycles: 5,
+            points: Vec::new()
+        }
+    }
+
+    pub fn with_cycles(mut self, settle_cycles: usize, measure_cycles: usize) -> FrequencyResponse{
+        // Cycles run before measuring to let transients die out, then
+        // cycles correlated against the references
+        if measure_cycles == 0{
+            panic!("    ERROR| At least one measurement cycle is needed")
+        }
+        self.settle_cycles = settle_cycles;
+        self.measure_cycles = measure_cycles;
+        return self
+    }
+
+    pub fn run<F>(&mut self, freqs_hz: &[f64], mut step: F) -> &[FrequencyPoint]
+        where F: FnMut(f64) -> f64
+    {
+        // step takes the injected input for one dt and returns the measured
+        // output. Phase is unwrapped across the frequencies in the given
+        // order, negative is a lag.
+        self.points.clear();
+        let mut last_phase_rad: Option<f64> = None;
+
+        for &freq_hz in freqs_hz.iter(){
+            if !(freq_hz > 0.0 && freq_hz.is_finite()){
+                panic!("    ERROR| Frequency [{}] Hz must be positive and finite", freq_hz)
+            }
+            let omega = 2.0 * PI * freq_hz;
+            let samples_per_cycle = 1.0 / (freq_hz * self.dt);
+            let settle = (self.settle_cycles as f64 * samples_per_cycle).round() as usize;
+            let measure = (self.measure_cycles as f64 * samples_per_cycle).round() as usize;
+            if measure < 2{
+                panic!("    ERROR| Frequency [{}] Hz is too high for a time step of [{}] s", freq_hz, self.dt)
+            }
+
+            // y = A * G * sin(wt + phi) correlates to A * G * (cos phi, sin phi)
+            let (mut in_phase, mut quadrature) = (0.0, 0.0);
+            for n in 0..(settle + measure){
+                let phase = omega * n as f64 * self.dt;
+                let output = step(self.amplitude * phase.sin());
+                if n >= settle{
+                    in_phase += output * phase.sin();
+                    quadrature += output * phase.cos();
+                }
+            }
+            let scale = 2.0 / (measure as f64 * self.amplitude);
+            let (in_phase, quadrature) = (in_phase * scale, quadrature * scale);
+
+            let mut phase_rad = quadrature.atan2(in_phase);
+            if let Some(last) = last_phase_rad{
+                phase_rad -= 2.0 * PI * ((phase_rad - last) / (2.0 * PI)).round();
+            }
+            last_phase_rad = Some(phase_rad);
+
+            self.points.push(FrequencyPoint{
+                freq_hz,
+                gain_db: 20.0 * (in_phase.powi(2) + quadrature.powi(2)).sqrt().log10(),
+                phase_deg: phase_rad.to_degrees()
+            });
+        }
+        return &self.points
+    }
+
+    pub fn get_points(&self) -> &[FrequencyPoint]{
+        return &self.points
+    }
+
+    pub fn to_runtime(&self) -> Runtime{
+        // One step per frequency so the result goes through export_to_csv
+        let mut runtime = Runtime::new_growable(1.0, "point [-]");
+        for (i, point) in self.points.iter().enumerate(){
+            if i > 0{
+                runtime.increment();
+            }
+            runtime.add_or_set("freq [Hz]", point.freq_hz);
+            runtime.add_or_set("gain [dB]", point.gain_db);
+            runtime.add_or_set("phase [deg]", point.phase_deg);
+        }
+        runtime.is_running = false;
+        return runtime
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZETA: f64 = 0.3;
+    const OMEGA_N: f64 = 2.0;
+
+    fn step_response(time_s: f64) -> f64{
+        // Underdamped second-order unit step response
+        let beta = (1.0 - ZETA.powi(2)).sqrt();
+        let omega_d = OMEGA_N * beta;
+        return 1.0 - (-ZETA * OMEGA_N * time_s).exp() / beta
+            * ((omega_d * time_s) + ZETA.acos()).sin()
+    }
+
+    fn record(runtime: &mut Runtime, response: fn(f64) -> f64){
+        while runtime.is_running{
+            let time_s = runtime.get_x();
+            runtime.add_or_set("plant.y [-]", response(time_s));
+            runtime.increment();
+        }
+    }
+
+    fn assert_within_percent(value: f64, expected: f64, percent: f64){
+        assert!(
+            ((value - expected) / expected).abs() <= percent / 100.0,
+            "{value} not within {percent}% of {expected}"
+        );
+    }
+
+    #[test]
+    fn second_order_step(){
+        let mut runtime = Runtime::new(20.0, 1e-3, "time [s]");
+        record(&mut runtime, step_response);
+        let x = runtime.get_x_array();
+        let y = runtime.get_history("plant.y [-]");
+        assert_eq!(x.len(), y.len());
+
+        let beta = (1.0 - ZETA.powi(2)).sqrt();
+        let omega_d = OMEGA_N * beta;
+        assert_within_percent(
+            overshoot(y, 1.0).unwrap(),
+            (-ZETA * PI / beta).exp(),
+            1.0
+        );
+
+        // Deviation peaks are exp(-zeta * omega_n * t) at t = k * pi / omega_d,
+        // a band just under the third peak settles right after it
+        let t_peak = 3.0 * PI / omega_d;
+        let band = 0.999 * (-ZETA * OMEGA_N * t_peak).exp();
+        assert_within_percent(settling_time(x, y, 1.0, band).unwrap(), t_peak, 1.0);
+
+        // Compared to the final value
+        let final_value = vec![1.0; y.len()];
+        assert!(rms_error(&y[y.len() - 100..], &final_value[..100]) < 1e-3);
+    }
+
+    #[test]
+    fn first_order_step(){
+        // 1 - exp(-t / tau), 10-90% rise is tau * ln(9)
+        let mut runtime = Runtime::new(10.0, 1e-3, "time [s]");
+        record(&mut runtime, |time_s| 1.0 - (-time_s / 0.5).exp());
+        let x = runtime.get_x_array();
+        let y = runtime.get_history("plant.y [-]");
+
+        assert_within_percent(rise_time(x, y, 1.0, 0.1, 0.9).unwrap(), 0.5 * 9.0_f64.ln(), 1.0);
+        assert_within_percent(settling_time(x, y, 1.0, 0.02).unwrap(), -0.5 * 0.02_f64.ln(), 1.0);
+        assert_eq!(overshoot(y, 1.0), Some(0.0));
+    }
+
+    #[test]
+    fn zero_setpoint(){
+        // Decay to zero, the band is absolute
+        let mut runtime = Runtime::new(10.0, 1e-3, "time [s]");
+        record(&mut runtime, |time_s| (-time_s).exp());
+        let x = runtime.get_x_array();
+        let y = runtime.get_history("plant.y [-]");
+
+        assert_within_percent(settling_time(x, y, 0.0, 0.02).unwrap(), 50.0_f64.ln(), 1.0);
+        assert_within_percent(rise_time(x, y, 0.0, 0.1, 0.9).unwrap(), 9.0_f64.ln(), 1.0);
+        assert_eq!(overshoot(y, 0.0), Some(0.0));
+    }
+
+    #[test]
+    fn never_settles(){
+        // Undamped oscillation
+        let mut runtime = Runtime::new(10.0, 1e-3, "time [s]");
+        record(&mut runtime, |time_s| 1.0 - time_s.cos());
+        let x = runtime.get_x_array();
+        let y = runtime.get_history("plant.y [-]");
+
+        assert_eq!(settling_time(x, y, 1.0, 0.02), None);
+        assert_within_percent(overshoot(y, 1.0).unwrap(), 1.0, 1.0);
+        assert_eq!(rise_time(x, y, 5.0, 0.1, 0.9), None);
+        assert_eq!(overshoot(&[], 1.0), None);
+
+        // RMS of a sine about zero
+        let zeros = vec![0.0; y.len()];
+        let sine: Vec<f64> = x.iter().map(|time_s| (2.0 * PI * time_s).sin()).collect();
+        assert_within_percent(rms_error(&sine, &zeros), 0.5_f64.sqrt(), 1.0);
+    }
+
+    fn log_sweep(start_hz: f64, decades: f64, count: usize) -> Vec<f64>{
+        return (0..count)
+            .map(|i| start_hz * 10.0_f64.powf(decades * i as f64 / (count - 1) as f64))
+            .collect()
+    }
+
+    #[test]
+    fn first_order_filter_response(){
+        // y[n] = y[n-1] + alpha * (u[n] - y[n-1]),
+        // H(z) = alpha / (1 - (1 - alpha) z^-1)
+        let dt = 1e-3;
+        let tau_s = 1.0 / (2.0 * PI);
+        let alpha = dt / (tau_s + dt);
+
+        let mut state = 0.0;
+        let mut response = FrequencyResponse::new(dt, 0.5);
+        let points = response.run(&log_sweep(0.1, 2.0, 9), |input| {
+            state += alpha * (input - state);
+            return state
+        }).to_vec();
+        assert_eq!(points.len(), 9);
+
+        for point in points.iter(){
+            let omega_dt = 2.0 * PI * point.freq_hz * dt;
+            let (re, im) = (1.0 - ((1.0 - alpha) * omega_dt.cos()), (1.0 - alpha) * omega_dt.sin());
+            let gain_db = 20.0 * (alpha / (re.powi(2) + im.powi(2)).sqrt()).log10();
+            let phase_deg = -im.atan2(re).to_degrees();
+
+            assert!((point.gain_db - gain_db).abs() < 0.5, "{point:?} vs {gain_db} dB");
+            assert!((point.phase_deg - phase_deg).abs() < 2.0, "{point:?} vs {phase_deg} deg");
+        }
+
+        // About -3 dB and -45 deg at the corner
+        let corner = response.run(&[1.0], |input| {
+            state += alpha * (input - state);
+            return state
+        })[0];
+        assert!((corner.gain_db + 3.01).abs() < 0.1);
+        assert!((corner.phase_deg + 45.0).abs() < 1.0);
+
+        let runtime = response.to_runtime();
+        assert_eq!(runtime.get_series("gain [dB]"), [corner.gain_db]);
+    }
+
+    #[test]
+    fn pure_delay_phase(){
+        // 50 steps of 1 ms, phase = -360 * f * delay with unity gain
+        let dt = 1e-3;
+        let delay_s = 0.05;
+        let mut buffer = std::collections::VecDeque::from(vec![0.0; 50]);
+
+        let mut response = FrequencyResponse::new(dt, 1.0).with_cycles(2, 4);
+        let points = response.run(&log_sweep(0.5, 1.0, 11), |input| {
+            buffer.push_back(input);
+            return buffer.pop_front().unwrap()
+        }).to_vec();
+
+        for pair in points.windows(2){
+            let slope = (pair[1].phase_deg - pair[0].phase_deg) / (pair[1].freq_hz - pair[0].freq_hz);
+            assert!((slope + (360.0 * delay_s)).abs() < 0.05 * 360.0 * delay_s, "{slope} deg/Hz");
+        }
+        for point in points.iter(){
+            assert!(point.gain_db.abs() < 0.1);
+            assert!((point.phase_deg + (360.0 * point.freq_hz * delay_s)).abs() < 2.0);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_frequency(){
+        FrequencyResponse::new(1e-3, 1.0).run(&[1.0, 0.0], |input| input);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_frequency(){
+        FrequencyResponse::new(1e-3, 1.0).run(&[f64::NAN], |input| input);
+    }
+}
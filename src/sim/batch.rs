@@ -0,0 +1,111 @@
+use std::path::Path;
+use std::thread;
+
+use super::Runtime;
+
+pub(crate) fn parallel_map<C, T, F>(items: &[C], threads: usize, f: F) -> Vec<T>
+    where
+        C: Sync,
+        T: Send,
+        F: Fn(&C) -> T + Sync
+{
+    // Items are dealt round-robin, results come back in item order
+    let threads = threads.max(1);
+    let f = &f;
+
+    let mut indexed: Vec<(usize, T)> = thread::scope(|scope|{
+        let handles: Vec<_> = (0..threads).map(|thread_index|{
+            scope.spawn(move ||{
+                (thread_index..items.len()).step_by(threads)
+                    .map(|i| (i, f(&items[i])))
+                    .collect::<Vec<_>>()
+            })
+        }).collect();
+
+        handles.into_iter()
+            .flat_map(|handle| handle.join().expect("ERROR| Batch run panicked"))
+            .collect()
+    });
+    indexed.sort_by_key(|(i, _)| *i);
+
+    return indexed.into_iter().map(|(_, result)| result).collect()
+}
+
+pub fn run_batch<C, F>(configs: Vec<C>, threads: usize, f: F) -> Vec<Runtime>
+    where
+        C: Sync,
+        F: Fn(&C) -> Runtime + Sync
+{
+    // Each sim owns its Runtime so they can run on separate threads
+    return parallel_map(&configs, threads, f)
+}
+
+pub fn export_all(runtimes: &mut [Runtime], dir: &str, prefix: &str) -> Vec<String>{
+    // Files are named {prefix}_{run index}.csv
+    let mut file_paths = Vec::new();
+
+    for (i, runtime) in runtimes.iter_mut().enumerate(){
+        let file_path = Path::new(dir).join(format!("{prefix}_{i}.csv"));
+        let file_path = file_path.to_string_lossy().to_string();
+
+        runtime.export_to_csv(&file_path);
+        file_paths.push(file_path);
+    }
+
+    return file_paths
+}
+
+// ----------------------------------------------------------------------------
+// Tests
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    use super::*;
+
+    fn assert_send<T: Send>(){}
+
+    #[test]
+    fn parallel_batch(){
+        assert_send::<Runtime>();
+
+        let configs: Vec<f64> = (0..8).map(|i| i as f64).collect();
+        let thread_ids = Mutex::new(HashSet::new());
+
+        let mut runtimes = run_batch(configs, 4, |&slope|{
+            thread_ids.lock().unwrap().insert(thread::current().id());
+            let mut runtime = Runtime::new(1.0, 0.1, "time [s]");
+            while runtime.is_running{
+                let x = runtime.get_x();
+                runtime.add_or_set("line [-]", slope * x);
+                runtime.increment();
+            }
+            runtime.add_or_set("line [-]", slope * runtime.get_x());
+            runtime
+        });
+
+        // The configs were split across threads
+        assert!(thread_ids.into_inner().unwrap().len() > 1);
+
+        let dir = std::env::temp_dir().join("slippy_batch");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file_paths = export_all(&mut runtimes, dir.to_str().unwrap(), "run");
+
+        assert_eq!(file_paths.len(), 8);
+        let contents: Vec<String> = file_paths.iter()
+            .map(|file_path| std::fs::read_to_string(file_path).unwrap())
+            .collect();
+
+        // Results are kept in config order and differ between configs
+        for (i, content) in contents.iter().enumerate(){
+            assert!(file_paths[i].ends_with(&format!("run_{i}.csv")));
+            for other in contents[i + 1..].iter(){
+                assert_ne!(content, other);
+            }
+        }
+        assert_eq!(runtimes[3].get_value("line [-]"), 3.0 * runtimes[3].get_x());
+    }
+}
@@ -0,0 +1,139 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frame{
+    Body,
+    Inertial,
+    LocalLevel
+}
+
+impl Frame{
+    pub fn tag(&self) -> &'static str{
+        return match self{
+            Frame::Body => "body",
+            Frame::Inertial => "inertial",
+            Frame::LocalLevel => "local_level"
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Frame>{
+        return match tag{
+            "body" => Some(Frame::Body),
+            "inertial" => Some(Frame::Inertial),
+            "local_level" => Some(Frame::LocalLevel),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelInfo{
+    pub key: String,
+    pub node: String,
+    pub name: String,
+    pub unit: String,
+    pub frame: Option<Frame>
+}
+
+impl ChannelInfo{
+    pub fn parse(key: &str) -> ChannelInfo{
+        // "{node}.{name} [{unit}]", the node is everything before the first
+        // dot and may be empty, the unit defaults to [-]. A frame tag,
+        // "{node}.{name}@{frame} [{unit}]", is split off the name.
+        let (path, unit) = match key.trim().strip_suffix(']').and_then(|s| s.rsplit_once(" [")){
+            Some((path, unit)) => (path.trim(), unit.trim()),
+            None => (key.trim(), "-")
+        };
+
+        let (path, frame) = match path.rsplit_once('@'){
+            Some((untagged, tag)) if Frame::from_tag(tag).is_some() => (untagged, Frame::from_tag(tag)),
+            _ => (path, None)
+        };
+
+        let (node, name) = path.split_once('.').unwrap_or(("", path));
+
+        return ChannelInfo{
+            key: key.to_string(),
+            node: node.to_string(),
+            name: name.to_string(),
+            unit: unit.to_string(),
+            frame
+        }
+    }
+
+    pub fn tag_key(key: &str, frame: Frame) -> String{
+        // Inserts @{frame} ahead of the unit
+        return match key.rfind(" ["){
+            Some(index) if key.ends_with(']') => format!("{}@{}{}", &key[..index], frame.tag(), &key[index..]),
+            _ => format!("{}@{}", key, frame.tag())
+        }
+    }
+
+    pub fn format_key(node: &str, name: &str, unit: &str) -> String{
+        if node.is_empty(){
+            return format!("{name} [{unit}]")
+        }
+        return format!("{node}.{name} [{unit}]")
+    }
+
+    pub fn get_path(&self) -> String{
+        // Node and name without the unit
+        if self.node.is_empty(){
+            return self.name.clone()
+        }
+        return format!("{}.{}", self.node, self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse(){
+        let channel = ChannelInfo::parse("hopper.tvc.linkage.output_angle [rad]");
+        assert_eq!(channel.node, "hopper");
+        assert_eq!(channel.name, "tvc.linkage.output_angle");
+        assert_eq!(channel.unit, "rad");
+        assert_eq!(channel.get_path(), "hopper.tvc.linkage.output_angle");
+
+        // No node
+        let channel = ChannelInfo::parse("time [s]");
+        assert_eq!(channel.node, "");
+        assert_eq!(channel.name, "time");
+        assert_eq!(channel.unit, "s");
+
+        // No unit
+        let channel = ChannelInfo::parse("hopper.mode");
+        assert_eq!(channel.node, "hopper");
+        assert_eq!(channel.name, "mode");
+        assert_eq!(channel.unit, "-");
+
+        // Compound unit
+        let channel = ChannelInfo::parse("hopper.body_ang_accel.x [rad/s^2]");
+        assert_eq!(channel.unit, "rad/s^2");
+        assert_eq!(
+            ChannelInfo::format_key(&channel.node, &channel.name, &channel.unit),
+            channel.key
+        );
+        assert_eq!(channel.frame, None);
+    }
+
+    #[test]
+    fn frame_tag(){
+        let key = ChannelInfo::tag_key("hopper.body_force.x [N]", Frame::Body);
+        assert_eq!(key, "hopper.body_force.x@body [N]");
+
+        let channel = ChannelInfo::parse(&key);
+        assert_eq!(channel.frame, Some(Frame::Body));
+        assert_eq!(channel.get_path(), "hopper.body_force.x");
+        assert_eq!(channel.unit, "N");
+
+        let channel = ChannelInfo::parse(&ChannelInfo::tag_key("count", Frame::LocalLevel));
+        assert_eq!(channel.frame, Some(Frame::LocalLevel));
+        assert_eq!(channel.name, "count");
+
+        // Unknown tags are part of the name
+        let channel = ChannelInfo::parse("hopper.user@host [-]");
+        assert_eq!(channel.frame, None);
+        assert_eq!(channel.name, "user@host");
+    }
+}
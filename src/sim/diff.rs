@@ -0,0 +1,133 @@
+// Run to run comparison, e.g. before and after a refactor
+
+use std::collections::BTreeSet;
+
+use super::Runtime;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalDiff{
+    pub key: String,
+    pub max_abs: f64,
+    pub max_abs_x: f64,
+    pub max_rel: f64,
+    pub max_rel_x: f64
+}
+
+fn deviation(a: f64, b: f64) -> (f64, f64){
+    // Absolute and relative to the larger magnitude, NaN only matches NaN
+    if a.is_nan() && b.is_nan(){
+        return (0.0, 0.0)
+    }
+    if a.is_nan() || b.is_nan(){
+        return (f64::INFINITY, f64::INFINITY)
+    }
+
+    let abs = (a - b).abs();
+    let scale = a.abs().max(b.abs());
+    if abs == 0.0{
+        return (0.0, 0.0)
+    }
+    return (abs, abs / scale)
+}
+
+pub fn diff_runs(a: &Runtime, b: &Runtime) -> Vec<SignalDiff>{
+    // Per signal worst deviation up to the current step, sorted by key. A
+    // key recorded by only one run is an infinite deviation at the first
+    // step. The runs must have the same steps.
+    if a.get_curr_index() != b.get_curr_index(){
+        panic!(
+            "    ERROR| Runs are at different steps [{}] and [{}]",
+            a.get_curr_index(),
+            b.get_curr_index()
+        )
+    }
+    let x = a.get_x_array();
+    let a_keys = a.list_keys();
+    let b_keys = b.list_keys();
+    let keys: BTreeSet<String> = a_keys.iter().chain(b_keys.iter()).cloned().collect();
+
+    let mut diffs: Vec<SignalDiff> = Vec::new();
+    for key in keys.into_iter(){
+        if !a_keys.contains(&key) || !b_keys.contains(&key){
+            diffs.push(SignalDiff{
+                key,
+                max_abs: f64::INFINITY,
+                max_abs_x: x[0],
+                max_rel: f64::INFINITY,
+                max_rel_x: x[0]
+            });
+            continue
+        }
+        let mut diff = SignalDiff{key, max_abs: 0.0, max_abs_x: x[0], max_rel: 0.0, max_rel_x: x[0]};
+
+        let series = a.get_series(&diff.key).iter().zip(b.get_series(&diff.key).iter());
+        for (i, (a_value, b_value)) in series.enumerate(){
+            let (abs, rel) = deviation(*a_value, *b_value);
+            if abs > diff.max_abs{
+                diff.max_abs = abs;
+                diff.max_abs_x = x[i];
+            }
+            if rel > diff.max_rel{
+                diff.max_rel = rel;
+                diff.max_rel_x = x[i];
+            }
+        }
+        diffs.push(diff);
+    }
+    return diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(perturb_at_s: Option<f64>) -> Runtime{
+        let mut runtime = Runtime::new(2.0, 0.1, "time [s]");
+        while runtime.is_running{
+            let time_s = runtime.get_x();
+            runtime.add_or_set("ramp [-]", 2.0 * time_s);
+
+            // Single step bump on the flat signal
+            let bump = match perturb_at_s{
+                Some(at_s) if (time_s - at_s).abs() < 1e-9 => 0.5,
+                _ => 0.0
+            };
+            runtime.add_or_set("flat [-]", 4.0 + bump);
+            runtime.increment();
+        }
+        return runtime
+    }
+
+    #[test]
+    fn identical_runs(){
+        let diffs = diff_runs(&run(None), &run(None));
+        assert_eq!(diffs.len(), 2);
+        assert!(diffs.iter().all(|diff| diff.max_abs == 0.0 && diff.max_rel == 0.0));
+    }
+
+    #[test]
+    fn perturbed_run(){
+        let reference = run(None);
+        let perturbed = run(Some(1.2));
+        let diffs = diff_runs(&reference, &perturbed);
+
+        assert_eq!(diffs[0].key, "flat [-]");
+        assert_eq!(diffs[0].max_abs, 0.5);
+        assert!((diffs[0].max_rel - (0.5 / 4.5)).abs() < 1e-12);
+        assert!((diffs[0].max_abs_x - 1.2).abs() < 1e-9);
+        assert_eq!(diffs[0].max_abs_x, diffs[0].max_rel_x);
+
+        assert_eq!(diffs[1].key, "ramp [-]");
+        assert_eq!(diffs[1].max_abs, 0.0);
+
+        // Keys in only one run are reported from either side
+        let mut extra = run(None);
+        extra.add_or_set("extra [-]", 1.0);
+        for diffs in [diff_runs(&extra, &reference), diff_runs(&reference, &extra)]{
+            assert_eq!(diffs.len(), 3);
+            assert_eq!(diffs[0].key, "extra [-]");
+            assert_eq!(diffs[0].max_abs, f64::INFINITY);
+            assert_eq!(diffs[0].max_rel, f64::INFINITY);
+        }
+    }
+}
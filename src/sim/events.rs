@@ -0,0 +1,232 @@
+use super::Runtime;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event{
+    pub name: String,
+    pub x: f64,
+    pub value: f64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction{
+    Rising,
+    Falling,
+    Either
+}
+
+#[derive(Debug, Clone)]
+pub struct EventDetector{
+    name: String,
+    threshold: f64,
+    direction: Direction,
+    is_terminal: bool,
+    previous: Option<(f64, f64)>
+}
+
+impl EventDetector{
+    pub fn new(name: &str, threshold: f64) -> EventDetector{
+        return EventDetector{
+            name: name.to_string(),
+            threshold,
+            direction: Direction::Either,
+            is_terminal: false,
+            previous: None
+        }
+    }
+
+    pub fn set_direction(&mut self, direction: Direction){
+        self.direction = direction;
+    }
+
+    pub fn set_terminal(&mut self, is_terminal: bool){
+        // A terminal event stops the runtime when it fires
+        self.is_terminal = is_terminal;
+    }
+
+    pub fn update(&mut self, runtime: &mut Runtime, value: f64) -> Option<Event>{
+        // Linear interpolation between the previous and current step
+        let x = runtime.get_x();
+        let crossing = self.check_crossing(x, value)?;
+        let (prev_x, prev_value) = crossing;
+
+        let fraction = (self.threshold - prev_value) / (value - prev_value);
+        let event_x = prev_x + (x - prev_x) * fraction;
+
+        return Some(self.record(runtime, event_x, self.threshold))
+    }
+
+    pub fn update_refined<F>(&mut self, runtime: &mut Runtime, value: f64, expression: F)
+        -> Option<Event>
+        where F: Fn(f64) -> f64
+    {
+        // Bisection on the expression re-evaluated at x between the previous
+        // and current step
+        let x = runtime.get_x();
+        let (mut lower_x, prev_value) = self.check_crossing(x, value)?;
+        let mut upper_x = x;
+        let lower_sign = (prev_value - self.threshold).signum();
+
+        let tolerance = (x - lower_x).abs() * 1e-12;
+        let mut event_value = value;
+        for _ in 0..100{
+            let mid_x = (lower_x + upper_x) / 2.0;
+            event_value = expression(mid_x);
+
+            if (event_value - self.threshold).signum() == lower_sign{
+                lower_x = mid_x;
+            } else{
+                upper_x = mid_x;
+            }
+
+            if (upper_x - lower_x).abs() <= tolerance{
+                break
+            }
+        }
+
+        return Some(self.record(runtime, (lower_x + upper_x) / 2.0, event_value))
+    }
+
+    fn check_crossing(&mut self, x: f64, value: f64) -> Option<(f64, f64)>{
+        // Returns the previous step if the threshold was crossed since
+        let previous = self.previous.replace((x, value))?;
+        let (_, prev_value) = previous;
+
+        let was_below = prev_value < self.threshold;
+        let is_below = value < self.threshold;
+        if was_below == is_below{
+            return None
+        }
+
+        let is_match = match self.direction{
+            Direction::Rising => was_below,
+            Direction::Falling => is_below,
+            Direction::Either => true
+        };
+
+        if is_match{
+            return Some(previous)
+        }
+        return None
+    }
+
+    fn record(&self, runtime: &mut Runtime, x: f64, value: f64) -> Event{
+        let event = Event{
+            name: self.name.clone(),
+            x,
+            value
+        };
+        runtime.add_event(event.clone());
+
+        if self.is_terminal{
+            runtime.is_running = false;
+        }
+
+        return event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn apogee(){
+        let gravity_mps2 = -9.81;
+        let initial_vel_mps = 23.0;
+        let dt = 1e-2;
+
+        let mut runtime = Runtime::new(5.0, dt, "time [s]");
+        let mut linear = EventDetector::new("apogee", 0.0);
+        linear.set_direction(Direction::Falling);
+        let mut refined = EventDetector::new("apogee_refined", 0.0);
+        refined.set_direction(Direction::Falling);
+
+        let mut vel_mps = initial_vel_mps;
+        while runtime.is_running{
+            let x = runtime.get_x();
+            runtime.add_or_set("vel [m/s]", vel_mps);
+
+            // The closure integrates from the previous step
+            let prev_vel_mps = vel_mps - gravity_mps2 * dt;
+            linear.update(&mut runtime, vel_mps);
+            refined.update_refined(&mut runtime, vel_mps, |event_x|{
+                prev_vel_mps + gravity_mps2 * (event_x - (x - dt))
+            });
+
+            vel_mps += gravity_mps2 * dt;
+            runtime.increment();
+        }
+
+        // Velocity is logged before being stepped, so it is exact at x
+        let apogee_s = initial_vel_mps / -gravity_mps2;
+        let events = runtime.get_events();
+        assert_eq!(events.len(), 2);
+        for event in events{
+            assert!((event.x - apogee_s).abs() < dt / 100.0);
+        }
+    }
+
+    #[test]
+    fn multiple_events(){
+        let mut runtime = Runtime::new(10.0, 1e-2, "time [s]");
+        let mut detector = EventDetector::new("zero_crossing", 0.0);
+
+        while runtime.is_running{
+            let x = runtime.get_x();
+            detector.update(&mut runtime, (x - 0.5).sin());
+            runtime.increment();
+        }
+
+        // Crossings at 0.5 + n * pi
+        let events = runtime.get_events();
+        assert_eq!(events.len(), 4);
+        for (n, event) in events.iter().enumerate(){
+            assert_eq!(event.name, "zero_crossing");
+            assert_relative_eq!(
+                event.x,
+                0.5 + n as f64 * std::f64::consts::PI,
+                epsilon = 1e-4
+            );
+        }
+    }
+
+    #[test]
+    fn terminal_event(){
+        let mut runtime = Runtime::new(10.0, 1e-2, "time [s]");
+        let mut detector = EventDetector::new("limit", 2.0);
+        detector.set_direction(Direction::Rising);
+        detector.set_terminal(true);
+
+        let mut steps = 0;
+        while runtime.is_running{
+            let x = runtime.get_x();
+            runtime.add_or_set("x [-]", x);
+            detector.update(&mut runtime, x);
+            if !runtime.is_running{
+                break
+            }
+            steps += 1;
+            runtime.increment();
+        }
+
+        assert_eq!(runtime.get_events().len(), 1);
+        assert!(runtime.get_x() >= 2.0 && runtime.get_x() < 2.0 + 2e-2);
+        assert!(steps < 250);
+
+        let file_path = std::env::temp_dir().join("slippy_events.csv");
+        let file_path = file_path.to_str().unwrap();
+        runtime.export_events_to_csv(file_path);
+
+        let mut reader = csv::Reader::from_path(file_path).unwrap();
+        assert_eq!(
+            reader.headers().unwrap().iter().collect::<Vec<&str>>(),
+            vec!["event", "time [s]", "value"]
+        );
+        let records: Vec<csv::StringRecord> = reader.records()
+            .map(|record| record.unwrap())
+            .collect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(&records[0][0], "limit");
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::ops::{Mul, Div, Add};
 
 
@@ -38,6 +39,41 @@ pub trait Integrate{
         let euler =  self.clone() + (self.get_derivative() * dt);
         return euler
     }
+
+    fn adams_bashforth4(&mut self, history: &mut VecDeque<Self>, dt: f64) -> Self
+        where Self:
+            Sized +
+            Clone +
+            Add<Self, Output = Self> +
+            Mul<f64, Output = Self> +
+            Div<f64, Output = Self>,
+    {
+        // Source:
+        //    https://en.wikipedia.org/wiki/Linear_multistep_method
+        //
+        // Notes:
+        //     history holds the last three derivatives, oldest first, and is
+        //     filled by rk4 steps. Only for smooth dynamics with a fixed dt.
+        self.effects();
+        let derivative = self.get_derivative();
+
+        let next = if history.len() < 3{
+            self.rk4(dt)
+        } else{
+            self.clone() + (
+                (derivative.clone() * 55.0)
+                + (history[2].clone() * -59.0)
+                + (history[1].clone() * 37.0)
+                + (history[0].clone() * -9.0)
+            ) * dt / 24.0
+        };
+
+        history.push_back(derivative);
+        while history.len() > 3{
+            history.pop_front();
+        }
+        return next
+    }
 }
 
 #[cfg(test)]
@@ -45,6 +81,9 @@ mod tests {
 
     use super::*;
     use approx::assert_relative_eq;
+    use crate::test::assert_convergence_order;
+    use std::f64::consts::PI;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     #[derive(
         Debug,
         Clone,
@@ -103,6 +142,120 @@ mod tests {
     }
 
 
+    // x' = -x, x(t) = e^-t
+    #[derive(
+        Debug,
+        Clone,
+        Copy,
+        derive_more::Add,
+        derive_more::Mul,
+        derive_more::Div
+    )]
+    struct Decay{
+        x: f64
+    }
+
+    impl Integrate for Decay{
+        fn get_derivative(&self)-> Self {
+            return Decay{x: -self.x}
+        }
+    }
+
+    fn decay_error(dt: f64, use_rk4: bool) -> f64{
+        let mut state = Decay{x: 1.0};
+        let steps = (1.0 / dt).round() as usize;
+
+        for _ in 0..steps{
+            state = if use_rk4 {state.rk4(dt)} else {state.euler(dt)};
+        }
+        return (state.x - (-1.0_f64).exp()).abs()
+    }
+
+    fn decay_error_ab4(dt: f64) -> f64{
+        // Longer than decay_error so the rk4 startup steps do not dominate
+        let mut state = Decay{x: 1.0};
+        let mut history = VecDeque::new();
+        let steps = (2.0 / dt).round() as usize;
+
+        for _ in 0..steps{
+            state = state.adams_bashforth4(&mut history, dt);
+        }
+        return (state.x - (-2.0_f64).exp()).abs()
+    }
+
+    // x'' = -x, x(t) = cos(t)
+    static OSCILLATOR_EVALUATIONS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(
+        Debug,
+        Clone,
+        Copy,
+        derive_more::Add,
+        derive_more::Mul,
+        derive_more::Div
+    )]
+    struct Oscillator{
+        x: f64,
+        v: f64
+    }
+
+    impl Integrate for Oscillator{
+        fn get_derivative(&self)-> Self {
+            OSCILLATOR_EVALUATIONS.fetch_add(1, Ordering::Relaxed);
+            return Oscillator{x: self.v, v: -self.x}
+        }
+    }
+
+    fn oscillator_error(dt: f64, use_rk4: bool) -> (f64, usize){
+        // Error after 100 cycles and the derivative evaluations it took
+        let duration = 100.0 * 2.0 * PI;
+        let steps = (duration / dt).round() as usize;
+        let mut state = Oscillator{x: 1.0, v: 0.0};
+        let mut history = VecDeque::new();
+
+        let start_evaluations = OSCILLATOR_EVALUATIONS.load(Ordering::Relaxed);
+        for _ in 0..steps{
+            state = if use_rk4{
+                state.rk4(dt)
+            } else{
+                state.adams_bashforth4(&mut history, dt)
+            };
+        }
+        let evaluations = OSCILLATOR_EVALUATIONS.load(Ordering::Relaxed) - start_evaluations;
+
+        let time = steps as f64 * dt;
+        let error = (state.x - time.cos()).abs().max((state.v + time.sin()).abs());
+        return (error, evaluations)
+    }
+
+    #[test]
+    fn adams_bashforth4_convergence(){
+        assert_convergence_order(decay_error_ab4, 4.0, 0.15);
+    }
+
+    #[test]
+    fn adams_bashforth4_oscillator(){
+        // Same accuracy goal, each method at the largest dt that meets it.
+        // The evaluation count stands in for cost
+        let goal = 1e-6;
+        let (rk4_error, rk4_evaluations) = oscillator_error(0.02, true);
+        let (ab4_error, ab4_evaluations) = oscillator_error(0.008, false);
+
+        assert!(rk4_error < goal);
+        assert!(ab4_error < goal);
+        assert!(ab4_evaluations < rk4_evaluations);
+    }
+
+    #[test]
+    fn euler_convergence(){
+        assert_convergence_order(|dt| decay_error(dt, false), 1.0, 0.15);
+    }
+
+    #[test]
+    fn rk4_convergence(){
+        assert_convergence_order(|dt| decay_error(dt, true), 4.0, 0.15);
+    }
+
     #[test]
     fn euler(){
 
@@ -1,4 +1,17 @@
 pub mod integration;
 pub use integration::Integrate;
 pub mod runtime;
-pub use runtime::{Runtime,Save};
\ No newline at end of file
+pub use runtime::{Runtime,Save,RuntimeError,RuntimeConfig,NanPolicy,InfPolicy,OutputUnits};
+pub mod monte_carlo;
+pub use monte_carlo::{MonteCarlo, Dispersion, Dispersions, Metrics};
+pub mod batch;
+pub use batch::{run_batch, export_all};
+pub mod events;
+pub use events::{EventDetector, Event, Direction};
+pub mod channel;
+pub use channel::{ChannelInfo, Frame};
+pub mod analysis;
+pub mod diff;
+pub use diff::{diff_runs, SignalDiff};
+pub mod scheduler;
+pub use scheduler::{Scheduler, Task};
\ No newline at end of file
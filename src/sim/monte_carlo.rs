@@ -0,0 +1,308 @@
+use std::collections::{BTreeMap, HashMap};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand_distr::{Normal, Uniform, Distribution};
+
+use super::Runtime;
+use super::batch::parallel_map;
+
+pub type Metrics = BTreeMap<String, f64>;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Dispersion{
+    Normal{mean: f64, std: f64},
+    Uniform{min: f64, max: f64}
+}
+
+impl Dispersion{
+    fn sample(&self, rng: &mut StdRng) -> f64{
+        match *self{
+            Dispersion::Normal{mean, std} => {
+                let distr = Normal::new(mean, std).expect(
+                    "Could not create normal distribution from Dispersion"
+                );
+                return distr.sample(rng)
+            },
+            Dispersion::Uniform{min, max} => {
+                return Uniform::new_inclusive(min, max).sample(rng)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Dispersions{
+    pub run_index: usize,
+    values: HashMap<String, f64>
+}
+
+impl Dispersions{
+    pub fn get(&self, name: &str) -> f64{
+        if let Some(value) = self.values.get(name){
+            return *value
+        } else{
+            panic!("ERROR| Dispersion [{}] was never added", name)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricSummary{
+    pub name: String,
+    pub mean: f64,
+    pub std: f64,
+    pub min: f64,
+    pub max: f64,
+    pub p05: f64,
+    pub p50: f64,
+    pub p95: f64
+}
+
+pub struct MonteCarlo{
+    runs: usize,
+    seed: u64,
+    max_x_value: f64,
+    x_increment: f64,
+    x_key: String,
+    dispersions: BTreeMap<String, Dispersion>,
+    results: Vec<Metrics>,
+    csv_delimiter: u8,
+    csv_quote: u8
+}
+
+impl MonteCarlo{
+    pub fn new(
+        runs: usize,
+        seed: u64,
+        max_x_value: f64,
+        x_increment: f64,
+        x_key: &str
+    ) -> MonteCarlo{
+        // Each run gets a fresh Runtime built from these settings
+        return MonteCarlo{
+            runs,
+            seed,
+            max_x_value,
+            x_increment,
+            x_key: x_key.to_string(),
+            dispersions: BTreeMap::new(),
+            results: Vec::new(),
+            csv_delimiter: b',',
+            csv_quote: b'"'
+        }
+    }
+
+    pub fn set_csv_delimiter(&mut self, delimiter: u8){
+        // Passed on to each run's Runtime and the summary export
+        self.csv_delimiter = delimiter;
+    }
+
+    pub fn set_csv_quote(&mut self, quote: u8){
+        self.csv_quote = quote;
+    }
+
+    pub fn add_dispersion(&mut self, name: &str, dispersion: Dispersion){
+        self.dispersions.insert(name.to_string(), dispersion);
+    }
+
+    pub fn draw(&self, run_index: usize) -> Dispersions{
+        // Seeded per run so any single run can be reproduced on its own
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(run_index as u64));
+
+        let mut values = HashMap::new();
+        for (name, dispersion) in self.dispersions.iter(){
+            values.insert(name.clone(), dispersion.sample(&mut rng));
+        }
+
+        return Dispersions{run_index, values}
+    }
+
+    fn new_runtime(&self) -> Runtime{
+        let mut runtime = Runtime::new(self.max_x_value, self.x_increment, &self.x_key);
+        runtime.set_csv_delimiter(self.csv_delimiter);
+        runtime.set_csv_quote(self.csv_quote);
+        return runtime
+    }
+
+    pub fn run<F>(&mut self, mut sim: F) -> &Vec<Metrics>
+        where F: FnMut(&mut Runtime, &Dispersions) -> Metrics
+    {
+        self.results = (0..self.runs).map(|run_index|{
+            let mut runtime = self.new_runtime();
+            sim(&mut runtime, &self.draw(run_index))
+        }).collect();
+
+        return &self.results
+    }
+
+    pub fn run_parallel<F>(&mut self, threads: usize, sim: F) -> &Vec<Metrics>
+        where F: Fn(&mut Runtime, &Dispersions) -> Metrics + Sync
+    {
+        let run_indices: Vec<usize> = (0..self.runs).collect();
+        let this = &*self;
+
+        self.results = parallel_map(&run_indices, threads, |&run_index|{
+            let mut runtime = this.new_runtime();
+            sim(&mut runtime, &this.draw(run_index))
+        });
+
+        return &self.results
+    }
+
+    pub fn get_results(&self) -> &Vec<Metrics>{
+        return &self.results
+    }
+
+    pub fn summary(&self) -> Vec<MetricSummary>{
+        let mut samples: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        for metrics in self.results.iter(){
+            for (name, value) in metrics.iter(){
+                samples.entry(name.clone()).or_default().push(*value);
+            }
+        }
+
+        let mut summaries = Vec::new();
+        for (name, mut values) in samples.into_iter(){
+            values.sort_by(|a, b| a.total_cmp(b));
+
+            let count = values.len() as f64;
+            let mean = values.iter().sum::<f64>() / count;
+            let variance = values.iter()
+                .map(|value| (value - mean).powf(2.0))
+                .sum::<f64>() / (count - 1.0).max(1.0);
+
+            summaries.push(MetricSummary{
+                name,
+                mean,
+                std: variance.sqrt(),
+                min: values[0],
+                max: values[values.len() - 1],
+                p05: percentile(&values, 5.0),
+                p50: percentile(&values, 50.0),
+                p95: percentile(&values, 95.0)
+            });
+        }
+
+        return summaries
+    }
+
+    pub fn export_summary_csv(&self, file_path: &str){
+        // Same writer and CSV settings as the per-run exports
+        let mut runtime = Runtime::new_growable(self.x_increment, &self.x_key);
+        runtime.set_csv_delimiter(self.csv_delimiter);
+        runtime.set_csv_quote(self.csv_quote);
+        let mut writer = runtime.csv_writer(file_path);
+
+        writer.write_record(
+            ["metric", "mean", "std", "min", "max", "p05", "p50", "p95"]
+        ).unwrap();
+
+        // One row per metric
+        for summary in self.summary(){
+            writer.write_record([
+                summary.name,
+                summary.mean.to_string(),
+                summary.std.to_string(),
+                summary.min.to_string(),
+                summary.max.to_string(),
+                summary.p05.to_string(),
+                summary.p50.to_string(),
+                summary.p95.to_string()
+            ]).unwrap();
+        }
+        writer.flush().unwrap();
+    }
+}
+
+fn percentile(sorted: &[f64], percent: f64) -> f64{
+    // Linear interpolation between the closest ranks
+    let rank = (percent / 100.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+
+    return sorted[lower] + ((sorted[upper] - sorted[lower]) * (rank - lower as f64))
+}
+
+// ----------------------------------------------------------------------------
+// Tests
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn mass_metric(runtime: &mut Runtime, dispersions: &Dispersions) -> Metrics{
+        // Falls for the whole run under a dispersed mass and drag
+        let mass_kg = dispersions.get("mass [kg]");
+        let drag_n = dispersions.get("drag [N]");
+        let dt = runtime.get_dx();
+
+        let mut vel_mps = 0.0;
+        while runtime.is_running{
+            runtime.add_or_set("vel [m/s]", vel_mps);
+            vel_mps += ((-9.8 * mass_kg + drag_n) / mass_kg) * dt;
+            runtime.increment();
+        }
+
+        let mut metrics = Metrics::new();
+        metrics.insert("mass [kg]".to_string(), mass_kg);
+        metrics.insert("final_vel [m/s]".to_string(), vel_mps);
+        return metrics
+    }
+
+    #[test]
+    fn zero_dispersion(){
+        let mut monte_carlo = MonteCarlo::new(10, 7, 1.0, 1e-2, "time [s]");
+        monte_carlo.add_dispersion("mass [kg]", Dispersion::Normal{mean: 2.0, std: 0.0});
+        monte_carlo.add_dispersion("drag [N]", Dispersion::Uniform{min: 1.0, max: 1.0});
+
+        let results = monte_carlo.run(mass_metric).clone();
+
+        for metrics in results.iter(){
+            assert_eq!(metrics, &results[0]);
+        }
+    }
+
+    #[test]
+    fn normal_convergence(){
+        let mut monte_carlo = MonteCarlo::new(4000, 11, 0.1, 1e-2, "time [s]");
+        monte_carlo.add_dispersion("mass [kg]", Dispersion::Normal{mean: 5.0, std: 2.0});
+        monte_carlo.add_dispersion("drag [N]", Dispersion::Uniform{min: 0.0, max: 1.0});
+
+        monte_carlo.run_parallel(4, mass_metric);
+        let summary = monte_carlo.summary();
+        let mass = summary.iter().find(|s| s.name == "mass [kg]").unwrap();
+
+        assert_relative_eq!(mass.mean, 5.0, max_relative = 2e-2);
+        assert_relative_eq!(mass.std, 2.0, max_relative = 5e-2);
+        assert!(mass.min <= mass.p05 && mass.p05 <= mass.p50);
+        assert!(mass.p50 <= mass.p95 && mass.p95 <= mass.max);
+
+        // Parallel runs are reproducible serially
+        let parallel = monte_carlo.get_results().clone();
+        assert_eq!(monte_carlo.run(mass_metric), &parallel);
+    }
+
+    #[test]
+    fn summary_csv(){
+        let mut monte_carlo = MonteCarlo::new(20, 3, 0.1, 1e-2, "time [s]");
+        monte_carlo.add_dispersion("mass [kg]", Dispersion::Uniform{min: 1.0, max: 2.0});
+        monte_carlo.add_dispersion("drag [N]", Dispersion::Normal{mean: 0.0, std: 0.1});
+        monte_carlo.run(mass_metric);
+
+        let file_path = std::env::temp_dir().join("slippy_monte_carlo.csv");
+        let file_path = file_path.to_str().unwrap();
+        monte_carlo.set_csv_delimiter(b';');
+        monte_carlo.export_summary_csv(file_path);
+
+        let mut reader = csv::ReaderBuilder::new().delimiter(b';').from_path(file_path).unwrap();
+        let names: Vec<String> = reader.records()
+            .map(|record| record.unwrap()[0].to_string())
+            .collect();
+
+        assert_eq!(names, vec!["final_vel [m/s]", "mass [kg]"]);
+        let header = std::fs::read_to_string(file_path).unwrap();
+        assert!(header.starts_with("metric;mean;std;"));
+    }
+}
@@ -1,6 +1,11 @@
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, BTreeMap, BTreeSet};
 use std::path::Path;
 use csv;
+use serde_json;
+
+use super::events::Event;
+use super::channel::{ChannelInfo, Frame};
+use crate::{geo, units};
 
 #[allow(dead_code)]
 
@@ -11,7 +16,121 @@ pub struct Runtime{
     x_array: Vec<f64>,
     current_index: usize,
     pub is_running: bool,
-    data_dict: HashMap<String, Vec<f64>>
+    data_dict: HashMap<String, Vec<f64>>,
+    stride_dict: HashMap<String, usize>,
+    events: Vec<Event>,
+    config: RuntimeConfig,
+    warned_keys: BTreeSet<String>,
+    is_growable: bool,
+    channels: BTreeMap<String, ChannelInfo>,
+    csv_delimiter: u8,
+    csv_quote: u8
+}
+
+// Initial number of steps allocated by a growable runtime
+const GROWABLE_INITIAL_STEPS: usize = 1024;
+
+// Relative slack for max_x_value / x_increment to count as a whole number
+// of steps
+const X_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NanPolicy{
+    Panic,
+    Warn,
+    Ignore
+}
+
+// Same handling options for infinite values
+pub type InfPolicy = NanPolicy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct OutputUnits{
+    // Stored data is always SI, these only change the exported CSV
+    pub angles_deg: bool,
+    pub accel_g: bool
+}
+
+impl OutputUnits{
+    pub fn si() -> OutputUnits{
+        return OutputUnits::default()
+    }
+
+    pub fn engineering() -> OutputUnits{
+        return OutputUnits{angles_deg: true, accel_g: true}
+    }
+
+    fn convert(&self, key: &str) -> (String, f64){
+        // Relabelled key and the scale from SI, keys without a matching
+        // [unit] suffix are left alone
+        let deg = units::rad_to_deg(1.0);
+        let (name, unit) = match key.rfind(" ["){
+            Some(index) if key.ends_with(']') => (&key[..index], &key[index + 2..key.len() - 1]),
+            _ => return (key.to_string(), 1.0)
+        };
+
+        let (unit, scale) = match unit{
+            "rad" if self.angles_deg => ("deg", deg),
+            "rad/s" if self.angles_deg => ("deg/s", deg),
+            "rad/s^2" if self.angles_deg => ("deg/s^2", deg),
+            "m/s^2" if self.accel_g => ("g", units::mps2_to_g(1.0)),
+            _ => return (key.to_string(), 1.0)
+        };
+        return (format!("{name} [{unit}]"), scale)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RuntimeConfig{
+    pub nan_policy: NanPolicy,
+    pub inf_policy: InfPolicy,
+    pub output_units: OutputUnits,
+    // Write the frame of tagged channels into the csv header as
+    // {name}@{frame} [unit]
+    pub frame_tags: bool
+}
+
+impl Default for RuntimeConfig{
+    fn default() -> RuntimeConfig{
+        return RuntimeConfig{
+            nan_policy: NanPolicy::Warn,
+            inf_policy: InfPolicy::Warn,
+            output_units: OutputUnits::si(),
+            frame_tags: false
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RuntimeError{
+    Csv(csv::Error),
+    MissingKey(String),
+    BadValue(String)
+}
+
+impl std::fmt::Display for RuntimeError{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result{
+        match self{
+            RuntimeError::Csv(err) => write!(f, "ERROR| Csv error: {}", err),
+            RuntimeError::MissingKey(key) => write!(f, "ERROR| Key [{}] not found", key),
+            RuntimeError::BadValue(value) => write!(f, "ERROR| Could not parse [{}]", value)
+        }
+    }
+}
+
+impl From<csv::Error> for RuntimeError{
+    fn from(err: csv::Error) -> RuntimeError{
+        return RuntimeError::Csv(err)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RuntimeSnapshot{
+    current_index: usize,
+    data_dict: HashMap<String, Vec<f64>>,
+    stride_dict: HashMap<String, usize>,
+    events: Vec<Event>,
+    channels: BTreeMap<String, ChannelInfo>
 }
 
 impl Runtime{
@@ -20,40 +139,185 @@ impl Runtime{
         x_increment: f64,
         x_key: &str
     ) -> Runtime{
+        return Runtime::new_with_config(
+            max_x_value,
+            x_increment,
+            x_key,
+            RuntimeConfig::default()
+        )
+    }
 
-        // Intialize the array for which we will step through
-        let x_key = x_key.to_string();
-        let mut x_array: Vec<f64> = Vec::new();
+    pub fn new_with_config(
+        max_x_value: f64,
+        x_increment: f64,
+        x_key: &str,
+        config: RuntimeConfig
+    ) -> Runtime{
 
-        let mut current = 0.0;
-        while current < max_x_value{
-           current += x_increment;
-           x_array.push(current);
+        // Intialize the array for which we will step through, from 0.0 to
+        // the last step that does not pass max_x_value. That is n + 1 rows
+        // for n = max / increment, or the floor of it when it is not a whole
+        // number. A while is_running loop runs n times, the increment onto
+        // the last row stops it, so the final state is recorded with a save
+        // after the loop.
+        if x_increment <= 0.0{
+            panic!("    ERROR| X increment [{}] must be positive", x_increment)
         }
-
-        // Remove mutability from the x_array
-        let x_array = x_array;
+        let x_key = x_key.to_string();
+        let x_array = Runtime::build_x_array(max_x_value, x_increment);
+        let is_running = x_array.len() > 1;
 
         // Init Hashmap for Data Storage
         let data_dict: HashMap<String, Vec<f64>> = HashMap::new();
+        let stride_dict: HashMap<String, usize> = HashMap::new();
 
         return Runtime {
             x_key,
             x_increment,
             x_array,
             current_index: 0,
-            is_running: true,
-            data_dict
+            is_running,
+            data_dict,
+            stride_dict,
+            events: Vec::new(),
+            config,
+            warned_keys: BTreeSet::new(),
+            is_growable: false,
+            channels: BTreeMap::new(),
+            csv_delimiter: b',',
+            csv_quote: b'"'
+        }
+    }
+
+    fn build_x_array(max_x_value: f64, x_increment: f64) -> Vec<f64>{
+        // Multiples of the increment rather than a running sum so there is
+        // no accumulated error, and an exact last value when the steps fit
+        let ratio = (max_x_value / x_increment).max(0.0);
+        let nearest = ratio.round();
+        let is_whole = (ratio - nearest).abs() <= X_TOLERANCE * nearest.max(1.0);
+        let steps = if is_whole {nearest} else {ratio.floor()} as usize;
+
+        let mut x_array: Vec<f64> = (0..=steps).map(|i| i as f64 * x_increment).collect();
+        if is_whole && steps > 0{
+            x_array[steps] = max_x_value;
+        }
+        return x_array
+    }
+
+    pub fn new_growable(x_increment: f64, x_key: &str) -> Runtime{
+        // No fixed end, storage is extended as the runtime increments and
+        // is_running must be cleared by the caller
+        let mut runtime = Runtime::new(
+            x_increment * GROWABLE_INITIAL_STEPS as f64,
+            x_increment,
+            x_key
+        );
+        runtime.is_growable = true;
+        runtime.is_running = true;
+        return runtime
+    }
+
+    pub fn is_growable(&self) -> bool{
+        return self.is_growable
+    }
+
+    fn grow(&mut self){
+        // Doubles the storage, new steps continue the x_array as multiples
+        // of the increment like build_x_array
+        let start = self.x_array.len();
+        let additional = start.max(1);
+        self.x_array.extend((start..start + additional).map(|i| i as f64 * self.x_increment));
+
+        let len = self.x_array.len();
+        for (_, array) in self.data_dict.iter_mut(){
+            array.resize(len, 0.0);
         }
     }
 
+    pub fn from_csv(file_path: &str, x_key: &str) -> Result<Runtime, RuntimeError>{
+        // Rebuilds a finished runtime from export_to_csv output, blank cells
+        // from multi-rate exports are read back as NaN
+        let mut reader = csv::Reader::from_path(Path::new(file_path))?;
+        let header: Vec<String> = reader.headers()?.iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        if !header.iter().any(|key| key == x_key){
+            return Err(RuntimeError::MissingKey(x_key.to_string()))
+        }
+
+        let mut columns: Vec<Vec<f64>> = vec![Vec::new(); header.len()];
+        for record in reader.records(){
+            let record = record?;
+            for (i, cell) in record.iter().enumerate(){
+                let value = if cell.is_empty(){
+                    f64::NAN
+                } else{
+                    cell.parse::<f64>()
+                        .map_err(|_| RuntimeError::BadValue(cell.to_string()))?
+                };
+                columns[i].push(value);
+            }
+        }
+
+        let mut x_array = Vec::new();
+        let mut data_dict = HashMap::new();
+        for (key, column) in header.into_iter().zip(columns.into_iter()){
+            if key == x_key{
+                x_array = column;
+            } else{
+                data_dict.insert(key, column);
+            }
+        }
+
+        if x_array.is_empty(){
+            return Err(RuntimeError::MissingKey(x_key.to_string()))
+        }
+
+        let x_increment = if x_array.len() > 1{
+            x_array[1] - x_array[0]
+        } else{
+            0.0
+        };
+
+        let mut channels = BTreeMap::new();
+        for key in data_dict.keys(){
+            let channel = ChannelInfo::parse(key);
+            channels.insert(channel.get_path(), channel);
+        }
+
+        return Ok(Runtime {
+            x_key: x_key.to_string(),
+            x_increment,
+            current_index: x_array.len() - 1,
+            x_array,
+            is_running: false,
+            data_dict,
+            stride_dict: HashMap::new(),
+            events: Vec::new(),
+            config: RuntimeConfig::default(),
+            warned_keys: BTreeSet::new(),
+            is_growable: false,
+            channels,
+            csv_delimiter: b',',
+            csv_quote: b'"'
+        })
+    }
+
     pub fn add_or_set(&mut self, key: &str, value: f64) {
 
+        if value.is_nan(){
+            self.handle_non_finite(self.config.nan_policy, key, value);
+        } else if value.is_infinite(){
+            self.handle_non_finite(self.config.inf_policy, key, value);
+        }
+
         if self.data_dict.contains_key(key){
             self.value_set(key, value);
         }
 
         else if self.current_index == 0{
+            self.register_channel(key);
             self.data_dict.insert(
                 key.to_string(),
                 vec![0.0; self.x_array.len()]
@@ -62,18 +326,204 @@ impl Runtime{
         }
 
         else{
-            panic!(
-                "    ERROR| Dyanamic key [{}] must be intialized \
-                befor incrementing the runtime. \n
-                           Index is currently [{}]",
+            // Late registration, backfill the previous indices with NaN
+            self.register_channel(key);
+            self.data_dict.insert(
+                key.to_string(),
+                vec![f64::NAN; self.x_array.len()]
+            );
+            self.value_set(key, value);
+        }
+    }
+
+    fn register_channel(&mut self, key: &str){
+        // A signal can only be registered with one unit
+        let channel = ChannelInfo::parse(key);
+        if let Some(existing) = self.channels.get(&channel.get_path()){
+            if existing.unit != channel.unit{
+                panic!(
+                    "    ERROR| Channel [{}] is registered as [{}], not [{}]",
+                    channel.get_path(),
+                    existing.unit,
+                    channel.unit
+                )
+            }
+        }
+        self.channels.insert(channel.get_path(), channel);
+    }
+
+    pub fn add_channel(&mut self, node: &str, name: &str, unit: &str) -> String{
+        // Registers a key ahead of its first value, returns the key to use
+        // with add_or_set. Unset steps are NaN.
+        let key = ChannelInfo::format_key(node, name, unit);
+        if !self.data_dict.contains_key(&key){
+            self.register_channel(&key);
+            self.data_dict.insert(key.clone(), vec![f64::NAN; self.x_array.len()]);
+        }
+        return key
+    }
+
+    pub fn channels(&self) -> Vec<ChannelInfo>{
+        // Sorted by key, x key not included
+        let mut channels: Vec<ChannelInfo> = self.channels.values().cloned().collect();
+        channels.sort_by(|a, b| a.key.cmp(&b.key));
+        return channels
+    }
+
+    pub fn channels_in_node(&self, node: &str) -> Vec<ChannelInfo>{
+        return self.channels().into_iter().filter(|channel| channel.node == node).collect()
+    }
+
+    pub fn channels_with_unit(&self, unit: &str) -> Vec<ChannelInfo>{
+        return self.channels().into_iter().filter(|channel| channel.unit == unit).collect()
+    }
+
+    pub fn get_series(&self, key: &str) -> &[f64]{
+        // Values up to and including the current index
+        match self.data_dict.get(key){
+            Some(array) => return &array[..=self.current_index],
+            None => panic!("    ERROR| Series Key [{}] not in data_dict", key)
+        }
+    }
+
+    pub fn get_history(&self, key: &str) -> &Vec<f64>{
+        // Full storage, steps past the current index hold their initial
+        // value until the run reaches them
+        match self.data_dict.get(key){
+            Some(array) => return array,
+            None => panic!("    ERROR| History Key [{}] not in data_dict", key)
+        }
+    }
+
+    pub fn get_x_array(&self) -> &Vec<f64>{
+        return &self.x_array
+    }
+
+    pub fn add_vector3(&mut self, prefix: &str, unit: &str, vec: geo::Vector3){
+        // Logs {prefix}.x/y/z [unit]
+        self.add_or_set(format!("{prefix}.x [{unit}]").as_str(), vec.i);
+        self.add_or_set(format!("{prefix}.y [{unit}]").as_str(), vec.j);
+        self.add_or_set(format!("{prefix}.z [{unit}]").as_str(), vec.k);
+    }
+
+    pub fn add_vector3_in_frame(&mut self, prefix: &str, unit: &str, frame: Frame, vec: geo::Vector3){
+        // add_vector3 with the frame recorded on each channel
+        self.add_vector3(prefix, unit, vec);
+        for axis in ["x", "y", "z"]{
+            self.set_frame(format!("{prefix}.{axis} [{unit}]").as_str(), frame);
+        }
+    }
+
+    pub fn set_frame(&mut self, key: &str, frame: Frame){
+        // A signal can only be registered in one frame
+        let path = ChannelInfo::parse(key).get_path();
+        let channel = match self.channels.get_mut(&path){
+            Some(channel) => channel,
+            None => panic!("    ERROR| Frame Key [{}] not in data_dict", key)
+        };
+        match channel.frame{
+            Some(existing) if existing != frame => panic!(
+                "    ERROR| Channel [{}] is registered in the [{}] frame, not [{}]",
+                path,
+                existing.tag(),
+                frame.tag()
+            ),
+            _ => channel.frame = Some(frame)
+        }
+    }
+
+    pub fn get_frame(&self, key: &str) -> Option<Frame>{
+        return self.channels.get(&ChannelInfo::parse(key).get_path())
+            .and_then(|channel| channel.frame)
+    }
+
+    pub fn add_quaternion(&mut self, prefix: &str, quat: geo::Quaternion){
+        // Logs {prefix}.a/b/c/d [-]
+        self.add_or_set(format!("{prefix}.a [-]").as_str(), quat.a);
+        self.add_or_set(format!("{prefix}.b [-]").as_str(), quat.b);
+        self.add_or_set(format!("{prefix}.c [-]").as_str(), quat.c);
+        self.add_or_set(format!("{prefix}.d [-]").as_str(), quat.d);
+    }
+
+    pub fn add_matrix3x3(&mut self, prefix: &str, unit: &str, matrix: geo::Matrix3x3){
+        // Logs {prefix}.c11 ... c33 [unit]
+        let names = ["c11", "c12", "c13", "c21", "c22", "c23", "c31", "c32", "c33"];
+        for (name, value) in names.iter().zip(matrix.to_array()){
+            self.add_or_set(format!("{prefix}.{name} [{unit}]").as_str(), value);
+        }
+    }
+
+    fn handle_non_finite(&mut self, policy: NanPolicy, key: &str, value: f64){
+        match policy{
+            NanPolicy::Panic => panic!(
+                "    ERROR| Key [{}] set to [{}] at index [{}]",
                 key,
-                self.current_index + 1
+                value,
+                self.current_index
+            ),
+            NanPolicy::Warn => {
+                // Only the first occurrence per key is printed
+                if self.warned_keys.insert(key.to_string()){
+                    eprintln!(
+                        "    WARNING| Key [{}] set to [{}] at index [{}]",
+                        key,
+                        value,
+                        self.current_index
+                    );
+                }
+            },
+            NanPolicy::Ignore => ()
+        }
+    }
+
+    pub fn get_warned_keys(&self) -> Vec<String>{
+        // Keys that have been set to a non-finite value under the Warn policy
+        return self.warned_keys.iter().cloned().collect()
+    }
+
+    pub fn add_or_set_at_rate(&mut self, key: &str, value: f64, rate_hz: f64){
+        // Records the key only on steps matching its rate, the value is held
+        // between updates
+        let stride = self.rate_to_stride(rate_hz);
+
+        match self.stride_dict.get(key){
+            Some(&current) if current != stride => panic!(
+                "    ERROR| Key [{}] was registered at a different rate",
+                key
+            ),
+            _ => {self.stride_dict.insert(key.to_string(), stride);}
+        };
+
+        if self.current_index % stride == 0{
+            self.add_or_set(key, value);
+        }
+    }
+
+    pub fn is_step_at_rate(&self, rate_hz: f64) -> bool{
+        return self.current_index % self.rate_to_stride(rate_hz) == 0
+    }
+
+    fn rate_to_stride(&self, rate_hz: f64) -> usize{
+        // Number of base steps between updates of a slower signal
+        let stride = (1.0 / (rate_hz * self.x_increment)).round();
+
+        if stride.is_nan() || stride < 1.0{
+            panic!(
+                "    ERROR| Rate [{} Hz] is faster than the runtime rate [{} Hz]",
+                rate_hz,
+                1.0 / self.x_increment
             )
         }
+
+        return stride as usize
     }
 
     pub fn increment(&mut self){
 
+        if self.is_growable && self.current_index >= (self.x_array.len() - 1){
+            self.grow();
+        }
+
         if self.current_index < (self.x_array.len() - 1){
             self.current_index += 1;
 
@@ -81,12 +531,19 @@ impl Runtime{
             for (_, array) in self.data_dict.iter_mut(){
                 array[self.current_index] = array[self.current_index - 1];
             }
-        } else {
+        }
+
+        // Stops on reaching the last row, which is left for a final save
+        if self.is_at_end(){
             self.is_running = false;
-        };
+        }
 
     }
 
+    fn is_at_end(&self) -> bool{
+        return !self.is_growable && self.current_index >= (self.x_array.len() - 1)
+    }
+
     pub fn value_set(&mut self, key: &str, value: f64){
         // Read the current value
         if let Some(array) = self.data_dict.get_mut(key){
@@ -105,6 +562,48 @@ impl Runtime{
         }
     }
 
+    pub fn add_event(&mut self, event: Event){
+        self.events.push(event);
+    }
+
+    pub fn get_events(&self) -> &[Event]{
+        return &self.events
+    }
+
+    pub fn snapshot(&self) -> RuntimeSnapshot{
+        return RuntimeSnapshot{
+            current_index: self.current_index,
+            data_dict: self.data_dict.clone(),
+            stride_dict: self.stride_dict.clone(),
+            events: self.events.clone(),
+            channels: self.channels.clone()
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: RuntimeSnapshot){
+        // Rewinds to the snapshot, anything recorded after it is discarded
+        if snapshot.current_index >= self.x_array.len(){
+            panic!(
+                "    ERROR| Snapshot index [{}] is past the end of the runtime",
+                snapshot.current_index
+            )
+        }
+
+        self.current_index = snapshot.current_index;
+        self.data_dict = snapshot.data_dict;
+        self.stride_dict = snapshot.stride_dict;
+        self.events = snapshot.events;
+        self.channels = snapshot.channels;
+        self.is_running = !self.is_at_end();
+    }
+
+    pub fn list_keys(&self) -> Vec<String>{
+        // Sorted to match the export column order, x key not included
+        let mut keys: Vec<String> = self.data_dict.keys().cloned().collect();
+        keys.sort();
+        return keys
+    }
+
     pub fn get_curr_index(&self) -> usize{
         return self.current_index
     }
@@ -138,11 +637,126 @@ impl Runtime{
         self.data_dict = new_hashmap;
     }
 
+    pub fn set_csv_delimiter(&mut self, delimiter: u8){
+        // Field separator for all CSV exports, e.g. b';' or b'\t'
+        self.csv_delimiter = delimiter;
+    }
+
+    pub fn set_csv_quote(&mut self, quote: u8){
+        // Quote character for fields that contain the delimiter
+        self.csv_quote = quote;
+    }
+
     pub fn export_to_csv(&mut self, file_path: &str){
+        // Slower signals hold their last value between updates
+        self.write_csv(file_path, false, 1, None);
+    }
+
+    pub fn export_to_csv_decimated(&mut self, file_path: &str, stride: usize){
+        // Every stride-th row, the first and last rows are always kept
+        if stride == 0{
+            panic!("    ERROR| Decimation stride must be at least 1")
+        }
+        self.write_csv(file_path, false, stride, None);
+    }
+
+    pub fn export_to_csv_filtered(&mut self, file_path: &str, keys: &[&str]){
+        // Only the named keys are written, the x key is always included
+        for &key in keys.iter(){
+            if !self.data_dict.contains_key(key){
+                panic!("    ERROR| Export Key [{}] not in data_dict", key)
+            }
+        }
+        self.write_csv(file_path, false, 1, Some(keys));
+    }
+
+    pub fn export_phase_plane(&mut self, x_key: &str, y_key: &str, file_path: &str){
+        // One signal against another, rows are paired by step index
+        for &key in [x_key, y_key].iter(){
+            if key != self.x_key && !self.data_dict.contains_key(key){
+                panic!("    ERROR| Export Key [{}] not in data_dict", key)
+            }
+        }
+
+        let mut writer = self.csv_writer(file_path);
+
+        // Trim the data
+        self.trim_from_curr_index();
+
+        let x_values = self.data_dict.get(x_key).unwrap_or(&self.x_array);
+        let y_values = self.data_dict.get(y_key).unwrap_or(&self.x_array);
+
+        writer.write_record([x_key, y_key]).unwrap();
+        for (x, y) in x_values.iter().zip(y_values.iter()){
+            writer.write_record([x.to_string(), y.to_string()]).unwrap();
+        }
+        writer.flush().unwrap();
+    }
+
+    pub fn export_to_json(&mut self, file_path: &str){
+        // Pretty printed, one key per array
+        self.write_json(file_path, true);
+    }
+
+    pub fn export_to_json_compact(&mut self, file_path: &str){
+        self.write_json(file_path, false);
+    }
+
+    fn write_json(&mut self, file_path: &str, pretty: bool){
+        let path = Path::new(file_path);
+
+        // Trim the data
+        self.trim_from_curr_index();
+
+        // Sort Alphabetically, x key included
+        let mut sorted_datadict: BTreeMap<String, Vec<f64>> =
+            self.data_dict.clone().into_iter().collect();
+        sorted_datadict.insert(self.x_key.clone(), self.x_array.clone());
+
+        let json = if pretty{
+            serde_json::to_string_pretty(&sorted_datadict)
+        } else{
+            serde_json::to_string(&sorted_datadict)
+        }.expect("ERROR| Could not serialize the runtime to json");
+
+        // Attempt to write to this path and overwrite
+        if let Err(err) = std::fs::write(path, json){
+            panic!(
+                "ERROR| Could not export to path {}: {}",
+                path.to_string_lossy(),
+                err
+            );
+        }
+    }
+
+    pub fn export_events_to_csv(&self, file_path: &str){
+        let mut writer = self.csv_writer(file_path);
+
+        writer.write_record(["event", self.x_key.as_str(), "value"]).unwrap();
+        for event in self.events.iter(){
+            writer.write_record([
+                event.name.clone(),
+                event.x.to_string(),
+                event.value.to_string()
+            ]).unwrap();
+        }
+        writer.flush().unwrap();
+    }
+
+    pub fn export_to_csv_blanked(&mut self, file_path: &str){
+        // Slower signals are left empty between updates
+        self.write_csv(file_path, true, 1, None);
+    }
+
+    pub(crate) fn csv_writer(&self, file_path: &str) -> csv::Writer<std::fs::File>{
         let path = Path::new(file_path);
 
         // Attempt to write to this path and overwrite
-        let mut writer = match csv::Writer::from_path(&path){
+        let writer = csv::WriterBuilder::new()
+            .delimiter(self.csv_delimiter)
+            .quote(self.csv_quote)
+            .from_path(path);
+        return match writer{
             Ok(file) => file,
             Err(err) => {
                 panic!(
@@ -151,29 +765,63 @@ impl Runtime{
                     err
                 );
             }
-        };
+        }
+    }
+
+    fn write_csv(
+        &mut self,
+        file_path: &str,
+        blank_between_updates: bool,
+        row_stride: usize,
+        keys: Option<&[&str]>
+    ){
+        let mut writer = self.csv_writer(file_path);
 
         // Trim the data
         self.trim_from_curr_index();
 
         // Sort Alphabetically
-        let sorted_datadict: BTreeMap<String, Vec<f64>> =
-            self.data_dict.clone().into_iter().collect();
+        let sorted_datadict: BTreeMap<String, Vec<f64>> = self.data_dict.clone()
+            .into_iter()
+            .filter(|(key, _)| keys.is_none_or(|keys| keys.contains(&key.as_str())))
+            .collect();
 
         // Header
         let mut header: Vec<&str> = sorted_datadict.keys().map(|s| s.as_str()).collect();
         header.push(self.x_key.as_str());
 
-        writer.write_record(&header).unwrap();
+        // Labels and scales in the configured output units
+        let (labels, scales): (Vec<String>, Vec<f64>) = header.iter()
+            .map(|&key| if key == self.x_key {(key.to_string(), 1.0)} else {self.config.output_units.convert(key)})
+            .unzip();
+
+        // Frame tags after the unit conversion so they land on the new label
+        let labels: Vec<String> = labels.into_iter().zip(header.iter())
+            .map(|(label, &key)| match self.get_frame(key){
+                Some(frame) if self.config.frame_tags && key != self.x_key => ChannelInfo::tag_key(&label, frame),
+                _ => label
+            })
+            .collect();
+        writer.write_record(&labels).unwrap();
 
         // Body
+        let last_index = self.x_array.len() - 1;
         for (i, &time) in self.x_array.iter().enumerate(){
+            if i % row_stride != 0 && i != last_index{
+                continue
+            }
 
             let mut data_row: Vec<String> = Vec::new();
-            for &key in header.iter(){
+            for (&key, scale) in header.iter().zip(scales.iter()){
                 if key != self.x_key{
+                    let stride = self.stride_dict.get(key).unwrap_or(&1);
+                    if blank_between_updates && i % stride != 0{
+                        data_row.push(String::new());
+                        continue
+                    }
+
                     let value = sorted_datadict.get(key).unwrap();
-                    let value = value[i];
+                    let value = value[i] * scale;
                     data_row.push(
                         value.to_string()
                     );
@@ -193,6 +841,8 @@ impl Runtime{
 
 }
 pub trait Save{
+    // The single recording interface for all models, keys are prefixed with
+    // node_name and written at the runtime's current index
     fn save_data(&self, node_name: &str, runtime: &mut Runtime) where Self: Sized;
 
     fn save_data_verbose(&self, node_name: &str, runtime: &mut Runtime) where Self: Sized{
@@ -205,6 +855,7 @@ pub trait Save{
 #[cfg(test)]
 mod tests {
     use super::*;
+    use approx::assert_relative_eq;
 
     #[test]
     fn basic_test() {
@@ -245,4 +896,557 @@ mod tests {
         // runtime.export_to_csv("test", "")
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn x_array_length(){
+        // (max, increment, rows, loop updates, last x), the loop runs once
+        // per step and the last row is left for a save after it
+        let cases = [
+            (10.0, 1.0, 11, 10, 10.0),
+            (1.0, 0.1, 11, 10, 1.0),
+            (0.3, 0.1, 4, 3, 0.3),
+            (100.0, 1e-3, 100_001, 100_000, 100.0),
+            (1.0, 0.3, 4, 3, 0.9),
+            (0.25, 0.1, 3, 2, 0.2),
+            (0.05, 0.1, 1, 0, 0.0)
+        ];
+        for (max_x, increment, len, updates, last_x) in cases{
+            let mut runtime = Runtime::new(max_x, increment, "time [s]");
+            assert_eq!(runtime.x_array.len(), len, "max {max_x} increment {increment}");
+            assert_eq!(runtime.x_array[0], 0.0);
+            assert_relative_eq!(runtime.get_max_x(), last_x, max_relative = 1e-12);
+            assert!(runtime.get_max_x() <= max_x);
+
+            let mut count = 0;
+            while runtime.is_running{
+                assert_relative_eq!(runtime.get_x(), increment * count as f64, epsilon = 1e-9);
+                count += 1;
+                runtime.increment();
+            }
+            assert_eq!(count, updates, "max {max_x} increment {increment}");
+            assert_eq!(runtime.get_x(), runtime.get_max_x());
+        }
+
+        // Integrating in the loop leaves the state at max_x for the last row
+        let mut runtime = Runtime::new(1.0, 0.1, "time [s]");
+        let mut x = 0.0;
+        while runtime.is_running{
+            runtime.add_or_set("x [-]", x);
+            x += 0.1;
+            runtime.increment();
+        }
+        runtime.add_or_set("x [-]", x);
+        let series = runtime.get_series("x [-]");
+        for (value, time) in series.iter().zip(runtime.get_x_array().iter()){
+            assert_relative_eq!(*value, *time, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn multi_rate(){
+        let fast_key = "fast_count [-]";
+        let slow_key = "slow_count [-]";
+
+        // 1 kHz runtime with a 100 Hz signal
+        let mut runtime = Runtime::new(0.1, 1e-3, "time [s]");
+
+        let mut count = 0.0;
+        while runtime.is_running{
+            runtime.add_or_set(fast_key, count);
+            runtime.add_or_set_at_rate(slow_key, count, 100.0);
+
+            assert_eq!(
+                runtime.is_step_at_rate(100.0),
+                runtime.get_curr_index() % 10 == 0
+            );
+
+            count += 1.0;
+            runtime.increment();
+        }
+
+        // Final row
+        runtime.add_or_set(fast_key, count);
+        runtime.add_or_set_at_rate(slow_key, count, 100.0);
+
+        let file_path = std::env::temp_dir().join("slippy_multi_rate.csv");
+        let file_path = file_path.to_str().unwrap();
+
+        // Held
+        runtime.export_to_csv(file_path);
+        let mut reader = csv::Reader::from_path(file_path).unwrap();
+        let header = reader.headers().unwrap().clone();
+        let slow_col = header.iter().position(|h| h == slow_key).unwrap();
+        let fast_col = header.iter().position(|h| h == fast_key).unwrap();
+
+        for (i, record) in reader.records().enumerate(){
+            let record = record.unwrap();
+            let fast: f64 = record[fast_col].parse().unwrap();
+            let slow: f64 = record[slow_col].parse().unwrap();
+
+            assert_eq!(fast, i as f64);
+            assert_eq!(slow, (i - (i % 10)) as f64);
+        }
+
+        // Blanked
+        runtime.export_to_csv_blanked(file_path);
+        let mut reader = csv::Reader::from_path(file_path).unwrap();
+
+        for (i, record) in reader.records().enumerate(){
+            let record = record.unwrap();
+            if i % 10 == 0{
+                let slow: f64 = record[slow_col].parse().unwrap();
+                assert_eq!(slow, i as f64);
+            } else{
+                assert!(record[slow_col].is_empty());
+            }
+            assert!(!record[fast_col].is_empty());
+        }
+    }
+
+    #[test]
+    fn late_registration(){
+        let late_key = "late_count [-]";
+
+        let mut runtime = Runtime::new(10.0, 1.0, "time [s]");
+
+        let mut count = 0.0;
+        while runtime.is_running{
+            runtime.add_or_set("count [-]", count);
+
+            if runtime.get_curr_index() >= 5{
+                runtime.add_or_set(late_key, count);
+            }
+
+            count += 1.0;
+            runtime.increment();
+        }
+
+        // Final row
+        runtime.add_or_set("count [-]", count);
+        runtime.add_or_set(late_key, count);
+
+        let file_path = std::env::temp_dir().join("slippy_late_registration.csv");
+        let file_path = file_path.to_str().unwrap();
+        runtime.export_to_csv(file_path);
+
+        let mut reader = csv::Reader::from_path(file_path).unwrap();
+        let header = reader.headers().unwrap().clone();
+        let late_col = header.iter().position(|h| h == late_key).unwrap();
+
+        for (i, record) in reader.records().enumerate(){
+            let value: f64 = record.unwrap()[late_col].parse().unwrap();
+            if i < 5{
+                assert!(value.is_nan());
+            } else{
+                assert_eq!(value, i as f64);
+            }
+        }
+    }
+
+    #[test]
+    fn json_round_trip(){
+        let mut runtime = Runtime::new(1.0, 0.1, "time [s]");
+
+        let mut count = 0.0;
+        while runtime.is_running{
+            runtime.add_or_set("count [-]", count);
+            runtime.add_or_set("double [-]", count * 2.0);
+
+            count += 1.0;
+            runtime.increment();
+        }
+
+        let file_path = std::env::temp_dir().join("slippy_round_trip.json");
+        let file_path = file_path.to_str().unwrap();
+
+        for pretty in [true, false]{
+            if pretty{
+                runtime.export_to_json(file_path);
+            } else{
+                runtime.export_to_json_compact(file_path);
+            }
+
+            let json = std::fs::read_to_string(file_path).unwrap();
+            assert_eq!(json.contains('\n'), pretty);
+            let parsed: HashMap<String, Vec<f64>> = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(parsed.len(), runtime.data_dict.len() + 1);
+            assert_eq!(parsed.get("time [s]").unwrap(), &runtime.x_array);
+            for (key, array) in runtime.data_dict.iter(){
+                assert_eq!(parsed.get(key).unwrap(), array);
+            }
+        }
+    }
+
+    #[test]
+    fn snapshot_restore(){
+        use crate::geo;
+        use crate::physics::RigidBody;
+        use crate::instrumentation::BasicSensor;
+        use crate::sim::{Save, Integrate};
+
+        let mut runtime = Runtime::new(10.0, 1e-2, "time [s]");
+        let dt = runtime.get_dx();
+
+        let mut body = RigidBody::identity();
+        let mut sensor = BasicSensor::new_std_seeded(0.1, 1.0, 0.0, "m", 42);
+
+        let mut checkpoint = None;
+        let mut first_run: Option<HashMap<String, Vec<f64>>> = None;
+
+        loop{
+            while runtime.is_running{
+                if checkpoint.is_none() && runtime.get_x() >= 5.0{
+                    // The whole body rather than its state array, so the
+                    // derived accelerations and held loads come back too
+                    checkpoint = Some((
+                        runtime.snapshot(),
+                        body.clone(),
+                        sensor.clone()
+                    ));
+                }
+
+                // Noisy feedback so the sensor drives the dynamics
+                let measured = sensor.output(body.get_intertial_pos_m().k);
+                body.body_force_n = geo::Vector3::new(0.0, 0.0, 1.0 - measured);
+
+                body.save_data("body", &mut runtime);
+                sensor.save_data("sensor", &mut runtime);
+
+                body = body.rk4(dt);
+                runtime.increment();
+            }
+
+            if first_run.is_some(){
+                break
+            }
+            first_run = Some(runtime.data_dict.clone());
+
+            // Rewind to 5 s and fly the second half again
+            let (snapshot, saved_body, saved_sensor) = checkpoint.clone().unwrap();
+            runtime.restore(snapshot);
+            body = saved_body;
+            sensor = saved_sensor;
+        }
+
+        let first_run = first_run.unwrap();
+        let start = checkpoint.unwrap().0.current_index;
+        assert!(start > 0);
+
+        // The replay matches from the checkpoint row onward
+        for (key, array) in runtime.data_dict.iter(){
+            assert_eq!(&first_run.get(key).unwrap()[start..], &array[start..]);
+        }
+    }
+
+    #[test]
+    fn csv_tab_delimiter(){
+        let mut runtime = Runtime::new(0.3, 0.1, "time [s]");
+        while runtime.is_running{
+            runtime.add_or_set("a [-]", 1.5);
+            runtime.add_or_set("b, with comma [-]", -2.0);
+            runtime.increment();
+        }
+
+        let file_path = std::env::temp_dir().join("slippy_tab_delimiter.tsv");
+        let file_path = file_path.to_str().unwrap();
+        runtime.set_csv_delimiter(b'\t');
+        runtime.set_csv_quote(b'\'');
+        runtime.export_to_csv(file_path);
+
+        let text = std::fs::read_to_string(file_path).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(lines.next().unwrap(), "a [-]\tb, with comma [-]\ttime [s]");
+        for line in lines{
+            let fields: Vec<&str> = line.split('\t').collect();
+            assert_eq!(fields.len(), 3);
+            assert_eq!(fields[..2], ["1.5", "-2"]);
+        }
+
+        // Quoted when the field holds the delimiter
+        runtime.set_csv_delimiter(b',');
+        runtime.export_to_csv(file_path);
+        let text = std::fs::read_to_string(file_path).unwrap();
+        assert!(text.starts_with("a [-],'b, with comma [-]',time [s]"));
+    }
+
+    #[test]
+    fn csv_round_trip(){
+        let mut runtime = Runtime::new(1.0, 0.1, "time [s]");
+
+        let mut count = 0.0;
+        while runtime.is_running{
+            runtime.add_or_set("count [-]", count);
+            runtime.add_or_set("half [-]", count / 2.0);
+
+            count += 1.0;
+            runtime.increment();
+        }
+
+        let file_path = std::env::temp_dir().join("slippy_csv_round_trip.csv");
+        let file_path = file_path.to_str().unwrap();
+        runtime.export_to_csv(file_path);
+
+        let imported = Runtime::from_csv(file_path, "time [s]").unwrap();
+
+        assert_eq!(imported.x_array, runtime.x_array);
+        assert_eq!(imported.data_dict, runtime.data_dict);
+        assert_eq!(imported.get_value("count [-]"), runtime.get_value("count [-]"));
+        assert_relative_eq!(imported.get_dx(), runtime.get_dx(), max_relative = 1e-9);
+
+        // Wrong x key
+        assert!(matches!(
+            Runtime::from_csv(file_path, "angle [rad]"),
+            Err(RuntimeError::MissingKey(_))
+        ));
+    }
+
+    #[test]
+    fn decimated_export(){
+        let mut runtime = Runtime::new(1.0, 1e-3, "time [s]");
+
+        let mut count = 0.0;
+        while runtime.is_running{
+            runtime.add_or_set("count [-]", count);
+            count += 1.0;
+            runtime.increment();
+        }
+
+        // Final row
+        runtime.add_or_set("count [-]", count);
+
+        let file_path = std::env::temp_dir().join("slippy_decimated.csv");
+        let file_path = file_path.to_str().unwrap();
+        runtime.export_to_csv_decimated(file_path, 10);
+
+        let full_x = runtime.x_array.clone();
+        let mut expected_rows: Vec<usize> = (0..full_x.len()).step_by(10).collect();
+        if *expected_rows.last().unwrap() != full_x.len() - 1{
+            expected_rows.push(full_x.len() - 1);
+        }
+
+        let mut reader = csv::Reader::from_path(file_path).unwrap();
+        let records: Vec<csv::StringRecord> = reader.records()
+            .map(|record| record.unwrap())
+            .collect();
+
+        assert_eq!(records.len(), expected_rows.len());
+        for (record, &i) in records.iter().zip(expected_rows.iter()){
+            let count: f64 = record[0].parse().unwrap();
+            let time: f64 = record[1].parse().unwrap();
+            assert_eq!(count, i as f64);
+            assert_eq!(time, full_x[i]);
+        }
+    }
+
+    #[test]
+    fn filtered_export(){
+        let mut runtime = Runtime::new(1.0, 0.1, "time [s]");
+
+        let mut count = 0.0;
+        while runtime.is_running{
+            runtime.add_or_set("c [-]", count);
+            runtime.add_or_set("a [-]", count * 2.0);
+            runtime.add_or_set("b [-]", count * 3.0);
+
+            count += 1.0;
+            runtime.increment();
+        }
+
+        // Final row
+        runtime.add_or_set("c [-]", count);
+        runtime.add_or_set("a [-]", count * 2.0);
+        runtime.add_or_set("b [-]", count * 3.0);
+
+        assert_eq!(runtime.list_keys(), vec!["a [-]", "b [-]", "c [-]"]);
+
+        let file_path = std::env::temp_dir().join("slippy_filtered.csv");
+        let file_path = file_path.to_str().unwrap();
+        runtime.export_to_csv_filtered(file_path, &["c [-]", "a [-]"]);
+
+        let mut reader = csv::Reader::from_path(file_path).unwrap();
+        assert_eq!(
+            reader.headers().unwrap().iter().collect::<Vec<&str>>(),
+            vec!["a [-]", "c [-]", "time [s]"]
+        );
+        for (i, record) in reader.records().enumerate(){
+            let record = record.unwrap();
+            assert_eq!(record.len(), 3);
+            assert_eq!(record[0].parse::<f64>().unwrap(), i as f64 * 2.0);
+            assert_eq!(record[1].parse::<f64>().unwrap(), i as f64);
+        }
+    }
+
+    #[test]
+    fn channel_registry(){
+        use crate::physics::RigidBody;
+        use crate::sim::Save;
+
+        let mut runtime = Runtime::new(1.0, 0.1, "time [s]");
+        RigidBody::identity().save_data("hopper", &mut runtime);
+        runtime.add_or_set("hopper.tvc.linkage.output_angle [rad]", 0.5);
+        runtime.add_or_set("count", 1.0);
+
+        // 9 vectors, a quaternion and the mass from the rigidbody plus the
+        // linkage
+        assert_eq!(runtime.channels_in_node("hopper").len(), (9 * 3) + 4 + 1 + 1);
+        assert_eq!(runtime.channels().len(), runtime.list_keys().len());
+
+        let radians = runtime.channels_with_unit("rad");
+        assert_eq!(radians.len(), 1);
+        assert_eq!(radians[0].name, "tvc.linkage.output_angle");
+
+        let unitless = runtime.channels_in_node("");
+        assert_eq!(unitless.len(), 1);
+        assert_eq!(unitless[0].unit, "-");
+
+        // Explicit registration
+        let key = runtime.add_channel("guidance", "range", "m");
+        assert_eq!(key, "guidance.range [m]");
+        assert!(runtime.get_value(&key).is_nan());
+        runtime.increment();
+        runtime.add_or_set(&key, 3.0);
+        assert!(runtime.get_series(&key)[0].is_nan());
+        assert_eq!(runtime.get_series(&key)[1], 3.0);
+        assert_eq!(runtime.get_series("count").len(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn channel_unit_mismatch(){
+        let mut runtime = Runtime::new(1.0, 0.1, "time [s]");
+        runtime.add_channel("hopper", "pos.x", "m");
+        runtime.add_or_set("hopper.pos.x [ft]", 1.0);
+    }
+
+    #[test]
+    fn growable(){
+        let key = "count [-]";
+        let mut runtime = Runtime::new_growable(0.5, "time [s]");
+        assert!(runtime.is_growable());
+
+        let initial_len = runtime.x_array.len();
+        let steps = (initial_len * 3) + 7;
+
+        let mut count = 0.0;
+        for _ in 0..steps{
+            runtime.add_or_set(key, count);
+            count += 1.0;
+            runtime.increment();
+            assert!(runtime.is_running);
+        }
+        runtime.add_or_set(key, count);
+
+        assert_eq!(runtime.get_curr_index(), steps);
+        assert!(runtime.x_array.len() > initial_len);
+        assert_eq!(runtime.x_array.len(), runtime.data_dict[key].len());
+        assert_relative_eq!(runtime.get_x(), 0.5 * steps as f64, max_relative = 1e-12);
+
+        // Every step kept its value across the regrowth
+        let data = &runtime.data_dict[key][..=steps];
+        for (i, &value) in data.iter().enumerate(){
+            assert_eq!(value, i as f64);
+        }
+
+        // Exports stop at the current index
+        let file_path = std::env::temp_dir().join("slippy_growable.csv");
+        let file_path = file_path.to_str().unwrap();
+        runtime.export_to_csv(file_path);
+        let reader = csv::Reader::from_path(file_path).unwrap();
+        assert_eq!(reader.into_records().count(), steps + 1);
+    }
+
+    #[test]
+    fn growable_no_drift(){
+        // An increment with no exact binary form, x stays a multiple of it
+        // after several regrowths
+        let mut runtime = Runtime::new_growable(0.1, "time [s]");
+        let steps = GROWABLE_INITIAL_STEPS * 5;
+        for _ in 0..steps{
+            runtime.increment();
+        }
+        for (i, &x) in runtime.x_array.iter().enumerate(){
+            assert_eq!(x, i as f64 * 0.1);
+        }
+        assert_eq!(runtime.get_x(), steps as f64 * 0.1);
+    }
+
+    #[test]
+    fn phase_plane_export(){
+        let mut runtime = Runtime::new(1.0, 0.01, "time [s]");
+
+        // Harmonic oscillator traces a circle
+        while runtime.is_running{
+            let x = runtime.get_x();
+            runtime.add_or_set("rate [rad/s]", -x.sin());
+            runtime.add_or_set("angle [rad]", x.cos());
+            runtime.add_or_set("other [-]", 0.0);
+            runtime.increment();
+        }
+
+        let file_path = std::env::temp_dir().join("slippy_phase_plane.csv");
+        let file_path = file_path.to_str().unwrap();
+        runtime.export_phase_plane("angle [rad]", "rate [rad/s]", file_path);
+
+        let mut reader = csv::Reader::from_path(file_path).unwrap();
+        assert_eq!(
+            reader.headers().unwrap().iter().collect::<Vec<&str>>(),
+            vec!["angle [rad]", "rate [rad/s]"]
+        );
+
+        let records: Vec<csv::StringRecord> = reader.records()
+            .map(|record| record.unwrap())
+            .collect();
+        assert_eq!(records.len(), runtime.get_curr_index() + 1);
+        for (i, record) in records.iter().enumerate(){
+            let angle: f64 = record[0].parse().unwrap();
+            let rate: f64 = record[1].parse().unwrap();
+            assert_eq!(angle, runtime.data_dict["angle [rad]"][i]);
+            assert_eq!(rate, runtime.data_dict["rate [rad/s]"][i]);
+            assert_relative_eq!(angle.powi(2) + rate.powi(2), 1.0, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn phase_plane_unknown_key(){
+        let mut runtime = Runtime::new(1.0, 0.1, "time [s]");
+        runtime.add_or_set("angle [rad]", 0.0);
+        runtime.export_phase_plane("angle [rad]", "missing [-]", "unused.csv");
+    }
+
+    #[test]
+    fn nan_policy(){
+        // Default warns and still stores the value
+        let mut runtime = Runtime::new(1.0, 0.1, "time [s]");
+        runtime.add_or_set("fine [-]", 1.0);
+        runtime.add_or_set("nan [-]", f64::NAN);
+        runtime.add_or_set("inf [-]", f64::INFINITY);
+        assert!(runtime.get_value("nan [-]").is_nan());
+        assert_eq!(runtime.get_warned_keys(), vec!["inf [-]", "nan [-]"]);
+
+        // Ignore
+        let config = RuntimeConfig{
+            nan_policy: NanPolicy::Ignore,
+            inf_policy: InfPolicy::Warn,
+            ..RuntimeConfig::default()
+        };
+        let mut runtime = Runtime::new_with_config(1.0, 0.1, "time [s]", config);
+        runtime.add_or_set("nan [-]", f64::NAN);
+        runtime.add_or_set("inf [-]", f64::NEG_INFINITY);
+        assert!(runtime.get_value("nan [-]").is_nan());
+        assert_eq!(runtime.get_warned_keys(), vec!["inf [-]"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_policy_panic(){
+        let config = RuntimeConfig{
+            nan_policy: NanPolicy::Panic,
+            inf_policy: InfPolicy::Panic,
+            ..RuntimeConfig::default()
+        };
+        let mut runtime = Runtime::new_with_config(1.0, 0.1, "time [s]", config);
+        runtime.add_or_set("nan [-]", f64::NAN);
+    }
+}
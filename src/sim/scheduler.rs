@@ -0,0 +1,172 @@
+// Multi-rate tasks inside one Runtime loop, e.g. physics every step with
+// control and sensors at slower rates
+
+use super::{Runtime, Save};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Task{
+    pub name: String,
+    pub period_s: f64,
+    pub stride: usize,
+    pub is_exact: bool,
+    pub count: usize
+}
+
+#[derive(Debug, Clone)]
+pub struct Scheduler{
+    dt: f64,
+    tasks: Vec<Task>
+}
+
+impl Scheduler{
+    pub fn new(dt: f64) -> Scheduler{
+        // dt is the runtime step the tasks are scheduled against
+        if dt <= 0.0{
+            panic!("    ERROR| Scheduler step [{}] must be positive", dt)
+        }
+        return Scheduler{dt, tasks: Vec::new()}
+    }
+
+    pub fn add_task(&mut self, name: &str, period_s: f64) -> usize{
+        // Returns the task id. Periods are rounded to a whole number of
+        // steps, a period that is not a multiple of dt is flagged and runs at
+        // the rounded period.
+        if period_s < self.dt{
+            panic!(
+                "    ERROR| Task [{}] period [{}] is shorter than the step [{}]",
+                name,
+                period_s,
+                self.dt
+            )
+        }
+        if self.tasks.iter().any(|task| task.name == name){
+            panic!("    ERROR| Task [{}] is already registered", name)
+        }
+
+        let stride = (period_s / self.dt).round() as usize;
+        let is_exact = ((stride as f64 * self.dt) - period_s).abs() <= 1e-9 * period_s;
+        if !is_exact{
+            eprintln!(
+                "    WARNING| Task [{}] period [{}] is not a multiple of the step [{}], running every [{}]",
+                name,
+                period_s,
+                self.dt,
+                stride as f64 * self.dt
+            );
+        }
+
+        self.tasks.push(Task{name: name.to_string(), period_s, stride, is_exact, count: 0});
+        return self.tasks.len() - 1
+    }
+
+    pub fn is_due(&self, task_id: usize, step: usize) -> bool{
+        // Integer step counting so there is no drift over long runs
+        return step % self.tasks[task_id].stride == 0
+    }
+
+    pub fn run<F>(&mut self, runtime: &Runtime, mut task: F)
+        where F: FnMut(usize)
+    {
+        // Calls task with the id of each task due on the current step, in
+        // registration order
+        let step = runtime.get_curr_index();
+        for task_id in 0..self.tasks.len(){
+            if self.is_due(task_id, step){
+                self.tasks[task_id].count += 1;
+                task(task_id);
+            }
+        }
+    }
+
+    pub fn get_task(&self, task_id: usize) -> &Task{
+        return &self.tasks[task_id]
+    }
+
+    pub fn get_count(&self, task_id: usize) -> usize{
+        return self.tasks[task_id].count
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Data recording
+// ----------------------------------------------------------------------------
+
+impl Save for Scheduler{
+    fn save_data(&self, node_name: &str, runtime: &mut Runtime) where Self: Sized {
+        for task in self.tasks.iter(){
+            runtime.add_or_set(format!(
+                "{node_name}.{}.count [-]", task.name).as_str(),
+                task.count as f64
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_run_count(){
+        let dt = 1e-4;
+        let mut runtime = Runtime::new(100.0, dt, "time [s]");
+        let mut scheduler = Scheduler::new(dt);
+        let task = scheduler.add_task("control", 0.0033);
+        assert!(scheduler.get_task(task).is_exact);
+        assert_eq!(scheduler.get_task(task).stride, 33);
+
+        // Over a million steps the count is still exact
+        let mut steps: usize = 0;
+        while runtime.is_running{
+            scheduler.run(&runtime, |_| ());
+            steps += 1;
+            runtime.increment();
+        }
+        // Due on steps 0, 33, ..., 999_999 = 33 * 30_303
+        assert_eq!(steps, 1_000_000);
+        assert_eq!(scheduler.get_count(task), 30_304);
+    }
+
+    #[test]
+    fn inexact_period(){
+        let mut scheduler = Scheduler::new(1e-3);
+        let exact = scheduler.add_task("control", 5e-3);
+        let inexact = scheduler.add_task("sensor", 2.5e-3);
+        assert!(scheduler.get_task(exact).is_exact);
+        assert!(!scheduler.get_task(inexact).is_exact);
+        assert_eq!(scheduler.get_task(inexact).stride, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn period_shorter_than_step(){
+        Scheduler::new(1e-3).add_task("fast", 1e-4);
+    }
+
+    #[test]
+    fn registration_order(){
+        let dt = 1e-3;
+        let mut runtime = Runtime::new(0.02, dt, "time [s]");
+        let mut scheduler = Scheduler::new(dt);
+        let physics = scheduler.add_task("physics", 1e-3);
+        let sensors = scheduler.add_task("sensors", 1e-2);
+        let control = scheduler.add_task("control", 5e-3);
+
+        let mut order: Vec<(usize, usize)> = Vec::new();
+        for _ in 0..10{
+            let step = runtime.get_curr_index();
+            scheduler.run(&runtime, |task_id| order.push((step, task_id)));
+            runtime.increment();
+        }
+
+        assert_eq!(&order[..3], &[(0, physics), (0, sensors), (0, control)]);
+        assert_eq!(order[3], (1, physics));
+        assert_eq!(order.iter().filter(|(step, _)| *step == 5).map(|(_, id)| *id).collect::<Vec<usize>>(), vec![physics, control]);
+        assert_eq!(scheduler.get_count(physics), 10);
+        assert_eq!(scheduler.get_count(sensors), 1);
+        assert_eq!(scheduler.get_count(control), 2);
+
+        scheduler.save_data("scheduler", &mut runtime);
+        assert_eq!(runtime.get_value("scheduler.control.count [-]"), 2.0);
+    }
+}
@@ -1,5 +1,7 @@
 use approx::assert_relative_eq;
 
+use crate::geo::{Vector3, Matrix3x3, Quaternion};
+
 const FLOAT_DEV: f64  = 1e-3;
 
 pub fn almost_equal_array(array1: &[f64], array2: &[f64]){
@@ -16,4 +18,46 @@ pub fn almost_equal_array(array1: &[f64], array2: &[f64]){
             max_relative=FLOAT_DEV
         )
     }
-}
\ No newline at end of file
+}
+
+pub fn assert_convergence_order<F>(method: F, expected_order: f64, tol: f64)
+    where F: Fn(f64) -> f64
+{
+    // method(dt) returns the absolute error against a known solution, each
+    // halving of dt should shrink the error by 2^order
+    let dts = [0.2, 0.1, 0.05, 0.025];
+    let errors: Vec<f64> = dts.iter().map(|&dt| method(dt)).collect();
+
+    let observed_orders: Vec<f64> = errors.windows(2)
+        .map(|pair| (pair[0] / pair[1]).ln() / 2.0_f64.ln())
+        .collect();
+
+    for (i, observed_order) in observed_orders.iter().enumerate(){
+        assert!(
+            (observed_order - expected_order).abs() <= tol,
+            "ERROR| Observed order [{}] for dt [{}] -> [{}] is not within [{}] of [{}], all orders {:?}",
+            observed_order,
+            dts[i],
+            dts[i + 1],
+            tol,
+            expected_order,
+            observed_orders
+        );
+    }
+}
+
+pub fn assert_transform_agree(quat: Quaternion, matrix: Matrix3x3, vec: Vector3, tol: f64){
+    // Rotating through the quaternion and the matrix should match when the
+    // matrix is the quaternion's DCM
+    let by_quat = quat.transform(vec);
+    let by_matrix = matrix.transform(vec);
+
+    assert!(
+        (by_quat - by_matrix).norm() <= tol,
+        "ERROR| Transform of {:?} differs, quaternion {:?} gives {:?}, matrix gives {:?}",
+        vec,
+        quat,
+        by_quat,
+        by_matrix
+    );
+}
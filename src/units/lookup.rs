@@ -0,0 +1,286 @@
+use std::path::Path;
+use csv;
+
+#[derive(Debug)]
+pub enum LookupError{
+    Csv(csv::Error),
+    BadValue(String),
+    BadShape(String)
+}
+
+impl std::fmt::Display for LookupError{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result{
+        match self{
+            LookupError::Csv(err) => write!(f, "ERROR| Csv error: {}", err),
+            LookupError::BadValue(value) => write!(f, "ERROR| Could not parse [{}]", value),
+            LookupError::BadShape(msg) => write!(f, "ERROR| Bad table shape: {}", msg)
+        }
+    }
+}
+
+impl From<csv::Error> for LookupError{
+    fn from(err: csv::Error) -> LookupError{
+        return LookupError::Csv(err)
+    }
+}
+
+fn parse_cell(cell: &str) -> Result<f64, LookupError>{
+    return cell.trim().parse::<f64>()
+        .map_err(|_| LookupError::BadValue(cell.to_string()))
+}
+
+fn check_axis(axis: &[f64], name: &str) -> Result<(), LookupError>{
+    // Axes must be non-empty and strictly increasing
+    if axis.is_empty(){
+        return Err(LookupError::BadShape(format!("{} axis is empty", name)))
+    }
+    if axis.windows(2).any(|pair| pair[1] <= pair[0]){
+        return Err(LookupError::BadShape(format!("{} axis is not strictly increasing", name)))
+    }
+    return Ok(())
+}
+
+fn bracket(axis: &[f64], x: f64) -> (usize, f64){
+    // Lower index and fraction to the next node, clamped to the ends
+    let last = axis.len() - 1;
+    if last == 0 || x <= axis[0]{
+        return (0, 0.0)
+    }
+    if x >= axis[last]{
+        return (last - 1, 1.0)
+    }
+
+    let upper = axis.partition_point(|&node| node <= x);
+    let lower = upper - 1;
+    return (lower, (x - axis[lower]) / (axis[upper] - axis[lower]))
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64{
+    // Exact at both nodes
+    return (a * (1.0 - t)) + (b * t)
+}
+
+// ----------------------------------------------------------------------------
+// 1D
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lookup1D{
+    x: Vec<f64>,
+    y: Vec<f64>
+}
+
+impl Lookup1D{
+    pub fn new(x: Vec<f64>, y: Vec<f64>) -> Result<Lookup1D, LookupError>{
+        check_axis(&x, "x")?;
+        if x.len() != y.len(){
+            return Err(LookupError::BadShape(format!(
+                "x has {} points but y has {}", x.len(), y.len()
+            )))
+        }
+        return Ok(Lookup1D{x, y})
+    }
+
+    pub fn from_csv(file_path: &str) -> Result<Lookup1D, LookupError>{
+        // Two columns with a header row, x then y
+        let mut reader = csv::Reader::from_path(Path::new(file_path))?;
+
+        let mut x = Vec::new();
+        let mut y = Vec::new();
+        for record in reader.records(){
+            let record = record?;
+            if record.len() != 2{
+                return Err(LookupError::BadShape(format!(
+                    "expected 2 columns, found {}", record.len()
+                )))
+            }
+            x.push(parse_cell(&record[0])?);
+            y.push(parse_cell(&record[1])?);
+        }
+
+        return Lookup1D::new(x, y)
+    }
+
+    pub fn sample(&self, x: f64) -> f64{
+        // Linear interpolation, clamped outside of the table
+        if self.x.len() == 1{
+            return self.y[0]
+        }
+        let (i, t) = bracket(&self.x, x);
+        return lerp(self.y[i], self.y[i + 1], t)
+    }
+
+    pub fn get_x(&self) -> &[f64]{
+        return &self.x
+    }
+
+    pub fn get_y(&self) -> &[f64]{
+        return &self.y
+    }
+
+    pub fn integrate(&self) -> f64{
+        // Trapezoidal area under the table
+        return self.x.windows(2)
+            .zip(self.y.windows(2))
+            .map(|(x, y)| (x[1] - x[0]) * (y[0] + y[1]) / 2.0)
+            .sum()
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 2D
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lookup2D{
+    x: Vec<f64>,
+    y: Vec<f64>,
+    z: Vec<Vec<f64>>
+}
+
+impl Lookup2D{
+    pub fn new(x: Vec<f64>, y: Vec<f64>, z: Vec<Vec<f64>>) -> Result<Lookup2D, LookupError>{
+        // z is indexed as z[y][x]
+        check_axis(&x, "x")?;
+        check_axis(&y, "y")?;
+        if z.len() != y.len() || z.iter().any(|row| row.len() != x.len()){
+            return Err(LookupError::BadShape(format!(
+                "z must be {} rows of {} columns", y.len(), x.len()
+            )))
+        }
+        return Ok(Lookup2D{x, y, z})
+    }
+
+    pub fn from_csv(file_path: &str) -> Result<Lookup2D, LookupError>{
+        // Grid layout, the header holds the x axis after a corner cell and
+        // each row starts with its y value
+        let mut reader = csv::Reader::from_path(Path::new(file_path))?;
+
+        let x = reader.headers()?.iter()
+            .skip(1)
+            .map(parse_cell)
+            .collect::<Result<Vec<f64>, LookupError>>()?;
+
+        let mut y = Vec::new();
+        let mut z = Vec::new();
+        for record in reader.records(){
+            let record = record?;
+            let mut cells = record.iter();
+            match cells.next(){
+                Some(cell) => y.push(parse_cell(cell)?),
+                None => continue
+            }
+            z.push(cells.map(parse_cell).collect::<Result<Vec<f64>, LookupError>>()?);
+        }
+
+        return Lookup2D::new(x, y, z)
+    }
+
+    pub fn sample(&self, x: f64, y: f64) -> f64{
+        // Bilinear interpolation, clamped outside of the table
+        let (i, tx) = bracket(&self.x, x);
+        let (j, ty) = bracket(&self.y, y);
+        let i_next = (i + 1).min(self.x.len() - 1);
+        let j_next = (j + 1).min(self.y.len() - 1);
+
+        let lower = lerp(self.z[j][i], self.z[j][i_next], tx);
+        let upper = lerp(self.z[j_next][i], self.z[j_next][i_next], tx);
+        return lerp(lower, upper, ty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn write_temp(name: &str, contents: &str) -> String{
+        let file_path = std::env::temp_dir().join(name);
+        std::fs::write(&file_path, contents).unwrap();
+        return file_path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn lookup_1d(){
+        let table = Lookup1D::new(
+            vec![0.0, 1.0, 3.0],
+            vec![10.0, 20.0, 0.0]
+        ).unwrap();
+
+        // Nodes
+        assert_eq!(table.sample(0.0), 10.0);
+        assert_eq!(table.sample(1.0), 20.0);
+        assert_eq!(table.sample(3.0), 0.0);
+
+        // Midpoints
+        assert_relative_eq!(table.sample(0.5), 15.0);
+        assert_relative_eq!(table.sample(2.0), 10.0);
+
+        // Clamped
+        assert_eq!(table.sample(-1.0), 10.0);
+        assert_eq!(table.sample(10.0), 0.0);
+
+        // Bad tables
+        assert!(Lookup1D::new(vec![0.0, 0.0], vec![1.0, 2.0]).is_err());
+        assert!(Lookup1D::new(vec![0.0, 1.0], vec![1.0]).is_err());
+    }
+
+    #[test]
+    fn lookup_1d_csv(){
+        let file_path = write_temp(
+            "slippy_lookup_1d.csv",
+            "time [s],thrust [N]\n0.0,0.0\n0.5,10.0\n1.0,0.0\n"
+        );
+        let table = Lookup1D::from_csv(&file_path).unwrap();
+        assert_eq!(table.sample(0.5), 10.0);
+        assert_relative_eq!(table.sample(0.75), 5.0);
+
+        // Malformed
+        let file_path = write_temp(
+            "slippy_lookup_1d_bad.csv",
+            "time [s],thrust [N]\n0.0,0.0\n0.5,ten\n"
+        );
+        assert!(matches!(
+            Lookup1D::from_csv(&file_path),
+            Err(LookupError::BadValue(_))
+        ));
+    }
+
+    #[test]
+    fn lookup_2d(){
+        let table = Lookup2D::new(
+            vec![0.0, 1.0, 2.0],
+            vec![0.0, 10.0],
+            vec![
+                vec![0.0, 1.0, 2.0],
+                vec![10.0, 11.0, 12.0]
+            ]
+        ).unwrap();
+
+        // Nodes
+        assert_eq!(table.sample(1.0, 0.0), 1.0);
+        assert_eq!(table.sample(2.0, 10.0), 12.0);
+
+        // Midpoints
+        assert_relative_eq!(table.sample(0.5, 5.0), 5.5);
+        assert_relative_eq!(table.sample(1.5, 2.5), 4.0);
+
+        // Clamped
+        assert_eq!(table.sample(-1.0, -1.0), 0.0);
+        assert_eq!(table.sample(5.0, 50.0), 12.0);
+
+        // From csv
+        let file_path = write_temp(
+            "slippy_lookup_2d.csv",
+            "mach,0.0,1.0,2.0\n0.0,0.0,1.0,2.0\n10.0,10.0,11.0,12.0\n"
+        );
+        assert_eq!(Lookup2D::from_csv(&file_path).unwrap(), table);
+
+        // Ragged
+        let file_path = write_temp(
+            "slippy_lookup_2d_bad.csv",
+            "mach,0.0,1.0\n0.0,0.0,1.0\n10.0,10.0\n"
+        );
+        assert!(Lookup2D::from_csv(&file_path).is_err());
+    }
+}
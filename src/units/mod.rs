@@ -1,9 +1,23 @@
 use std::f64::consts::PI;
 
+pub mod lookup;
+pub use lookup::{Lookup1D, Lookup2D, LookupError};
+
 pub fn deg_to_rad(x: f64) -> f64{
     return   x * PI / 180.0
 }
 
 pub fn rad_to_deg(x: f64) -> f64{
     return   x * 180.0 / PI
+}
+
+// Standard gravity, used for g units and the ISA atmosphere
+pub const STANDARD_GRAVITY_MPS2: f64 = 9.80665;
+
+pub fn mps2_to_g(x: f64) -> f64{
+    return x / STANDARD_GRAVITY_MPS2
+}
+
+pub fn g_to_mps2(x: f64) -> f64{
+    return x * STANDARD_GRAVITY_MPS2
 }
\ No newline at end of file